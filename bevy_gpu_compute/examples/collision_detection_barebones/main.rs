@@ -60,7 +60,7 @@ mod collision_detection_module {
     fn calculate_distance_squared(p1: Vec2F32, p2: Vec2F32) -> f32 {
         let dx = p1.x - p2[0];
         let dy = p1.y - p2[1];
-        return dx * dx + dy * dy;
+        dx * dx + dy * dy
     }
     fn main(iter_pos: WgslIterationPosition) {
         let current_entity = iter_pos.x;
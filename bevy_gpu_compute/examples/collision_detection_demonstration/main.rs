@@ -85,14 +85,14 @@ mod collision_detection_module {
     fn calculate_distance_squared(p1: Vec2F32, p2: Vec2F32) -> f32 {
         let dx = p1.x - p2[0];
         let dy = p1.y - p2[1];
-        return dx * dx + dy * dy;
+        dx * dx + dy * dy
     }
     fn main(iter_pos: WgslIterationPosition) {
         let current_entity = iter_pos.x;
         let other_entity = iter_pos.y;
         // Early exit conditions
-        let out_of_bounds = current_entity >= WgslVecInput::vec_len::<Position>() as u32
-            || other_entity >= WgslVecInput::vec_len::<Position>() as u32;
+        let out_of_bounds = current_entity >= WgslVecInput::vec_len::<Position>()
+            || other_entity >= WgslVecInput::vec_len::<Position>();
         if out_of_bounds || current_entity == other_entity || current_entity >= other_entity {
             return;
         }
@@ -109,17 +109,17 @@ mod collision_detection_module {
             * MY_CONST as f32;
         let rad_sum_sq = radius_sum * radius_sum;
         // index = y * width + x
-        let debug_index = other_entity * WgslVecInput::vec_len::<Radius>() as u32 + current_entity;
+        let debug_index = other_entity * WgslVecInput::vec_len::<Radius>() + current_entity;
         let is_collision = dist_squared < rad_sum_sq;
         WgslOutput::set::<MyDebugInfo>(
             debug_index,
             MyDebugInfo {
                 entity1: current_entity,
                 entity2: other_entity,
-                counter_value: WgslOutput::len::<CollisionResult>() as u32,
+                counter_value: WgslOutput::len::<CollisionResult>(),
                 is_collision: is_collision as i32,
-                dist_squared: dist_squared,
-                rad_sum_sq: rad_sum_sq,
+                dist_squared,
+                rad_sum_sq,
                 rad_mult: WgslConfigInput::get::<Config>().radius_multiplier,
             },
         );
@@ -174,7 +174,7 @@ fn modify_task(mut gpu_tasks: GpuTaskRunner, state: Res<State>) {
     gpu_tasks.run_commands(pending_commands);
 }
 fn modify_task_config_inputs(mut count: Local<u32>, mut gpu_tasks: GpuTaskRunner) {
-    let radius_multiplier = if *count <= 0 {
+    let radius_multiplier = if *count == 0 {
         RADIUS_MULTIPLIER_FRAME_0
     } else if *count == 1 {
         RADIUS_MULTIPLIER_FRAME_1
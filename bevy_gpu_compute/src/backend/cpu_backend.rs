@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use bevy_gpu_compute_core::{
+    CpuSchedule, IterSpaceDimmension, IterationSpace, MaxOutputLengths, TypeErasedArrayInputData,
+    TypeErasedArrayOutputData, TypeErasedConfigInputData,
+    wgsl::shader_module::complete_shader_module::WgslShaderModule,
+};
+
+use crate::task::{
+    lib::GpuComputeTask,
+    outputs::output_future::PendingOutputs,
+    task_components::{
+        configuration::{lib::TaskConfiguration, wgsl_code::WgslCode},
+        runtime_state::lib::{TaskRuntimeState, TaskRuntimeStateBuilder},
+    },
+};
+
+use super::{ComputeBackend, ComputeBackendKind};
+
+/// The `run_entry_points_on_cpu` function generated by `#[wgsl_shader_module]`
+pub type CpuRunner = fn(
+    &[String],
+    IterationSpace,
+    &TypeErasedConfigInputData,
+    &TypeErasedArrayInputData,
+    &MaxOutputLengths,
+    &CpuSchedule,
+) -> TypeErasedArrayOutputData;
+
+/// Runs tasks with the rust version of their shader module. There are no buffers to keep up to date, everything happens in `run`.
+pub struct CpuBackend;
+
+impl ComputeBackend for CpuBackend {
+    fn kind(&self) -> ComputeBackendKind {
+        ComputeBackendKind::Cpu
+    }
+    fn create_wgsl_code(
+        &self,
+        _label: &str,
+        wgsl_shader_module: &WgslShaderModule,
+        iter_space_dimmensions: IterSpaceDimmension,
+    ) -> WgslCode {
+        WgslCode::from_shader_module_for_cpu(wgsl_shader_module, iter_space_dimmensions)
+    }
+    fn create_runtime_state(
+        &self,
+        task_name: &str,
+        configuration: &TaskConfiguration,
+    ) -> TaskRuntimeState {
+        TaskRuntimeStateBuilder::new(None, task_name, configuration).build()
+    }
+    fn on_config_inputs_set(&self, _task: &mut GpuComputeTask) {}
+    fn on_inputs_set(&self, _task: &mut GpuComputeTask, _lengths_changed: bool) {}
+    fn on_mutate(&self, _task: &mut GpuComputeTask) {}
+    fn run_async(&self, task: &mut GpuComputeTask) -> PendingOutputs {
+        let entry_point = task
+            .configuration()
+            .shader()
+            .entry_point_function_name()
+            .to_string();
+        PendingOutputs::Ready(run_on_cpu(task, &[entry_point]))
+    }
+    fn receive_outputs(&self, _task: &mut GpuComputeTask) {}
+    fn run(&self, task: &mut GpuComputeTask, entry_points: &[String]) {
+        let output = run_on_cpu(task, entry_points);
+        task.current_data_mut().set_output(output);
+    }
+}
+
+/// the outputs of running the entry points with the task's `CpuRunner`, which are not stored in the task data
+fn run_on_cpu(task: &GpuComputeTask, entry_points: &[String]) -> TypeErasedArrayOutputData {
+    let cpu_runner = task.configuration().cpu_runner().unwrap_or_else(|| {
+        panic!(
+            "Task {} has no rust version of its shader, so it cannot run on the CPU",
+            task.name()
+        )
+    });
+    let no_config_inputs = TypeErasedConfigInputData::new(HashMap::new());
+    let config_inputs = task
+        .current_data()
+        .config_input()
+        .as_ref()
+        .unwrap_or(&no_config_inputs);
+    let Some(inputs) = task.current_data().input().as_ref() else {
+        panic!("Input has not been set for task {}", task.name());
+    };
+    // the generated runner expects every input to be set, so check here and name the task, like `create_bind_group` does on the GPU
+    for config in task.configuration().inputs().configs() {
+        if config_inputs.get_bytes(config.name.name()).is_none() {
+            panic!(
+                "Config input {} has not been set for task {}",
+                config.name.name(),
+                task.name()
+            );
+        }
+    }
+    for array in task.configuration().inputs().arrays() {
+        if inputs.get_bytes(array.name.name()).is_none() {
+            panic!(
+                "Input {} has not been set for task {}",
+                array.name.name(),
+                task.name()
+            );
+        }
+    }
+    cpu_runner(
+        entry_points,
+        *task.configuration().iteration_space(),
+        config_inputs,
+        inputs,
+        task.configuration().outputs().max_lengths(),
+        &CpuSchedule::default(),
+    )
+}
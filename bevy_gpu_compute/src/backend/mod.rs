@@ -0,0 +1,79 @@
+pub mod cpu_backend;
+pub mod wgpu_backend;
+
+use std::sync::Arc;
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Resource;
+use bevy_gpu_compute_core::{
+    IterSpaceDimmension, wgsl::shader_module::complete_shader_module::WgslShaderModule,
+};
+
+use crate::task::{
+    lib::GpuComputeTask,
+    outputs::output_future::PendingOutputs,
+    task_components::{
+        configuration::{lib::TaskConfiguration, wgsl_code::WgslCode},
+        runtime_state::lib::TaskRuntimeState,
+    },
+};
+
+/// The backends that tasks can run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComputeBackendKind {
+    /// Runs tasks on the GPU. Requires a GPU adapter, and in a bevy app also bevy's `RenderPlugin`.
+    Wgpu,
+    /// Runs the rust version of the shader module that `#[wgsl_shader_module]` generates, see `run_on_cpu`. Needs no GPU, so it also works in headless apps and on CI machines.
+    Cpu,
+}
+
+/**
+Everything that `GpuTaskRunner` and `BevyGpuComputeTaskCreator` do with a task that depends on where it runs.
+
+The task data (inputs, outputs, configuration) is kept up to date by the system params, the backend is only told when it changed, so that it can, for example, update its buffers.
+*/
+pub trait ComputeBackend: Send + Sync {
+    fn kind(&self) -> ComputeBackendKind;
+    fn create_wgsl_code(
+        &self,
+        label: &str,
+        wgsl_shader_module: &WgslShaderModule,
+        iter_space_dimmensions: IterSpaceDimmension,
+    ) -> WgslCode;
+    fn create_runtime_state(
+        &self,
+        task_name: &str,
+        configuration: &TaskConfiguration,
+    ) -> TaskRuntimeState;
+    fn on_config_inputs_set(&self, task: &mut GpuComputeTask);
+    fn on_inputs_set(&self, task: &mut GpuComputeTask, lengths_changed: bool);
+    /// called after the iteration space or the max output lengths changed
+    fn on_mutate(&self, task: &mut GpuComputeTask);
+    /// Runs the entry points one after another over the same inputs and outputs, and stores the outputs in the task data, or starts reading them back if the task uses `ReadbackMode::Async`
+    fn run(&self, task: &mut GpuComputeTask, entry_points: &[String]);
+    /// Runs the default entry point like `run`, but hands the outputs to the caller instead of storing them in the task data, without waiting for the GPU
+    fn run_async(&self, task: &mut GpuComputeTask) -> PendingOutputs;
+    /// Stores the outputs of asynchronous runs that have arrived in the task data, without waiting for the others
+    fn receive_outputs(&self, task: &mut GpuComputeTask);
+}
+
+/// The backend that all tasks run on, chosen by `BevyGpuComputePlugin`, or passed to `GpuComputeContext::from_backend`
+#[derive(Clone)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct ActiveComputeBackend {
+    backend: Arc<dyn ComputeBackend>,
+}
+
+impl ActiveComputeBackend {
+    pub fn new(backend: impl ComputeBackend + 'static) -> Self {
+        ActiveComputeBackend {
+            backend: Arc::new(backend),
+        }
+    }
+    pub fn kind(&self) -> ComputeBackendKind {
+        self.backend.kind()
+    }
+    pub fn get(&self) -> &dyn ComputeBackend {
+        self.backend.as_ref()
+    }
+}
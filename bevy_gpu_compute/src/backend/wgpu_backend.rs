@@ -0,0 +1,222 @@
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{OnceLock, mpsc};
+
+#[cfg(feature = "bevy")]
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy_gpu_compute_core::{
+    IterSpaceDimmension, wgsl::shader_module::complete_shader_module::WgslShaderModule,
+};
+
+use crate::task::{
+    buffers::{
+        create_config_input_buffers::update_config_input_buffers,
+        create_input_buffers::update_input_buffers, create_output_buffers::update_output_buffers,
+    },
+    compute_pipeline::update_on_pipeline_const_change::update_compute_pipeline,
+    dispatch::{create_bind_group::create_bind_group, dispatch_to_gpu::dispatch_to_gpu},
+    lib::GpuComputeTask,
+    outputs::{
+        async_readback::{receive_readbacks, start_readback, submit_readback},
+        output_future::PendingOutputs,
+        read_gpu_output_counts::read_gpu_output_counts,
+        read_gpu_task_outputs::read_gpu_outputs,
+    },
+    task_components::{
+        configuration::{lib::TaskConfiguration, readback_mode::ReadbackMode, wgsl_code::WgslCode},
+        runtime_state::lib::{TaskRuntimeState, TaskRuntimeStateBuilder},
+    },
+};
+
+use pollster::FutureExt;
+use wgpu::{Device, Queue, SubmissionIndex};
+
+use super::{ComputeBackend, ComputeBackendKind};
+
+/// Where the backend gets its device and queue from, so that it can share them with bevy's renderer, or own them
+trait DeviceAndQueue: Send + Sync {
+    fn device(&self) -> &Device;
+    fn queue(&self) -> &Queue;
+}
+
+impl DeviceAndQueue for (Device, Queue) {
+    fn device(&self) -> &Device {
+        &self.0
+    }
+    fn queue(&self) -> &Queue {
+        &self.1
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl DeviceAndQueue for (RenderDevice, RenderQueue) {
+    fn device(&self) -> &Device {
+        self.0.wgpu_device()
+    }
+    fn queue(&self) -> &Queue {
+        &self.1
+    }
+}
+
+/// Runs tasks on the GPU, with a plain wgpu device and queue, or the ones that bevy's `RenderPlugin` created
+pub struct WgpuBackend {
+    device_and_queue: Arc<dyn DeviceAndQueue>,
+    /// sends the submissions of `run_async` to the thread that polls the device for them, started on the first `run_async`
+    #[cfg(not(target_arch = "wasm32"))]
+    poller: OnceLock<mpsc::Sender<SubmissionIndex>>,
+}
+
+impl WgpuBackend {
+    pub fn new(device: Device, queue: Queue) -> Self {
+        WgpuBackend {
+            device_and_queue: Arc::new((device, queue)),
+            #[cfg(not(target_arch = "wasm32"))]
+            poller: OnceLock::new(),
+        }
+    }
+    #[cfg(feature = "bevy")]
+    pub fn from_render_device(render_device: RenderDevice, render_queue: RenderQueue) -> Self {
+        WgpuBackend {
+            device_and_queue: Arc::new((render_device, render_queue)),
+            #[cfg(not(target_arch = "wasm32"))]
+            poller: OnceLock::new(),
+        }
+    }
+    pub(crate) fn device(&self) -> &Device {
+        self.device_and_queue.device()
+    }
+    fn queue(&self) -> &Queue {
+        self.device_and_queue.queue()
+    }
+    /**
+    The map callbacks of a readback only run when the device is polled, and outside of a bevy app nothing else polls it, so one thread per backend waits for each submission in turn. It stops when the backend is dropped.
+    On wasm32 there are no threads, and the browser maps buffers without polling.
+    */
+    fn poll_until_done(&self, submission_index: SubmissionIndex) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let sender = self.poller.get_or_init(|| {
+                let (sender, receiver) = mpsc::channel::<SubmissionIndex>();
+                let device_and_queue = self.device_and_queue.clone();
+                std::thread::spawn(move || {
+                    for submission_index in receiver {
+                        device_and_queue
+                            .device()
+                            .poll(wgpu::Maintain::WaitForSubmissionIndex(submission_index));
+                    }
+                });
+                sender
+            });
+            sender
+                .send(submission_index)
+                .expect("The device polling thread stopped");
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = submission_index;
+    }
+    /// Creates its own device, without bevy's `RenderPlugin` or a window. Prefers a hardware adapter, and uses a software (fallback) adapter if there is none. Returns `None` if wgpu finds no adapter at all.
+    pub fn headless() -> Option<Self> {
+        let instance = wgpu::Instance::default();
+        let adapter = [false, true]
+            .into_iter()
+            .find_map(|force_fallback_adapter| {
+                instance
+                    .request_adapter(&wgpu::RequestAdapterOptions {
+                        force_fallback_adapter,
+                        ..Default::default()
+                    })
+                    .block_on()
+            })?;
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("bevy_gpu_compute headless device"),
+                    required_features: adapter.features(),
+                    required_limits: adapter.limits(),
+                    memory_hints: wgpu::MemoryHints::default(),
+                },
+                None,
+            )
+            .block_on()
+            .ok()?;
+        Some(WgpuBackend::new(device, queue))
+    }
+}
+
+impl ComputeBackend for WgpuBackend {
+    fn kind(&self) -> ComputeBackendKind {
+        ComputeBackendKind::Wgpu
+    }
+    fn create_wgsl_code(
+        &self,
+        label: &str,
+        wgsl_shader_module: &WgslShaderModule,
+        iter_space_dimmensions: IterSpaceDimmension,
+    ) -> WgslCode {
+        WgslCode::from_shader_module(
+            label,
+            self.device(),
+            wgsl_shader_module,
+            iter_space_dimmensions,
+        )
+    }
+    fn create_runtime_state(
+        &self,
+        task_name: &str,
+        configuration: &TaskConfiguration,
+    ) -> TaskRuntimeState {
+        TaskRuntimeStateBuilder::new(Some(self.device()), task_name, configuration).build()
+    }
+    fn on_config_inputs_set(&self, task: &mut GpuComputeTask) {
+        update_config_input_buffers(task, self.device());
+        // the bind group still points at the old config buffers
+        if task.current_data().input().is_some() {
+            create_bind_group(task, self.device());
+        }
+    }
+    fn on_inputs_set(&self, task: &mut GpuComputeTask, lengths_changed: bool) {
+        if lengths_changed {
+            update_compute_pipeline(task, self.device());
+        }
+        update_input_buffers(task, self.device());
+        create_bind_group(task, self.device());
+    }
+    fn on_mutate(&self, task: &mut GpuComputeTask) {
+        update_compute_pipeline(task, self.device());
+        update_output_buffers(task, self.device());
+        // the bind group still points at the old output buffers
+        if task.current_data().input().is_some() {
+            create_bind_group(task, self.device());
+        }
+    }
+    fn run(&self, task: &mut GpuComputeTask, entry_points: &[String]) {
+        match task.configuration().readback_mode() {
+            ReadbackMode::Blocking => {
+                // the outputs of earlier asynchronous runs must not replace the ones of this run later
+                receive_readbacks(task, self.device(), 0);
+                dispatch_to_gpu(task, self.device(), self.queue(), entry_points);
+                let output_counts = read_gpu_output_counts(task, self.device(), self.queue());
+                read_gpu_outputs(output_counts, task, self.device(), self.queue());
+            }
+            ReadbackMode::Async { max_in_flight } => {
+                receive_readbacks(task, self.device(), max_in_flight - 1);
+                dispatch_to_gpu(task, self.device(), self.queue(), entry_points);
+                start_readback(task, self.device(), self.queue());
+            }
+        }
+    }
+    fn run_async(&self, task: &mut GpuComputeTask) -> PendingOutputs {
+        let entry_point = task
+            .configuration()
+            .shader()
+            .entry_point_function_name()
+            .to_string();
+        dispatch_to_gpu(task, self.device(), self.queue(), &[entry_point]);
+        let readback = submit_readback(task, self.device(), self.queue());
+        self.poll_until_done(readback.submission_index().clone());
+        PendingOutputs::Readback(readback)
+    }
+    fn receive_outputs(&self, task: &mut GpuComputeTask) {
+        receive_readbacks(task, self.device(), usize::MAX);
+    }
+}
@@ -0,0 +1,197 @@
+/*!
+Runs tasks on a plain wgpu device, without a bevy app:
+```ignore
+let mut context = GpuComputeContext::new(device, queue);
+context.create_task::<my_shader::Types>(
+    "my_task",
+    my_shader::parsed(),
+    IterationSpace::new(64, 1, 1),
+    my_shader::MaxOutputLengthsBuilder::new().set_result(64).finish(),
+);
+context.set_inputs("my_task", my_shader::InputDataBuilder::new().set_value(values).into());
+context.run("my_task");
+let results = context.read::<my_shader::OutputDataBuilder>("my_task");
+```
+The bevy plugin and system params do the same with tasks stored as components.
+*/
+use std::collections::HashMap;
+
+use bevy_gpu_compute_core::{
+    MaxOutputLengths, OutputDataBuilderTrait, TypeErasedArrayInputData, TypeErasedConfigInputData,
+    TypesSpec, wgsl::shader_module::user_defined_portion::WgslShaderModuleUserPortion,
+};
+use wgpu::{Device, Queue};
+
+use crate::{
+    backend::{ActiveComputeBackend, cpu_backend::CpuBackend, wgpu_backend::WgpuBackend},
+    ram_limit::RamLimit,
+    task::{
+        commands::{GpuTaskCommand, assert_entry_points_exist},
+        lib::GpuComputeTask,
+        outputs::output_future::{PendingOutputs, TaskOutputFuture},
+        task_components::configuration::{
+            iteration_space::IterationSpace, readback_mode::ReadbackMode,
+        },
+        verify_enough_memory::{check_have_enough_memory, verify_have_enough_memory},
+    },
+};
+
+/// Owns a set of tasks, and the backend they run on
+pub struct GpuComputeContext {
+    backend: ActiveComputeBackend,
+    tasks: HashMap<String, GpuComputeTask>,
+    ram_limit: Option<RamLimit>,
+}
+
+impl GpuComputeContext {
+    /// Runs tasks on the given wgpu device
+    pub fn new(device: Device, queue: Queue) -> Self {
+        Self::from_backend(ActiveComputeBackend::new(WgpuBackend::new(device, queue)))
+    }
+    /// Creates its own wgpu device, see `WgpuBackend::headless`. Returns `None` if wgpu finds no adapter.
+    pub fn headless() -> Option<Self> {
+        WgpuBackend::headless()
+            .map(|backend| Self::from_backend(ActiveComputeBackend::new(backend)))
+    }
+    /// Runs tasks with the rust version of their shader module, see `ComputeBackendKind::Cpu`
+    pub fn cpu() -> Self {
+        Self::from_backend(ActiveComputeBackend::new(CpuBackend))
+    }
+    /// Runs tasks on the same backend as another context or app, for example the `ActiveComputeBackend` resource
+    pub fn from_backend(backend: ActiveComputeBackend) -> Self {
+        GpuComputeContext {
+            backend,
+            tasks: HashMap::new(),
+            ram_limit: None,
+        }
+    }
+    pub fn backend(&self) -> &ActiveComputeBackend {
+        &self.backend
+    }
+
+    /**
+    Creates a task that can be run until it is deleted. Replaces any task with the same name.
+    `ShaderModuleTypes` and `wgsl_shader_module` come from the `#[wgsl_shader_module]` module, like `my_shader::Types` and `my_shader::parsed()`.
+    */
+    pub fn create_task<ShaderModuleTypes: TypesSpec>(
+        &mut self,
+        name: &str,
+        wgsl_shader_module: WgslShaderModuleUserPortion,
+        iteration_space: IterationSpace,
+        max_output_vector_lengths: MaxOutputLengths,
+    ) {
+        let task = GpuComputeTask::from_shader::<ShaderModuleTypes>(
+            name,
+            self.backend.get(),
+            wgsl_shader_module,
+            iteration_space,
+            max_output_vector_lengths,
+        );
+        self.tasks.insert(name.to_string(), task);
+        // creates the output buffers
+        self.apply(
+            name,
+            GpuTaskCommand::Mutate {
+                iteration_space: None,
+                max_output_lengths: None,
+            },
+        );
+    }
+    pub fn delete_task(&mut self, name: &str) {
+        self.tasks.remove(name).expect("Task not found");
+    }
+    pub fn has_task(&self, name: &str) -> bool {
+        self.tasks.contains_key(name)
+    }
+
+    pub fn set_config_inputs(&mut self, name: &str, inputs: TypeErasedConfigInputData) {
+        self.apply(name, GpuTaskCommand::SetConfigInputs(Box::new(inputs)));
+    }
+    pub fn set_inputs(&mut self, name: &str, data: TypeErasedArrayInputData) {
+        self.apply(name, GpuTaskCommand::SetInputs(Box::new(data)));
+    }
+    /// If a parameter is None then the existing value is retained
+    pub fn mutate(
+        &mut self,
+        name: &str,
+        iteration_space: Option<IterationSpace>,
+        max_output_lengths: Option<MaxOutputLengths>,
+    ) {
+        self.apply(
+            name,
+            GpuTaskCommand::Mutate {
+                iteration_space,
+                max_output_lengths,
+            },
+        );
+    }
+    /// Runs the main entry point, and waits for the outputs
+    pub fn run(&mut self, name: &str) {
+        self.apply(name, GpuTaskCommand::Run);
+    }
+    /// Runs the entry points (`main` or `#[wgsl_entry]` functions) one after another over the same inputs and outputs. Panics if the task has no entry point with one of the names.
+    pub fn run_entry_points(&mut self, name: &str, entry_points: &[&str]) {
+        assert_entry_points_exist(self.task_mut(name), entry_points);
+        self.apply(
+            name,
+            GpuTaskCommand::RunEntryPoints(entry_points.iter().map(|e| e.to_string()).collect()),
+        );
+    }
+    /**
+    Sets the inputs and runs the main entry point, like `set_inputs` followed by `run`, without waiting for the GPU.
+    The returned future resolves to the outputs of this run, which are not stored in the task, so `read` does not return them. If the outputs of all tasks would no longer fit in memory, the task does not run and the future resolves to an error.
+    */
+    pub fn run_async<OutputDataBuilder: OutputDataBuilderTrait>(
+        &mut self,
+        name: &str,
+        inputs: TypeErasedArrayInputData,
+    ) -> TaskOutputFuture<OutputDataBuilder> {
+        let backend = self.backend.clone();
+        let should_recompute_memory =
+            GpuTaskCommand::SetInputs(Box::new(inputs)).apply(self.task_mut(name), backend.get());
+        if should_recompute_memory {
+            let all_tasks: Vec<_> = self.tasks.values().collect();
+            if let Err(error) =
+                check_have_enough_memory(&all_tasks, self.ram_limit.get_or_insert_default())
+            {
+                return TaskOutputFuture::new(PendingOutputs::Failed(error));
+            }
+        }
+        TaskOutputFuture::new(backend.get().run_async(self.task_mut(name)))
+    }
+    /// How the outputs of later runs are read back, see `ReadbackMode`
+    pub fn set_readback_mode(&mut self, name: &str, readback_mode: ReadbackMode) {
+        self.apply(name, GpuTaskCommand::SetReadbackMode(readback_mode));
+    }
+    /**
+    the latest result is cleared after this call, you cannot retrieve it a second time
+    With `ReadbackMode::Async` this returns an error until the outputs of a run have arrived
+    */
+    pub fn read<OutputDataBuilder: OutputDataBuilderTrait>(
+        &mut self,
+        name: &str,
+    ) -> Result<OutputDataBuilder, String> {
+        let backend = self.backend.clone();
+        let task = self.task_mut(name);
+        backend.get().receive_outputs(task);
+        let result = if let Some(d) = &task.current_data().output() {
+            Ok(OutputDataBuilder::from(d))
+        } else {
+            Err("No output data found".into())
+        };
+        task.current_data_mut().clear_output();
+        result
+    }
+
+    fn task_mut(&mut self, name: &str) -> &mut GpuComputeTask {
+        self.tasks.get_mut(name).expect("Task not found")
+    }
+    fn apply(&mut self, name: &str, command: GpuTaskCommand) {
+        let backend = self.backend.clone();
+        let should_recompute_memory = command.apply(self.task_mut(name), backend.get());
+        if should_recompute_memory {
+            let all_tasks: Vec<_> = self.tasks.values().collect();
+            verify_have_enough_memory(&all_tasks, self.ram_limit.get_or_insert_default());
+        }
+    }
+}
@@ -1,6 +1,12 @@
+mod backend;
+mod context;
+#[cfg(feature = "bevy")]
 mod plugin;
 pub mod prelude;
 mod ram_limit;
+#[cfg(feature = "bevy")]
 mod spawn_fallback_camera;
+#[cfg(feature = "bevy")]
 mod system_params;
 mod task;
+pub mod testing;
@@ -1,9 +1,15 @@
 use bevy::{
     app::{App, Plugin, Startup, Update},
+    log,
     prelude::{AppExtStates, IntoSystemConfigs, States, in_state},
+    render::renderer::{RenderDevice, RenderQueue},
 };
 
 use crate::{
+    backend::{
+        ActiveComputeBackend, ComputeBackendKind, cpu_backend::CpuBackend,
+        wgpu_backend::WgpuBackend,
+    },
     ram_limit::RamLimit,
     spawn_fallback_camera::{spawn_fallback_camera, spawn_fallback_camera_runif},
 };
@@ -17,28 +23,57 @@ pub enum BevyGpuComputeState {
     Stopped,
 }
 
+/**
+Runs tasks on bevy's render device if there is one. Without bevy's `RenderPlugin` it creates its own wgpu device, and only if wgpu finds no adapter, not even a software one, does it run tasks on the CPU.
+With `RenderPlugin` (part of `DefaultPlugins`) the CPU fallback never happens on a machine without an adapter, because `RenderPlugin` panics before this plugin can pick a backend. Leave it out there, for example with `MinimalPlugins`, or select `ComputeBackendKind::Cpu`.
+*/
 pub struct BevyGpuComputePlugin {
     with_default_schedule: bool,
+    /// `None` picks wgpu if there is a GPU adapter, and falls back to the CPU otherwise
+    backend: Option<ComputeBackendKind>,
 }
 
 impl Plugin for BevyGpuComputePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<RamLimit>()
             .init_state::<BevyGpuComputeState>();
-        if self.with_default_schedule {
-            app.add_systems(Startup, spawn_fallback_camera).add_systems(
-                Update,
-                (spawn_fallback_camera.run_if(spawn_fallback_camera_runif),)
-                    .chain()
-                    .run_if(in_state(BevyGpuComputeState::Running)),
-            );
-        } else {
-            app.add_systems(
-                Update,
-                spawn_fallback_camera
-                    .run_if(spawn_fallback_camera_runif)
-                    .run_if(in_state(BevyGpuComputeState::Running)),
-            );
+        // the CPU backend does not depend on other plugins, so it is available right away, also in apps that are never finished, like most tests
+        if self.backend == Some(ComputeBackendKind::Cpu) {
+            app.insert_resource(ActiveComputeBackend::new(CpuBackend));
+        }
+    }
+    /// the render device only exists once bevy's `RenderPlugin` has finished
+    fn finish(&self, app: &mut App) {
+        if self.backend == Some(ComputeBackendKind::Cpu) {
+            return;
+        }
+        let render_device = app.world().get_resource::<RenderDevice>().cloned();
+        let render_queue = app.world().get_resource::<RenderQueue>().cloned();
+        match (render_device, render_queue) {
+            (Some(render_device), Some(render_queue)) => {
+                app.insert_resource(ActiveComputeBackend::new(WgpuBackend::from_render_device(
+                    render_device,
+                    render_queue,
+                )));
+                self.add_fallback_camera_systems(app);
+            }
+            // without `RenderPlugin`, for example with `MinimalPlugins`, create a device like `GpuComputeContext::headless` does
+            _ => match WgpuBackend::headless() {
+                Some(backend) => {
+                    log::info!(
+                        "GPU Compute: No render device found, running tasks on a headless wgpu device"
+                    );
+                    app.insert_resource(ActiveComputeBackend::new(backend));
+                }
+                None => {
+                    assert!(
+                        self.backend.is_none(),
+                        "The wgpu compute backend was selected, but wgpu found no adapter, not even a software one. Install one, like lavapipe or llvmpipe, or select `ComputeBackendKind::Cpu`"
+                    );
+                    log::warn!("GPU Compute: No GPU adapter found, running all tasks on the CPU");
+                    app.insert_resource(ActiveComputeBackend::new(CpuBackend));
+                }
+            },
         }
     }
 }
@@ -47,6 +82,7 @@ impl Default for BevyGpuComputePlugin {
     fn default() -> Self {
         BevyGpuComputePlugin {
             with_default_schedule: true,
+            backend: None,
         }
     }
 }
@@ -54,6 +90,30 @@ impl BevyGpuComputePlugin {
     pub fn no_default_schedule() -> Self {
         BevyGpuComputePlugin {
             with_default_schedule: false,
+            backend: None,
+        }
+    }
+    /// Runs all tasks on the given backend, instead of picking one automatically
+    pub fn with_backend(mut self, backend: ComputeBackendKind) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+    /// only needed when running on the GPU
+    fn add_fallback_camera_systems(&self, app: &mut App) {
+        if self.with_default_schedule {
+            app.add_systems(Startup, spawn_fallback_camera).add_systems(
+                Update,
+                (spawn_fallback_camera.run_if(spawn_fallback_camera_runif),)
+                    .chain()
+                    .run_if(in_state(BevyGpuComputeState::Running)),
+            );
+        } else {
+            app.add_systems(
+                Update,
+                spawn_fallback_camera
+                    .run_if(spawn_fallback_camera_runif)
+                    .run_if(in_state(BevyGpuComputeState::Running)),
+            );
         }
     }
 }
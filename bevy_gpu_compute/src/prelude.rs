@@ -1,18 +1,29 @@
 // Proc macros
 pub use bevy_gpu_compute_macro::wgsl_config;
+pub use bevy_gpu_compute_macro::wgsl_entry;
 pub use bevy_gpu_compute_macro::wgsl_input_array;
 pub use bevy_gpu_compute_macro::wgsl_output_array;
 pub use bevy_gpu_compute_macro::wgsl_output_vec;
+pub use bevy_gpu_compute_macro::wgsl_shader_library;
 pub use bevy_gpu_compute_macro::wgsl_shader_module;
 
 //helpers when writing the shader module:
 pub use bevy_gpu_compute_core::MaxOutputLengths;
 pub use bevy_gpu_compute_core::wgsl_helpers::*;
 
+pub use crate::backend::{ActiveComputeBackend, ComputeBackendKind};
+pub use crate::context::GpuComputeContext;
+#[cfg(feature = "bevy")]
 pub use crate::plugin::BevyGpuComputePlugin;
 
+#[cfg(feature = "bevy")]
 pub use crate::system_params::task_creator::BevyGpuComputeTaskCreator;
+#[cfg(feature = "bevy")]
 pub use crate::system_params::task_deleter::BevyGpuComputeTaskDeleter;
+#[cfg(feature = "bevy")]
 pub use crate::system_params::task_reader::GpuTaskReader;
+#[cfg(feature = "bevy")]
 pub use crate::system_params::task_runner::GpuTaskRunner;
+pub use crate::task::outputs::output_future::TaskOutputFuture;
 pub use crate::task::task_components::configuration::iteration_space::IterationSpace;
+pub use crate::task::task_components::configuration::readback_mode::ReadbackMode;
@@ -1,7 +1,8 @@
+#[cfg(feature = "bevy")]
 use bevy::prelude::Resource;
 use sysinfo::System;
 
-#[derive(Resource)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
 pub struct RamLimit {
     pub total_mem: u64,
 }
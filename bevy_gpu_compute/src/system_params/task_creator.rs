@@ -1,20 +1,21 @@
 use bevy::{
     ecs::system::SystemParam,
     prelude::{Commands, Entity, Res},
-    render::renderer::RenderDevice,
 };
 use bevy_gpu_compute_core::{
     MaxOutputLengths, TypesSpec,
     wgsl::shader_module::user_defined_portion::WgslShaderModuleUserPortion,
 };
 
-use crate::{prelude::IterationSpace, task::lib::BevyGpuComputeTask};
+use crate::{
+    backend::ActiveComputeBackend, prelude::IterationSpace, task::lib::BevyGpuComputeTask,
+};
 
 #[derive(SystemParam)]
 
 pub struct BevyGpuComputeTaskCreator<'w, 's> {
     commands: Commands<'w, 's>,
-    render_device: Res<'w, RenderDevice>,
+    backend: Res<'w, ActiveComputeBackend>,
 }
 
 impl BevyGpuComputeTaskCreator<'_, '_> {
@@ -53,7 +54,7 @@ impl BevyGpuComputeTaskCreator<'_, '_> {
     ) -> Entity {
         let task = BevyGpuComputeTask::from_shader::<ShaderModuleTypes>(
             name,
-            &self.render_device,
+            self.backend.get(),
             wgsl_shader_module,
             iteration_space,
             max_output_vector_lengths,
@@ -1,16 +1,23 @@
-use bevy::{ecs::system::SystemParam, prelude::Query};
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::{Query, Res},
+};
 use bevy_gpu_compute_core::OutputDataBuilderTrait;
 
-use crate::task::lib::BevyGpuComputeTask;
+use crate::{backend::ActiveComputeBackend, task::lib::BevyGpuComputeTask};
 
 #[derive(SystemParam)]
 
 pub struct GpuTaskReader<'w, 's> {
     tasks: Query<'w, 's, &'static mut BevyGpuComputeTask>,
+    backend: Res<'w, ActiveComputeBackend>,
 }
 
 impl GpuTaskReader<'_, '_> {
-    /// the latest result is cleared after this call, you cannot retrieve it a second time
+    /**
+    the latest result is cleared after this call, you cannot retrieve it a second time
+    With `ReadbackMode::Async` this returns an error until the outputs of a run have arrived
+    */
     pub fn latest_results<OutputDataBuilder: OutputDataBuilderTrait>(
         &mut self,
         name: &str,
@@ -20,6 +27,7 @@ impl GpuTaskReader<'_, '_> {
             .iter_mut()
             .find(|task| task.name() == name)
             .expect("Task not found");
+        self.backend.get().receive_outputs(&mut task);
         let result = if let Some(d) = &task.current_data().output() {
             Ok(OutputDataBuilder::from(d))
         } else {
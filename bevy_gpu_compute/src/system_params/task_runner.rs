@@ -1,26 +1,17 @@
 use bevy::{
     ecs::system::SystemParam,
-    log,
     prelude::{Entity, Query, Res},
-    render::renderer::{RenderDevice, RenderQueue},
 };
+use bevy_gpu_compute_core::{OutputDataBuilderTrait, TypeErasedArrayInputData};
 
 use crate::{
+    backend::ActiveComputeBackend,
     ram_limit::RamLimit,
     task::{
-        buffers::{
-            create_config_input_buffers::update_config_input_buffers,
-            create_input_buffers::update_input_buffers,
-            create_output_buffers::update_output_buffers,
-        },
         commands::{GpuTaskCommand, GpuTaskCommands},
-        compute_pipeline::update_on_pipeline_const_change::update_compute_pipeline,
-        dispatch::{create_bind_group::create_bind_group, dispatch_to_gpu::dispatch_to_gpu},
         lib::BevyGpuComputeTask,
-        outputs::{
-            read_gpu_output_counts::read_gpu_output_counts, read_gpu_task_outputs::read_gpu_outputs,
-        },
-        verify_enough_memory::verify_have_enough_memory,
+        outputs::output_future::{PendingOutputs, TaskOutputFuture},
+        verify_enough_memory::{check_have_enough_memory, verify_have_enough_memory},
     },
 };
 
@@ -28,8 +19,7 @@ use crate::{
 #[derive(SystemParam)]
 pub struct GpuTaskRunner<'w, 's> {
     tasks: Query<'w, 's, (Entity, &'static mut BevyGpuComputeTask)>,
-    render_device: Res<'w, RenderDevice>,
-    render_queue: Res<'w, RenderQueue>,
+    backend: Res<'w, ActiveComputeBackend>,
     ram_limit: Res<'w, RamLimit>,
 }
 
@@ -37,13 +27,13 @@ impl GpuTaskRunner<'_, '_> {
     /// get a GpuTaskCommands object, which is actually a queue of commands to be run.
     /// #### You MUST call `run_commands` on the returned object to actually run the commands.
     pub fn task(&mut self, name: &str) -> GpuTaskCommands {
-        let (entity, _) = self
+        let (entity, task) = self
             .tasks
-            .iter_mut()
+            .iter()
             .find(|(_, task)| task.name() == name)
             .expect("Task not found");
 
-        GpuTaskCommands::new(entity)
+        GpuTaskCommands::new(entity, task)
     }
 
     /// Runs all previously queued commands for the task
@@ -52,49 +42,46 @@ impl GpuTaskRunner<'_, '_> {
             .tasks
             .get_mut(commands.entity())
             .expect("Task entity not found");
+        let backend = self.backend.get();
         let mut should_recompute_memory = false;
         for cmd in commands.commands {
-            log::trace!("Running command: {}", cmd);
-            match cmd {
-                GpuTaskCommand::SetConfigInputs(inputs) => {
-                    task.current_data_mut().set_config_input(*inputs);
-                    update_config_input_buffers(&mut task, &self.render_device);
-                }
-                GpuTaskCommand::SetInputs(data) => {
-                    let lengths_changed = task
-                        .current_data_mut()
-                        .set_input_and_check_lengths_changed(*data);
-                    if lengths_changed {
-                        update_compute_pipeline(&mut task, &self.render_device);
-                    }
-                    update_input_buffers(&mut task, &self.render_device);
-                    create_bind_group(&mut task, &self.render_device);
-                }
-                GpuTaskCommand::Mutate {
-                    iteration_space,
-                    max_output_lengths,
-                } => {
-                    task.mutate(iteration_space, max_output_lengths);
-                    update_compute_pipeline(&mut task, &self.render_device);
-                    update_output_buffers(&mut task, &self.render_device);
-                    should_recompute_memory = true;
-                }
-                GpuTaskCommand::Run => {
-                    dispatch_to_gpu(&mut task, &self.render_device, &self.render_queue);
-                    let output_counts =
-                        read_gpu_output_counts(&mut task, &self.render_device, &self.render_queue);
-                    read_gpu_outputs(
-                        output_counts,
-                        &mut task,
-                        &self.render_device,
-                        &self.render_queue,
-                    );
-                }
-            }
+            should_recompute_memory |= cmd.apply(&mut task, backend);
         }
         if should_recompute_memory {
             let all_tasks: Vec<_> = self.tasks.iter().map(|(_, t)| t).collect();
             verify_have_enough_memory(&all_tasks, &self.ram_limit);
         }
     }
+
+    /**
+    Sets the inputs and runs the main entry point of the task right away, without waiting for the GPU. The blocking alternative is to queue `set_inputs` and `run` and call `run_commands`.
+    The returned future resolves to the outputs of this run once they are back from the GPU. It does not borrow the runner, so it can be spawned on bevy's `AsyncComputeTaskPool`:
+    ```ignore
+    let outputs = task_runner.run_async::<my_shader::OutputDataBuilder>("my_task", inputs);
+    let task = AsyncComputeTaskPool::get().spawn(outputs);
+    ```
+    The outputs are not stored in the task, so `GpuTaskReader` does not return them. If the outputs of all tasks would no longer fit in memory, the task does not run and the future resolves to an error.
+    */
+    pub fn run_async<OutputDataBuilder: OutputDataBuilderTrait>(
+        &mut self,
+        name: &str,
+        inputs: TypeErasedArrayInputData,
+    ) -> TaskOutputFuture<OutputDataBuilder> {
+        let (entity, mut task) = self
+            .tasks
+            .iter_mut()
+            .find(|(_, task)| task.name() == name)
+            .expect("Task not found");
+        let backend = self.backend.get();
+        let should_recompute_memory =
+            GpuTaskCommand::SetInputs(Box::new(inputs)).apply(&mut task, backend);
+        if should_recompute_memory {
+            let all_tasks: Vec<_> = self.tasks.iter().map(|(_, t)| t).collect();
+            if let Err(error) = check_have_enough_memory(&all_tasks, &self.ram_limit) {
+                return TaskOutputFuture::new(PendingOutputs::Failed(error));
+            }
+        }
+        let (_, mut task) = self.tasks.get_mut(entity).expect("Task entity not found");
+        TaskOutputFuture::new(backend.run_async(&mut task))
+    }
 }
@@ -1,18 +1,17 @@
-use bevy::{
-    log::{self},
-    render::renderer::RenderDevice,
+use wgpu::{
+    BufferUsages, Device,
+    util::{BufferInitDescriptor, DeviceExt},
 };
-use wgpu::{BufferUsages, util::BufferInitDescriptor};
 
 use crate::task::lib::BevyGpuComputeTask;
 
-pub fn update_config_input_buffers(task: &mut BevyGpuComputeTask, render_device: &RenderDevice) {
+pub fn update_config_input_buffers(task: &mut BevyGpuComputeTask, device: &Device) {
     log::trace!("Creating config input buffers for task {}", task.name());
     task.buffers_mut().config.clear();
     let mut new_buffers = Vec::new();
     for s in task.configuration().inputs().configs().iter() {
         let label = format!("{}-input-{}", task.name(), s.name.name());
-        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        let buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some(&label),
             contents: task
                 .current_data()
@@ -1,17 +1,16 @@
-use bevy::{
-    log::{self},
-    render::renderer::RenderDevice,
+use wgpu::{
+    BufferUsages, Device,
+    util::{BufferInitDescriptor, DeviceExt},
 };
-use wgpu::{BufferUsages, util::BufferInitDescriptor};
 
 use crate::task::lib::BevyGpuComputeTask;
 
-pub fn update_input_buffers(task: &mut BevyGpuComputeTask, render_device: &RenderDevice) {
+pub fn update_input_buffers(task: &mut BevyGpuComputeTask, device: &Device) {
     task.buffers_mut().input.clear();
     let mut new_buffers = Vec::new();
     for s in task.configuration().inputs().arrays().iter() {
         let label = format!("{}-input-{}", task.name(), s.name.name());
-        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        let buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some(&label),
             contents: task
                 .current_data()
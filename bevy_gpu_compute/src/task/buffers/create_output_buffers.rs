@@ -1,9 +1,11 @@
-use bevy::render::renderer::RenderDevice;
-use wgpu::{BufferDescriptor, BufferUsages, util::BufferInitDescriptor};
+use wgpu::{
+    BufferDescriptor, BufferUsages, Device,
+    util::{BufferInitDescriptor, DeviceExt},
+};
 
 use crate::task::{lib::BevyGpuComputeTask, outputs::definitions::wgsl_counter::WgslCounter};
 
-pub fn update_output_buffers(task: &mut BevyGpuComputeTask, render_device: &RenderDevice) {
+pub fn update_output_buffers(task: &mut BevyGpuComputeTask, device: &Device) {
     let mut output_buffers = Vec::new();
     let mut output_staging_buffers = Vec::new();
     let mut output_count_buffers = Vec::new();
@@ -17,14 +19,14 @@ pub fn update_output_buffers(task: &mut BevyGpuComputeTask, render_device: &Rend
             .max_lengths()
             .get_by_name(&spec.name);
         let output_size = spec.bytes as u64 * length as u64;
-        let output_buffer = render_device.create_buffer(&BufferDescriptor {
+        let output_buffer = device.create_buffer(&BufferDescriptor {
             label: Some(&format!("{:}-output-{:}", task.name(), i)),
             size: output_size,
             usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
         output_buffers.push(output_buffer);
-        let output_staging_buffer = render_device.create_buffer(&wgpu::BufferDescriptor {
+        let output_staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some(&format!("{:}-output-staging-{:}", task.name(), i)),
             size: output_size,
             usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
@@ -32,13 +34,13 @@ pub fn update_output_buffers(task: &mut BevyGpuComputeTask, render_device: &Rend
         });
         output_staging_buffers.push(output_staging_buffer);
         if spec.include_count {
-            let counter_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            let counter_buffer = device.create_buffer_init(&BufferInitDescriptor {
                 label: Some(&format!("{:}-output-counter-{:}", task.name(), i)),
                 contents: bytemuck::cast_slice(&[WgslCounter { count: 0 }]),
                 usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
             });
             output_count_buffers.push(counter_buffer);
-            let counter_staging_buffer = render_device.create_buffer(&wgpu::BufferDescriptor {
+            let counter_staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
                 label: Some(&format!("{:}-output-counter-staging-{:}", task.name(), i)),
                 size: std::mem::size_of::<WgslCounter>() as u64,
                 usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
@@ -1,12 +1,21 @@
+#[cfg(feature = "bevy")]
 use bevy::prelude::Entity;
 use bevy_gpu_compute_core::{
     MaxOutputLengths, TypeErasedArrayInputData, TypeErasedConfigInputData,
 };
 
-use crate::prelude::IterationSpace;
+use crate::{
+    backend::ComputeBackend, prelude::IterationSpace,
+    task::task_components::configuration::readback_mode::ReadbackMode,
+};
+
+use super::lib::BevyGpuComputeTask;
 
+#[cfg(feature = "bevy")]
 pub struct GpuTaskCommands {
     entity: Entity,
+    task_name: String,
+    entry_points: Vec<String>,
     pub commands: Vec<GpuTaskCommand>,
 }
 
@@ -18,6 +27,8 @@ pub enum GpuTaskCommand {
         max_output_lengths: Option<MaxOutputLengths>,
     },
     Run,
+    RunEntryPoints(Vec<String>),
+    SetReadbackMode(ReadbackMode),
 }
 impl std::fmt::Display for GpuTaskCommand {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -33,14 +44,92 @@ impl std::fmt::Display for GpuTaskCommand {
                 iteration_space, max_output_lengths
             ),
             GpuTaskCommand::Run => write!(f, "Run"),
+            GpuTaskCommand::RunEntryPoints(entry_points) => {
+                write!(f, "RunEntryPoints {:?}", entry_points)
+            }
+            GpuTaskCommand::SetReadbackMode(readback_mode) => {
+                write!(f, "SetReadbackMode {:?}", readback_mode)
+            }
+        }
+    }
+}
+
+/// Panics with the available entry points if the task does not have all of the given ones, so that a wrong name is reported where the run is queued, instead of when it is dispatched
+pub fn assert_entry_points_exist(task: &BevyGpuComputeTask, entry_points: &[&str]) {
+    check_entry_points(
+        task.name(),
+        task.configuration().shader().entry_point_function_names(),
+        entry_points,
+    );
+}
+
+fn check_entry_points(task_name: &str, available: &[String], entry_points: &[&str]) {
+    if let Some(missing) = entry_points
+        .iter()
+        .find(|e| !available.iter().any(|a| a == *e))
+    {
+        panic!(
+            "Entry point {} not found in task {}, the available entry points are {:?}",
+            missing, task_name, available
+        );
+    }
+}
+
+impl GpuTaskCommand {
+    /// Updates the task data and tells the backend about it. Returns true if the memory needed by the task outputs may have changed.
+    pub fn apply(self, task: &mut BevyGpuComputeTask, backend: &dyn ComputeBackend) -> bool {
+        log::trace!("Running command: {}", self);
+        match self {
+            GpuTaskCommand::SetConfigInputs(inputs) => {
+                task.current_data_mut().set_config_input(*inputs);
+                backend.on_config_inputs_set(task);
+            }
+            GpuTaskCommand::SetInputs(data) => {
+                let lengths_changed = task
+                    .current_data_mut()
+                    .set_input_and_check_lengths_changed(*data);
+                backend.on_inputs_set(task, lengths_changed);
+            }
+            GpuTaskCommand::Mutate {
+                iteration_space,
+                max_output_lengths,
+            } => {
+                task.mutate(iteration_space, max_output_lengths);
+                backend.on_mutate(task);
+                return true;
+            }
+            GpuTaskCommand::Run => {
+                let entry_point = task
+                    .configuration()
+                    .shader()
+                    .entry_point_function_name()
+                    .to_string();
+                backend.run(task, &[entry_point]);
+            }
+            GpuTaskCommand::RunEntryPoints(entry_points) => {
+                backend.run(task, &entry_points);
+            }
+            GpuTaskCommand::SetReadbackMode(readback_mode) => {
+                task.set_readback_mode(readback_mode);
+                // each run that can wait for its outputs needs its own staging buffers
+                return true;
+            }
         }
+        false
     }
 }
 
+#[cfg(feature = "bevy")]
 impl GpuTaskCommands {
-    pub fn new(entity: Entity) -> Self {
+    pub fn new(entity: Entity, task: &BevyGpuComputeTask) -> Self {
         GpuTaskCommands {
             entity,
+            task_name: task.name().to_string(),
+            entry_points: task
+                .configuration()
+                .shader()
+                .entry_point_function_names()
+                .to_vec(),
             commands: Vec::new(),
         }
     }
@@ -78,4 +167,21 @@ impl GpuTaskCommands {
         self.commands.push(GpuTaskCommand::Run);
         self
     }
+
+    /// This queues a run of the given entry points (`main` or `#[wgsl_entry]` functions), dispatched one after another over the same inputs and outputs. The outputs are read once all of them have finished. You still MUST call `GpuTaskRunner::run_commands` for this to take effect.
+    /// Panics if the task has no entry point with one of the names.
+    pub fn run_entry_points(mut self, entry_points: &[&str]) -> Self {
+        check_entry_points(&self.task_name, &self.entry_points, entry_points);
+        self.commands.push(GpuTaskCommand::RunEntryPoints(
+            entry_points.iter().map(|e| e.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// This queues a change of how the outputs of later runs are read back, see `ReadbackMode`. You still MUST call `GpuTaskRunner::run_commands` for this to take effect.
+    pub fn set_readback_mode(mut self, readback_mode: ReadbackMode) -> Self {
+        self.commands
+            .push(GpuTaskCommand::SetReadbackMode(readback_mode));
+        self
+    }
 }
@@ -1,13 +1,31 @@
-use bevy::{prelude::Component, render::render_resource::ComputePipeline};
+use wgpu::ComputePipeline;
+
+use crate::task::lib::BevyGpuComputeTask;
 
 use super::lru_cache::LruCache;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
 pub struct PipelineKey {
     pub pipeline_consts_version: u64,
+    /// the input array lengths are pipeline consts too, but do not change the config version
+    pub input_lengths_hash: u64,
+    pub entry_point: String,
+}
+
+impl PipelineKey {
+    pub fn for_task(task: &BevyGpuComputeTask, entry_point: String) -> Self {
+        PipelineKey {
+            pipeline_consts_version: task.configuration().version(),
+            input_lengths_hash: task
+                .current_data()
+                .input_lengths()
+                .as_ref()
+                .map_or(0, |lengths| lengths.hash()),
+            entry_point,
+        }
+    }
 }
 
-#[derive(Component)]
 pub struct PipelineLruCache {
     pub cache: LruCache<PipelineKey, ComputePipeline>,
 }
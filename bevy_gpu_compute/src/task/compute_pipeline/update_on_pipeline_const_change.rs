@@ -1,46 +1,62 @@
-use bevy::{log, render::renderer::RenderDevice};
-
-use wgpu::{ComputePipelineDescriptor, PipelineCompilationOptions};
+use wgpu::{ComputePipelineDescriptor, Device, PipelineCompilationOptions};
 
 use crate::task::lib::BevyGpuComputeTask;
 
 use super::pipeline_cache::PipelineKey;
 
-pub fn update_compute_pipeline(task: &mut BevyGpuComputeTask, render_device: &RenderDevice) {
+pub fn update_compute_pipeline(task: &mut BevyGpuComputeTask, device: &Device) {
     if task.current_data().input_lengths().is_none() {
         return;
     }
     log::trace!("Updating pipeline for task {}", task.name());
-    let key = PipelineKey {
-        pipeline_consts_version: task.configuration().version(),
-    };
+    for entry_point in task
+        .configuration()
+        .shader()
+        .entry_point_function_names()
+        .to_vec()
+    {
+        let key = PipelineKey::for_task(task, entry_point);
+        create_compute_pipeline_if_missing(task, device, key);
+    }
+}
+
+/// The cache only holds a limited number of pipelines, so one that was created by `update_compute_pipeline` may have been evicted since, for example when a task has more entry points than fit in the cache.
+pub fn create_compute_pipeline_if_missing(
+    task: &mut BevyGpuComputeTask,
+    device: &Device,
+    key: PipelineKey,
+) {
     if task
         .runtime_state()
         .pipeline_cache()
         .cache
         .contains_key(&key)
     {
-    } else {
-        log::trace!("Creating new pipeline for task {}", task.name());
-        log::trace!(
-            "pipeline layout {:?}",
-            task.runtime_state().pipeline_layout()
-        );
-        let compute_pipeline = render_device.create_compute_pipeline(&ComputePipelineDescriptor {
-            label: Some(task.name()),
-            layout: Some(task.runtime_state().pipeline_layout()),
-            module: task.configuration().shader().shader_module(),
-            entry_point: Some(task.configuration().shader().entry_point_function_name()),
-            // this is where we specify new values for pipeline constants...
-            compilation_options: PipelineCompilationOptions {
-                constants: &task.get_pipeline_consts(),
-                zero_initialize_workgroup_memory: Default::default(),
-            },
-            cache: None,
-        });
-        task.runtime_state_mut()
-            .pipeline_cache_mut()
-            .cache
-            .insert(key, compute_pipeline);
+        return;
     }
+    log::trace!(
+        "Creating new pipeline for task {}, entry point {}",
+        task.name(),
+        key.entry_point
+    );
+    log::trace!(
+        "pipeline layout {:?}",
+        task.runtime_state().pipeline_layout()
+    );
+    let compute_pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+        label: Some(task.name()),
+        layout: Some(task.runtime_state().pipeline_layout()),
+        module: task.configuration().shader().shader_module(),
+        entry_point: Some(&key.entry_point),
+        // this is where we specify new values for pipeline constants...
+        compilation_options: PipelineCompilationOptions {
+            constants: &task.get_pipeline_consts(),
+            zero_initialize_workgroup_memory: Default::default(),
+        },
+        cache: None,
+    });
+    task.runtime_state_mut()
+        .pipeline_cache_mut()
+        .cache
+        .insert(key, compute_pipeline);
 }
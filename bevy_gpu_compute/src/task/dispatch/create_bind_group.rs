@@ -1,7 +1,4 @@
-use bevy::{
-    log,
-    render::{render_resource::Buffer, renderer::RenderDevice},
-};
+use wgpu::{Buffer, Device};
 
 use crate::task::lib::BevyGpuComputeTask;
 
@@ -18,7 +15,7 @@ For example, this might be the wgsl code:
 
 The numbers in the `@binding` are the bind group entry numbers. The `@group` is the bind group number. We are only using a single bind group in the current library version.
  */
-pub fn create_bind_group(task: &mut BevyGpuComputeTask, render_device: &RenderDevice) {
+pub fn create_bind_group(task: &mut BevyGpuComputeTask, device: &Device) {
     log::trace!("Creating bind group for task {}", task.name());
     let mut bindings = Vec::new();
     for (i, s) in task.configuration().inputs().configs().iter().enumerate() {
@@ -52,8 +49,8 @@ pub fn create_bind_group(task: &mut BevyGpuComputeTask, render_device: &RenderDe
             binding: s.binding_number,
             resource: output_buffer.as_entire_binding(),
         });
-        if s.include_count {
-            let count_buffer: &Buffer = task.buffers().output.count.get(i).unwrap();
+        if let Some(count_index) = task.configuration().outputs().count_buffer_index(i) {
+            let count_buffer: &Buffer = task.buffers().output.count.get(count_index).unwrap();
             bindings.push(wgpu::BindGroupEntry {
                 binding: s.count_binding_number.unwrap(),
                 resource: count_buffer.as_entire_binding(),
@@ -62,5 +59,9 @@ pub fn create_bind_group(task: &mut BevyGpuComputeTask, render_device: &RenderDe
     }
     let layout = task.runtime_state().bind_group_layout();
     *task.runtime_state_mut().bind_group_mut() =
-        Some(render_device.create_bind_group(task.name(), layout, &bindings));
+        Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(task.name()),
+            layout,
+            entries: &bindings,
+        }));
 }
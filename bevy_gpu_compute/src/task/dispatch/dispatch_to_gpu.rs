@@ -1,30 +1,44 @@
-use bevy::render::renderer::{RenderDevice, RenderQueue};
+use wgpu::{Device, Queue};
 
-use crate::task::{compute_pipeline::pipeline_cache::PipelineKey, lib::BevyGpuComputeTask};
+use crate::task::{
+    compute_pipeline::{
+        pipeline_cache::PipelineKey,
+        update_on_pipeline_const_change::create_compute_pipeline_if_missing,
+    },
+    lib::BevyGpuComputeTask,
+};
+
+/// The entry points are dispatched in order over the same bind group, each one sees the writes of the ones before it. Their names are checked when the run is queued, see `assert_entry_points_exist`
 pub fn dispatch_to_gpu(
     task: &mut BevyGpuComputeTask,
-    render_device: &RenderDevice,
-    render_queue: &RenderQueue,
+    device: &Device,
+    queue: &Queue,
+    entry_points: &[String],
 ) {
-    let mut encoder = render_device.create_command_encoder(&Default::default());
+    let mut encoder = device.create_command_encoder(&Default::default());
     {
         let mut compute_pass = encoder.begin_compute_pass(&Default::default());
-        let key = PipelineKey {
-            pipeline_consts_version: task.configuration().version(),
-        };
-        compute_pass.set_pipeline(
-            task.runtime_state_mut()
-                .pipeline_cache_mut()
-                .cache
-                .get(&key)
-                .unwrap(),
-        );
-        compute_pass.set_bind_group(0, task.runtime_state().bind_group().as_ref().unwrap(), &[]);
-        compute_pass.dispatch_workgroups(
-            task.runtime_state().workgroup_space().x(),
-            task.runtime_state().workgroup_space().y(),
-            task.runtime_state().workgroup_space().z(),
-        );
+        for entry_point in entry_points {
+            let key = PipelineKey::for_task(task, entry_point.clone());
+            create_compute_pipeline_if_missing(task, device, key.clone());
+            compute_pass.set_pipeline(
+                task.runtime_state_mut()
+                    .pipeline_cache_mut()
+                    .cache
+                    .get(&key)
+                    .unwrap(),
+            );
+            compute_pass.set_bind_group(
+                0,
+                task.runtime_state().bind_group().as_ref().unwrap(),
+                &[],
+            );
+            compute_pass.dispatch_workgroups(
+                task.runtime_state().workgroup_space().x(),
+                task.runtime_state().workgroup_space().y(),
+                task.runtime_state().workgroup_space().z(),
+            );
+        }
     }
-    render_queue.submit(std::iter::once(encoder.finish()));
+    queue.submit(std::iter::once(encoder.finish()));
 }
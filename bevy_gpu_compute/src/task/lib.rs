@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use bevy::{ecs::component::Component, log, render::renderer::RenderDevice};
+#[cfg(feature = "bevy")]
+use bevy::ecs::component::Component;
 use bevy_gpu_compute_core::{InputTypesMetadataTrait, OutputTypesMetadataTrait};
 use bevy_gpu_compute_core::{
     MaxOutputLengths, TypesSpec,
@@ -9,18 +10,18 @@ use bevy_gpu_compute_core::{
     },
 };
 
+use crate::backend::ComputeBackend;
+
 use super::task_components::{
     buffers::TaskBuffers,
     configuration::{
         input_spec::InputSpec, iteration_space::IterationSpace, lib::TaskConfiguration,
-        output_spec::OutputSpec, wgsl_code::WgslCode,
+        output_spec::OutputSpec, readback_mode::ReadbackMode, wgsl_code::WgslCode,
     },
     data::TaskData,
     runtime_state::{
-        gpu_workgroup_sizes::GpuWorkgroupSizes,
-        gpu_workgroup_space::GpuWorkgroupSpace,
-        lib::{TaskRuntimeState, TaskRuntimeStateBuilder},
-        max_output_bytes::MaxOutputBytes,
+        gpu_workgroup_sizes::GpuWorkgroupSizes, gpu_workgroup_space::GpuWorkgroupSpace,
+        lib::TaskRuntimeState, max_output_bytes::MaxOutputBytes,
     },
 };
 
@@ -28,8 +29,7 @@ use super::task_components::{
 A task can only run once per run of the BevyGpuComputeRunTaskSet system set
 By default this means once per frame
 */
-
-#[derive(Component)]
+#[cfg_attr(feature = "bevy", derive(Component))]
 pub struct BevyGpuComputeTask {
     name: String,
     configuration: TaskConfiguration,
@@ -38,6 +38,9 @@ pub struct BevyGpuComputeTask {
     current_data: TaskData,
 }
 
+/// The name of a task outside of bevy, for example in a `GpuComputeContext`. It is the same type, which is only a component when the `bevy` feature is on.
+pub type GpuComputeTask = BevyGpuComputeTask;
+
 impl BevyGpuComputeTask {
     //getters
     pub fn name(&self) -> &str {
@@ -67,7 +70,7 @@ impl BevyGpuComputeTask {
     }
     pub fn from_shader<ShaderModuleTypes: TypesSpec>(
         name: &str,
-        render_device: &RenderDevice,
+        backend: &dyn ComputeBackend,
         wgsl_shader_module: WgslShaderModuleUserPortion,
         iteration_space: IterationSpace,
         max_output_vector_lengths: MaxOutputLengths,
@@ -79,22 +82,18 @@ impl BevyGpuComputeTask {
         );
         Self::create_manually::<ShaderModuleTypes>(
             name,
-            render_device,
+            backend,
             iteration_space,
             max_output_vector_lengths,
-            WgslCode::from_string(
-                name,
-                render_device,
-                full_module.wgsl_code(iteration_space.num_dimmensions()),
-                "main".to_string(),
-            ),
+            backend.create_wgsl_code(name, &full_module, iteration_space.num_dimmensions()),
         )
     }
 
     /// ensure that you send relevant update events after calling this function
+    /// On the CPU backend the task runs the rust version of the `ShaderModuleTypes` shader module, not `wgsl_code`.
     pub fn create_manually<ShaderModuleTypes: TypesSpec>(
         name: &str,
-        render_device: &RenderDevice,
+        backend: &dyn ComputeBackend,
         iteration_space: IterationSpace,
         max_output_array_lengths: MaxOutputLengths,
         wgsl_code: WgslCode,
@@ -109,9 +108,9 @@ impl BevyGpuComputeTask {
             iteration_space,
             InputSpec::new(input_metadata, config_input_metadata),
             OutputSpec::new(output_metadata, max_output_array_lengths),
+            Some(ShaderModuleTypes::run_entry_points_on_cpu),
         );
-        let runtime_state =
-            TaskRuntimeStateBuilder::new(render_device, name, &configuration).build();
+        let runtime_state = backend.create_runtime_state(name, &configuration);
         Self {
             name: name.to_string(),
             configuration,
@@ -150,6 +149,10 @@ impl BevyGpuComputeTask {
         self.update_runtime_state_on_iter_space_or_max_output_lengths_change();
     }
 
+    pub fn set_readback_mode(&mut self, readback_mode: ReadbackMode) {
+        self.configuration.set_readback_mode(readback_mode);
+    }
+
     pub fn get_pipeline_consts(&self) -> HashMap<String, f64> {
         let mut n: HashMap<String, f64> = HashMap::new();
         if self.current_data().input_lengths().is_none() {
@@ -0,0 +1,297 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+};
+
+use bevy_gpu_compute_core::TypeErasedArrayOutputData;
+use futures::task::AtomicWaker;
+use wgpu::{Buffer, BufferDescriptor, BufferUsages, Device, Queue, SubmissionIndex};
+
+use crate::task::{
+    lib::BevyGpuComputeTask, task_components::configuration::readback_mode::ReadbackMode,
+};
+
+use super::definitions::wgsl_counter::WgslCounter;
+
+/// The copies of one output of a run, made right after the run, so the next run can write to the output buffers while these are read
+struct PendingOutput {
+    name: String,
+    bytes: usize,
+    /// `None` if the output buffer is empty, since empty buffers cannot be mapped
+    staging: Option<Buffer>,
+    /// only output vecs have a counter
+    count_staging: Option<Buffer>,
+}
+
+impl PendingOutput {
+    fn staging_buffers(&self) -> impl Iterator<Item = &Buffer> {
+        self.staging.iter().chain(self.count_staging.iter())
+    }
+}
+
+/// The staging buffers of readbacks that have been read, so that later runs reuse them instead of creating new ones
+#[derive(Clone, Default)]
+pub struct StagingPool {
+    sets: Arc<Mutex<Vec<Vec<PendingOutput>>>>,
+}
+
+impl StagingPool {
+    /// a set whose staging buffers have the given sizes, `None` for empty outputs
+    fn take(&self, sizes: &[Option<u64>]) -> Option<Vec<PendingOutput>> {
+        let mut sets = self.sets.lock().unwrap();
+        // sets of other sizes are from before the output buffers were resized, and are not needed anymore
+        sets.retain(|set| {
+            set.iter()
+                .map(|output| output.staging.as_ref().map(|s| s.size()))
+                .eq(sizes.iter().copied())
+        });
+        sets.pop()
+    }
+    /// keeps the set for later runs, unless `capacity` sets are already kept
+    fn give_back(&self, set: Vec<PendingOutput>, capacity: usize) {
+        let mut sets = self.sets.lock().unwrap();
+        if sets.len() < capacity {
+            sets.push(set);
+        }
+    }
+}
+
+/// A run whose outputs are on their way back from the GPU
+pub struct PendingReadback {
+    outputs: Vec<PendingOutput>,
+    pool: StagingPool,
+    /// how many sets of staging buffers the pool keeps
+    pool_capacity: usize,
+    /// the submission that copies the outputs to the staging buffers
+    submission_index: SubmissionIndex,
+    /// staging buffers that are not mapped yet
+    unmapped: Arc<AtomicUsize>,
+    failed: Arc<AtomicBool>,
+    /// woken once all staging buffers are mapped
+    waker: Arc<AtomicWaker>,
+}
+
+impl PendingReadback {
+    pub fn is_done(&self) -> bool {
+        self.unmapped.load(Ordering::Acquire) == 0
+    }
+    pub fn waker(&self) -> &AtomicWaker {
+        &self.waker
+    }
+    /// the staging buffers are mapped once the device has been polled after this submission is done
+    pub fn submission_index(&self) -> &SubmissionIndex {
+        &self.submission_index
+    }
+
+    /// Copies the outputs out of the staging buffers, and returns the buffers to the pool. Only call this once `is_done`.
+    pub fn read(self) -> Result<TypeErasedArrayOutputData, String> {
+        if self.failed.load(Ordering::Acquire) {
+            return Err("Failed to read output from GPU".into());
+        }
+        let mut bytes_per_wgsl_output_type_name = HashMap::new();
+        for output in self.outputs.iter() {
+            let Some(staging) = &output.staging else {
+                bytes_per_wgsl_output_type_name.insert(output.name.clone(), Vec::new());
+                continue;
+            };
+            let mapped = staging.slice(..).get_mapped_range();
+            let length = match &output.count_staging {
+                Some(count_staging) => {
+                    let counter: WgslCounter =
+                        bytemuck::pod_read_unaligned(&count_staging.slice(..).get_mapped_range());
+                    log::debug!("Read length of output vec on gpu: {}", counter.count);
+                    (counter.count as usize * output.bytes).min(mapped.len())
+                }
+                None => mapped.len(),
+            };
+            bytes_per_wgsl_output_type_name.insert(output.name.clone(), mapped[..length].to_vec());
+        }
+        self.outputs
+            .iter()
+            .flat_map(PendingOutput::staging_buffers)
+            .for_each(Buffer::unmap);
+        self.pool.give_back(self.outputs, self.pool_capacity);
+        Ok(TypeErasedArrayOutputData::new(
+            bytes_per_wgsl_output_type_name,
+        ))
+    }
+}
+
+/// Queues the readback of `submit_readback` on the task, `receive_readbacks` moves its outputs into the task data once they are mapped
+pub fn start_readback(task: &mut BevyGpuComputeTask, device: &Device, queue: &Queue) {
+    let readback = submit_readback(task, device, queue);
+    task.buffers_mut().readbacks.push_back(readback);
+}
+
+/// How many sets of staging buffers a task keeps for reuse, one for each run that can wait for its outputs
+pub fn staging_pool_capacity(readback_mode: ReadbackMode) -> usize {
+    match readback_mode {
+        ReadbackMode::Blocking => 1,
+        ReadbackMode::Async { max_in_flight } => max_in_flight,
+    }
+}
+
+/// Copies the outputs of the run that was just dispatched to staging buffers from the task's pool, or new ones if none are free, resets the counters, and starts mapping the copies without waiting for them
+pub fn submit_readback(
+    task: &mut BevyGpuComputeTask,
+    device: &Device,
+    queue: &Queue,
+) -> PendingReadback {
+    let sizes: Vec<Option<u64>> = task
+        .buffers()
+        .output
+        .main
+        .iter()
+        .map(|b| (b.size() > 0).then(|| b.size()))
+        .collect();
+    let pool = task.buffers().staging_pool.clone();
+    let outputs = pool
+        .take(&sizes)
+        .unwrap_or_else(|| create_staging_set(task, device));
+    let mut encoder = device.create_command_encoder(&Default::default());
+    // only output vecs have a counter buffer
+    let mut count_buffers = task.buffers().output.count.iter();
+    for (output, output_buffer) in outputs.iter().zip(task.buffers().output.main.iter()) {
+        if let Some(staging) = &output.staging {
+            encoder.copy_buffer_to_buffer(output_buffer, 0, staging, 0, output_buffer.size());
+        }
+        if let Some(count_staging) = &output.count_staging {
+            let count_buffer = count_buffers.next().unwrap();
+            encoder.copy_buffer_to_buffer(count_buffer, 0, count_staging, 0, count_staging.size());
+            // reset the counter for the next run
+            encoder.clear_buffer(count_buffer, 0, None);
+        }
+    }
+    let submission_index = queue.submit(std::iter::once(encoder.finish()));
+
+    let staging_buffers: Vec<&Buffer> = outputs
+        .iter()
+        .flat_map(PendingOutput::staging_buffers)
+        .collect();
+    let unmapped = Arc::new(AtomicUsize::new(staging_buffers.len()));
+    let failed = Arc::new(AtomicBool::new(false));
+    let waker = Arc::new(AtomicWaker::new());
+    for staging in staging_buffers {
+        let unmapped = unmapped.clone();
+        let failed = failed.clone();
+        let waker = waker.clone();
+        staging
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                if let Err(error) = result {
+                    log::error!("Failed to map output staging buffer: {}", error);
+                    failed.store(true, Ordering::Release);
+                }
+                if unmapped.fetch_sub(1, Ordering::AcqRel) == 1 {
+                    waker.wake();
+                }
+            });
+    }
+    PendingReadback {
+        outputs,
+        pool,
+        pool_capacity: staging_pool_capacity(task.configuration().readback_mode()),
+        submission_index,
+        unmapped,
+        failed,
+        waker,
+    }
+}
+
+/// a staging buffer for every output of the task, at the current size of its output buffer
+fn create_staging_set(task: &BevyGpuComputeTask, device: &Device) -> Vec<PendingOutput> {
+    let staging_buffer = |label: String, size: u64| {
+        device.create_buffer(&BufferDescriptor {
+            label: Some(&label),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    };
+    task.configuration()
+        .outputs()
+        .arrays()
+        .iter()
+        .enumerate()
+        .map(|(i, metadata)| {
+            let size = task.buffers().output.main[i].size();
+            PendingOutput {
+                name: metadata.name.name().to_string(),
+                bytes: metadata.bytes,
+                staging: (size > 0).then(|| {
+                    staging_buffer(format!("{}-output-readback-{}", task.name(), i), size)
+                }),
+                count_staging: metadata.include_count.then(|| {
+                    staging_buffer(
+                        format!("{}-output-counter-readback-{}", task.name(), i),
+                        std::mem::size_of::<WgslCounter>() as u64,
+                    )
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Moves the outputs of finished runs into the task data, oldest first, and waits for the GPU until at most `max_in_flight` runs are still waiting for their outputs
+pub fn receive_readbacks(task: &mut BevyGpuComputeTask, device: &Device, max_in_flight: usize) {
+    if task.buffers().readbacks.is_empty() {
+        return;
+    }
+    // runs the map callbacks of everything the GPU has finished
+    device.poll(wgpu::Maintain::Poll);
+    while let Some(readback) = task.buffers().readbacks.front() {
+        if !readback.is_done() {
+            if task.buffers().readbacks.len() <= max_in_flight {
+                break;
+            }
+            device.poll(wgpu::Maintain::Wait);
+        }
+        let readback = task.buffers_mut().readbacks.pop_front().unwrap();
+        match readback.read() {
+            Ok(output) => task.current_data_mut().set_output(output),
+            // the outputs of this run are lost, later runs can still succeed
+            Err(error) => log::error!("{} in task {}", error, task.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::wgpu_backend::WgpuBackend;
+
+    fn staging_set(device: &Device, size: u64) -> Vec<PendingOutput> {
+        vec![PendingOutput {
+            name: "out".to_string(),
+            bytes: 4,
+            staging: Some(device.create_buffer(&BufferDescriptor {
+                label: None,
+                size,
+                usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })),
+            count_staging: None,
+        }]
+    }
+
+    #[test]
+    fn test_staging_pool_reuses_sets_of_the_same_size() {
+        let Some(backend) = WgpuBackend::headless() else {
+            return;
+        };
+        let device = backend.device();
+        let pool = StagingPool::default();
+        pool.give_back(staging_set(device, 16), 2);
+        pool.give_back(staging_set(device, 16), 2);
+        // more than `max_in_flight` sets are not kept
+        pool.give_back(staging_set(device, 16), 2);
+        assert_eq!(pool.sets.lock().unwrap().len(), 2);
+        assert!(pool.take(&[Some(16)]).is_some());
+        // sets of another size are dropped once the output buffers have been resized
+        assert!(pool.take(&[Some(32)]).is_none());
+        assert!(pool.sets.lock().unwrap().is_empty());
+    }
+}
@@ -1,4 +1,6 @@
+pub mod async_readback;
 pub mod definitions;
 pub mod helpers;
+pub mod output_future;
 pub mod read_gpu_output_counts;
 pub mod read_gpu_task_outputs;
@@ -0,0 +1,57 @@
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bevy_gpu_compute_core::{OutputDataBuilderTrait, TypeErasedArrayOutputData};
+
+use super::async_readback::PendingReadback;
+
+/// The outputs of a run, as the backend returns them from `run_async`
+pub enum PendingOutputs {
+    /// the CPU backend has them right away
+    Ready(TypeErasedArrayOutputData),
+    Readback(PendingReadback),
+    /// the run did not start, for example because the outputs would not fit in memory
+    Failed(String),
+}
+
+/**
+Resolves to the outputs of one run, once they are back from the GPU, or an error if they could not be read. Returned by `GpuTaskRunner::run_async` and `GpuComputeContext::run_async`.
+It does not borrow the task, so it can be awaited on any executor, like bevy's `AsyncComputeTaskPool`, while the task is run again.
+*/
+pub struct TaskOutputFuture<OutputDataBuilder> {
+    outputs: Option<PendingOutputs>,
+    _output: PhantomData<fn() -> OutputDataBuilder>,
+}
+
+impl<OutputDataBuilder> TaskOutputFuture<OutputDataBuilder> {
+    pub fn new(outputs: PendingOutputs) -> Self {
+        TaskOutputFuture {
+            outputs: Some(outputs),
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<OutputDataBuilder: OutputDataBuilderTrait> Future for TaskOutputFuture<OutputDataBuilder> {
+    type Output = Result<OutputDataBuilder, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let outputs = match self.outputs.take().expect("polled after completion") {
+            PendingOutputs::Ready(outputs) => Ok(outputs),
+            PendingOutputs::Failed(error) => Err(error),
+            PendingOutputs::Readback(readback) => {
+                readback.waker().register(cx.waker());
+                if !readback.is_done() {
+                    self.outputs = Some(PendingOutputs::Readback(readback));
+                    return Poll::Pending;
+                }
+                readback.read()
+            }
+        };
+        Poll::Ready(outputs.map(|outputs| OutputDataBuilder::from(&outputs)))
+    }
+}
@@ -1,10 +1,6 @@
 use std::sync::{Arc, Mutex};
 
-use bevy::{
-    log,
-    render::renderer::{RenderDevice, RenderQueue},
-};
-use wgpu::Buffer;
+use wgpu::{Buffer, Device, Queue};
 
 use crate::task::lib::BevyGpuComputeTask;
 
@@ -15,8 +11,8 @@ use super::{
 
 pub fn read_gpu_output_counts(
     task: &mut BevyGpuComputeTask,
-    render_device: &RenderDevice,
-    render_queue: &RenderQueue,
+    device: &Device,
+    queue: &Queue,
 ) -> Vec<Option<usize>> {
     let local_res_counts: Arc<Mutex<Vec<Option<usize>>>> = Arc::new(Mutex::new(Vec::new()));
     task.configuration()
@@ -24,14 +20,14 @@ pub fn read_gpu_output_counts(
         .arrays()
         .iter()
         .enumerate()
-        .for_each(|(i, metadata)| {
-            if metadata.include_count {
+        .for_each(|(i, _)| {
+            if let Some(count_index) = task.configuration().outputs().count_buffer_index(i) {
                 log::trace!("Reading count for output {}", i);
                 let count = read_gpu_output_counts_single_output_type(
-                    render_device,
-                    render_queue,
-                    &task.buffers().output.count[i],
-                    &task.buffers().output.count_staging[i],
+                    device,
+                    queue,
+                    &task.buffers().output.count[count_index],
+                    &task.buffers().output.count_staging[count_index],
                 );
                 local_res_counts.lock().unwrap().push(Some(count as usize));
             } else {
@@ -42,14 +38,14 @@ pub fn read_gpu_output_counts(
 }
 
 fn read_gpu_output_counts_single_output_type(
-    render_device: &RenderDevice,
-    render_queue: &RenderQueue,
+    device: &Device,
+    queue: &Queue,
     count_buffer: &Buffer,
     count_staging_buffer: &Buffer,
 ) -> u32 {
     let count = get_gpu_output_counter_value(
-        render_device,
-        render_queue,
+        device,
+        queue,
         count_buffer,
         count_staging_buffer,
         std::mem::size_of::<WgslCounter>() as u64,
@@ -1,10 +1,7 @@
 use std::cmp::min;
 
-use bevy::{
-    log,
-    render::renderer::{RenderDevice, RenderQueue},
-};
 use bevy_gpu_compute_core::TypeErasedArrayOutputData;
+use wgpu::{Device, Queue};
 
 use crate::task::lib::BevyGpuComputeTask;
 
@@ -16,8 +13,8 @@ use std::collections::HashMap;
 pub fn read_gpu_outputs(
     output_counts: Vec<Option<usize>>,
     task: &mut BevyGpuComputeTask,
-    render_device: &RenderDevice,
-    render_queue: &RenderQueue,
+    device: &Device,
+    queue: &Queue,
 ) {
     let mut bytes_per_wgsl_output_type_name: HashMap<String, Vec<u8>> = HashMap::new();
 
@@ -49,8 +46,8 @@ pub fn read_gpu_outputs(
                     .insert(metadata.name.name().to_string(), Vec::new());
             } else {
                 let raw_bytes = get_gpu_output_as_bytes_vec(
-                    render_device,
-                    render_queue,
+                    device,
+                    queue,
                     out_buffer,
                     staging_buffer,
                     total_byte_size as u64,
@@ -1,4 +1,8 @@
-use bevy::render::render_resource::Buffer;
+use std::collections::VecDeque;
+
+use wgpu::Buffer;
+
+use crate::task::outputs::async_readback::{PendingReadback, StagingPool};
 
 #[derive(Default)]
 
@@ -6,6 +10,10 @@ pub struct TaskBuffers {
     pub input: Vec<Buffer>,
     pub config: Vec<Buffer>,
     pub output: OutputBuffers,
+    /// runs with `ReadbackMode::Async` whose outputs have not been received yet, oldest first
+    pub readbacks: VecDeque<PendingReadback>,
+    /// staging buffers of earlier readbacks, shared with the readbacks so they can return them once read
+    pub staging_pool: StagingPool,
 }
 
 #[derive(Default)]
@@ -1,4 +1,5 @@
-use super::{iteration_space::IterationSpace, wgsl_code::WgslCode};
+use super::{iteration_space::IterationSpace, readback_mode::ReadbackMode, wgsl_code::WgslCode};
+use crate::backend::cpu_backend::CpuRunner;
 use crate::task::task_components::configuration::input_spec::InputSpec;
 use crate::task::task_components::configuration::output_spec::OutputSpec;
 
@@ -16,6 +17,8 @@ pub struct TaskConfiguration {
     inputs: InputSpec,
     outputs: OutputSpec,
     version: u64,
+    cpu_runner: Option<CpuRunner>,
+    readback_mode: ReadbackMode,
 }
 
 impl TaskConfiguration {
@@ -24,6 +27,7 @@ impl TaskConfiguration {
         iteration_space: IterationSpace,
         inputs: InputSpec,
         outputs: OutputSpec,
+        cpu_runner: Option<CpuRunner>,
     ) -> Self {
         TaskConfiguration {
             shader,
@@ -31,6 +35,8 @@ impl TaskConfiguration {
             inputs,
             outputs,
             version: 0,
+            cpu_runner,
+            readback_mode: ReadbackMode::default(),
         }
     }
 
@@ -52,6 +58,21 @@ impl TaskConfiguration {
     pub fn version(&self) -> u64 {
         self.version
     }
+    /// used by the CPU backend instead of the shader
+    pub fn cpu_runner(&self) -> Option<CpuRunner> {
+        self.cpu_runner
+    }
+    pub fn readback_mode(&self) -> ReadbackMode {
+        self.readback_mode
+    }
+    /// does not change the config version, the pipelines do not depend on it
+    pub fn set_readback_mode(&mut self, readback_mode: ReadbackMode) {
+        assert!(
+            readback_mode != ReadbackMode::Async { max_in_flight: 0 },
+            "At least one run has to be able to wait for its outputs, max_in_flight cannot be 0"
+        );
+        self.readback_mode = readback_mode;
+    }
     /// make sure you are actually changing the max lengths when you call this, otherwise the config version will be updated and cause unecessary recalculation of pipeline consts
     pub fn outputs_mut(&mut self) -> &mut OutputSpec {
         self.version += 1;
@@ -2,4 +2,5 @@ pub mod input_spec;
 pub mod iteration_space;
 pub mod lib;
 pub mod output_spec;
+pub mod readback_mode;
 pub mod wgsl_code;
@@ -16,6 +16,18 @@ impl OutputSpec {
     pub fn arrays(&self) -> &Vec<OutputTypeMetadata> {
         &self.arrays
     }
+    /// Only output vecs have a counter buffer, so the index of an output's counter buffer skips the output arrays before it. `None` for output arrays.
+    pub fn count_buffer_index(&self, output_index: usize) -> Option<usize> {
+        if !self.arrays.get(output_index)?.include_count {
+            return None;
+        }
+        Some(
+            self.arrays[..output_index]
+                .iter()
+                .filter(|a| a.include_count)
+                .count(),
+        )
+    }
     pub fn max_lengths(&self) -> &MaxOutputLengths {
         &self.max_lengths
     }
@@ -24,3 +36,38 @@ impl OutputSpec {
         self.max_lengths = new_max_lengths;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_gpu_compute_core::wgsl::shader_custom_type_name::ShaderCustomTypeName;
+
+    use super::*;
+
+    fn output(name: &str, include_count: bool) -> OutputTypeMetadata {
+        OutputTypeMetadata {
+            bytes: 4,
+            binding_number: 0,
+            include_count,
+            count_binding_number: None,
+            name: ShaderCustomTypeName::new(name),
+        }
+    }
+
+    #[test]
+    fn test_count_buffer_index_with_output_arrays_and_vecs() {
+        let spec = OutputSpec::new(
+            vec![
+                output("Area", false),
+                output("Large", true),
+                output("Scaled", false),
+                output("Big", true),
+            ],
+            MaxOutputLengths::empty(),
+        );
+        assert_eq!(spec.count_buffer_index(0), None);
+        assert_eq!(spec.count_buffer_index(1), Some(0));
+        assert_eq!(spec.count_buffer_index(2), None);
+        assert_eq!(spec.count_buffer_index(3), Some(1));
+        assert_eq!(spec.count_buffer_index(4), None);
+    }
+}
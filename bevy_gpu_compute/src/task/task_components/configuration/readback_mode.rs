@@ -0,0 +1,12 @@
+/// How the outputs of a run get back from the GPU. Only changes anything on the wgpu backend, the CPU backend always has the outputs right after the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadbackMode {
+    /// `Run` waits until the GPU is done, so the outputs can be read right after it
+    #[default]
+    Blocking,
+    /**
+    `Run` only submits the work, and the outputs can be read once the GPU is done, usually one or two frames later. Until then `GpuTaskReader::latest_results` returns an error.
+    At most `max_in_flight` runs wait for their outputs at the same time, running the task again once that many are waiting blocks until the oldest one is done.
+    */
+    Async { max_in_flight: usize },
+}
@@ -1,17 +1,21 @@
-use bevy::render::renderer::RenderDevice;
-use wgpu::{ShaderModule, ShaderModuleDescriptor, ShaderSource};
+use bevy_gpu_compute_core::{
+    IterSpaceDimmension, wgsl::shader_module::complete_shader_module::WgslShaderModule,
+};
+use pollster::FutureExt;
+use wgpu::{Device, ErrorFilter, ShaderModule, ShaderModuleDescriptor, ShaderSource};
 
 #[derive(Debug)]
 pub struct WgslCode {
     code: String,
-    entry_point_function_name: String,
+    /// the first one is the default, dispatched by `GpuTaskCommands::run`
+    entry_point_function_names: Vec<String>,
     shader_module: Option<ShaderModule>,
 }
 impl Default for WgslCode {
     fn default() -> Self {
         Self {
             code: "".to_string(),
-            entry_point_function_name: "".to_string(),
+            entry_point_function_names: vec![],
             shader_module: None,
         }
     }
@@ -20,33 +24,100 @@ impl Default for WgslCode {
 impl WgslCode {
     pub fn from_string(
         label: &str,
-        render_device: &RenderDevice,
+        device: &Device,
         wgsl_code: String,
         entry_point_function_name: String,
     ) -> Self {
+        Self::from_string_with_entry_points(
+            label,
+            device,
+            wgsl_code,
+            vec![entry_point_function_name],
+        )
+    }
+    /// for shaders with more than one entry point, the first one is the default
+    pub fn from_string_with_entry_points(
+        label: &str,
+        device: &Device,
+        wgsl_code: String,
+        entry_point_function_names: Vec<String>,
+    ) -> Self {
+        assert!(
+            !entry_point_function_names.is_empty(),
+            "At least one entry point is required for shader {}",
+            label
+        );
         Self {
             code: wgsl_code.clone(),
-            entry_point_function_name,
-            shader_module: Some(render_device.create_shader_module(ShaderModuleDescriptor {
+            entry_point_function_names,
+            shader_module: Some(device.create_shader_module(ShaderModuleDescriptor {
                 label: Some(label),
                 source: ShaderSource::Wgsl(wgsl_code.into()),
             })),
         }
     }
+    /// For shaders generated with `#[wgsl_shader_module]`. If the shader fails to compile, the error points at the rust code that each reported line of wgsl was generated from.
+    pub fn from_shader_module(
+        label: &str,
+        device: &Device,
+        wgsl_shader_module: &WgslShaderModule,
+        iter_space_dimmensions: IterSpaceDimmension,
+    ) -> Self {
+        let wgsl_code = wgsl_shader_module.wgsl_code(iter_space_dimmensions);
+        let entry_point_function_names = wgsl_shader_module.user_portion.entry_point_names();
+        assert!(
+            !entry_point_function_names.is_empty(),
+            "At least one entry point is required for shader {}",
+            label
+        );
+        device.push_error_scope(ErrorFilter::Validation);
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some(label),
+            source: ShaderSource::Wgsl(wgsl_code.clone().into()),
+        });
+        if let Some(error) = device.pop_error_scope().block_on() {
+            panic!(
+                "Failed to create shader {}: {}",
+                label,
+                wgsl_shader_module
+                    .add_rust_locations_to_error(iter_space_dimmensions, &error.to_string())
+            );
+        }
+        Self {
+            code: wgsl_code,
+            entry_point_function_names,
+            shader_module: Some(shader_module),
+        }
+    }
+    /// For tasks on the CPU backend, which run the rust version of the shader module, so the WGSL is only kept for reference and never compiled
+    pub fn from_shader_module_for_cpu(
+        wgsl_shader_module: &WgslShaderModule,
+        iter_space_dimmensions: IterSpaceDimmension,
+    ) -> Self {
+        Self {
+            code: wgsl_shader_module.wgsl_code(iter_space_dimmensions),
+            entry_point_function_names: wgsl_shader_module.user_portion.entry_point_names(),
+            shader_module: None,
+        }
+    }
     pub fn from_file(
         label: &str,
-        render_device: &RenderDevice,
+        device: &Device,
         file_path: &str,
         entry_point_function_name: String,
     ) -> Self {
         let code = std::fs::read_to_string(file_path).unwrap();
-        Self::from_string(label, render_device, code, entry_point_function_name)
+        Self::from_string(label, device, code, entry_point_function_name)
     }
     pub fn code(&self) -> &str {
         &self.code
     }
+    /// the default entry point
     pub fn entry_point_function_name(&self) -> &str {
-        &self.entry_point_function_name
+        &self.entry_point_function_names[0]
+    }
+    pub fn entry_point_function_names(&self) -> &[String] {
+        &self.entry_point_function_names
     }
     pub fn shader_module(&self) -> &ShaderModule {
         assert!(
@@ -48,18 +48,14 @@ impl TaskData {
     pub fn set_input_and_check_lengths_changed(&mut self, input: TypeErasedArrayInputData) -> bool {
         let mut lengths_changed = false;
         let lengths = InputArrayDataLengths::new(input.get_lengths().clone());
-        if self.input_lengths.is_none() {
-            self.input_lengths = Some(lengths);
-            lengths_changed = true;
-        } else {
-            let new_hash = self
-                .input_lengths
-                .as_mut()
-                .unwrap()
-                .update_and_return_new_hash_if_changed(lengths);
+        if let Some(input_lengths) = self.input_lengths.as_mut() {
+            let new_hash = input_lengths.update_and_return_new_hash_if_changed(lengths);
             if new_hash.is_some() {
                 lengths_changed = true;
             }
+        } else {
+            self.input_lengths = Some(lengths);
+            lengths_changed = true;
         }
         self.input = Some(input);
         lengths_changed
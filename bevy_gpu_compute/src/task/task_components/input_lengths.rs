@@ -19,7 +19,10 @@ impl InputArrayDataLengths {
     }
     pub fn hash_map(map: &HashMap<String, usize>) -> u64 {
         let mut hasher = DefaultHasher::new();
-        for (key, value) in map {
+        // sorted, since the iteration order differs between maps with the same entries
+        let mut entries: Vec<_> = map.iter().collect();
+        entries.sort();
+        for (key, value) in entries {
             key.hash(&mut hasher);
             value.hash(&mut hasher);
         }
@@ -45,3 +48,24 @@ impl InputArrayDataLengths {
         self.hash
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_does_not_depend_on_insertion_order() {
+        let entry = |i: usize| (format!("Input{}", i), i);
+        let forwards: HashMap<String, usize> = (0..16).map(entry).collect();
+        let backwards: HashMap<String, usize> = (0..16).rev().map(entry).collect();
+        assert_eq!(
+            InputArrayDataLengths::hash_map(&forwards),
+            InputArrayDataLengths::hash_map(&backwards)
+        );
+        let mut lengths = InputArrayDataLengths::new(forwards);
+        assert_eq!(
+            lengths.update_and_return_new_hash_if_changed(InputArrayDataLengths::new(backwards)),
+            None
+        );
+    }
+}
@@ -1,11 +1,4 @@
-use bevy::{
-    log,
-    render::{
-        render_resource::{BindGroup, BindGroupLayout},
-        renderer::RenderDevice,
-    },
-};
-use wgpu::PipelineLayout;
+use wgpu::{BindGroup, BindGroupLayout, Device, PipelineLayout};
 
 use crate::task::{
     compute_pipeline::pipeline_cache::PipelineLruCache,
@@ -23,8 +16,10 @@ pub struct TaskRuntimeState {
     max_output_bytes: MaxOutputBytes,
     pipeline_cache: PipelineLruCache,
     bind_group: Option<BindGroup>,
-    bind_group_layout: BindGroupLayout,
-    pipeline_layout: PipelineLayout,
+    /// only created by the wgpu backend
+    bind_group_layout: Option<BindGroupLayout>,
+    /// only created by the wgpu backend
+    pipeline_layout: Option<PipelineLayout>,
 }
 
 impl TaskRuntimeState {
@@ -34,8 +29,8 @@ impl TaskRuntimeState {
         max_output_bytes: MaxOutputBytes,
         pipeline_cache: PipelineLruCache,
         bind_group: Option<BindGroup>,
-        bind_group_layout: BindGroupLayout,
-        pipeline_layout: PipelineLayout,
+        bind_group_layout: Option<BindGroupLayout>,
+        pipeline_layout: Option<PipelineLayout>,
     ) -> Self {
         TaskRuntimeState {
             workgroup_sizes,
@@ -69,10 +64,14 @@ impl TaskRuntimeState {
         &mut self.bind_group
     }
     pub fn bind_group_layout(&self) -> &BindGroupLayout {
-        &self.bind_group_layout
+        self.bind_group_layout
+            .as_ref()
+            .expect("Only tasks on the wgpu backend have a bind group layout")
     }
     pub fn pipeline_layout(&self) -> &PipelineLayout {
-        &self.pipeline_layout
+        self.pipeline_layout
+            .as_ref()
+            .expect("Only tasks on the wgpu backend have a pipeline layout")
     }
     pub fn _internal_set_max_output_bytes(&mut self, new_max_output_bytes: MaxOutputBytes) {
         self.max_output_bytes = new_max_output_bytes;
@@ -87,18 +86,19 @@ impl TaskRuntimeState {
 
 pub struct TaskRuntimeStateBuilder<'a> {
     task_name: &'a str,
-    render_device: &'a RenderDevice,
+    /// without a render device, as on the CPU backend, no GPU resources are created
+    device: Option<&'a Device>,
     task_configuration: &'a TaskConfiguration,
 }
 
 impl<'a> TaskRuntimeStateBuilder<'a> {
     pub fn new(
-        render_device: &'a RenderDevice,
+        device: Option<&'a Device>,
         task_name: &'a str,
         task_configuration: &'a TaskConfiguration,
     ) -> Self {
         TaskRuntimeStateBuilder {
-            render_device,
+            device,
             task_name,
             task_configuration,
         }
@@ -116,7 +116,13 @@ impl<'a> TaskRuntimeStateBuilder<'a> {
         );
         let pipeline_cache = PipelineLruCache::default();
         let bind_group = None;
-        let (bind_group_layout, pipeline_layout) = self.setup_static_runtime_state();
+        let (bind_group_layout, pipeline_layout) = match self.device {
+            Some(device) => {
+                let (bind_group_layout, pipeline_layout) = self.setup_static_runtime_state(device);
+                (Some(bind_group_layout), Some(pipeline_layout))
+            }
+            None => (None, None),
+        };
         TaskRuntimeState::new(
             workgroup_sizes,
             workgroup_space,
@@ -127,21 +133,27 @@ impl<'a> TaskRuntimeStateBuilder<'a> {
             pipeline_layout,
         )
     }
-    pub fn setup_static_runtime_state(&mut self) -> (BindGroupLayout, PipelineLayout) {
-        let bind_group_layout = self.get_bind_group_layouts();
-        let pipeline_layout = self.get_pipeline_layout(&bind_group_layout);
+    pub fn setup_static_runtime_state(
+        &mut self,
+        device: &Device,
+    ) -> (BindGroupLayout, PipelineLayout) {
+        let bind_group_layout = self.get_bind_group_layouts(device);
+        let pipeline_layout = self.get_pipeline_layout(device, &bind_group_layout);
         (bind_group_layout, pipeline_layout)
     }
 
-    fn get_pipeline_layout(&self, bind_group_layout: &BindGroupLayout) -> PipelineLayout {
-        self.render_device
-            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some(self.task_name),
-                bind_group_layouts: &[bind_group_layout],
-                push_constant_ranges: &[],
-            })
-    }
-    fn get_bind_group_layouts(&self) -> BindGroupLayout {
+    fn get_pipeline_layout(
+        &self,
+        device: &Device,
+        bind_group_layout: &BindGroupLayout,
+    ) -> PipelineLayout {
+        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(self.task_name),
+            bind_group_layouts: &[bind_group_layout],
+            push_constant_ranges: &[],
+        })
+    }
+    fn get_bind_group_layouts(&self, device: &Device) -> BindGroupLayout {
         let mut layouts = Vec::new();
         self.task_configuration
             .inputs()
@@ -184,8 +196,10 @@ impl<'a> TaskRuntimeStateBuilder<'a> {
                 }
             });
         log::debug!("Bind group layouts: {:?}", layouts);
-        self.render_device
-            .create_bind_group_layout(Some(self.task_name), &layouts)
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(self.task_name),
+            entries: &layouts,
+        })
     }
 
     fn create_bind_group_layout_entry(
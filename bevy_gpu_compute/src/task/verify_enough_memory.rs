@@ -1,12 +1,23 @@
-use bevy::log;
-
-use crate::ram_limit::RamLimit;
+use crate::{ram_limit::RamLimit, task::outputs::async_readback::staging_pool_capacity};
 
 use super::lib::BevyGpuComputeTask;
 
+/// Panics if the outputs of all tasks, and their staging buffers, would not fit in memory
 pub fn verify_have_enough_memory(tasks: &Vec<&BevyGpuComputeTask>, ram_limit: &RamLimit) {
+    if let Err(error) = check_have_enough_memory(tasks, ram_limit) {
+        panic!("{}", error);
+    }
+}
+
+/// Like `verify_have_enough_memory`, but returns the error, for the runs that report it through their `TaskOutputFuture`
+pub fn check_have_enough_memory(
+    tasks: &Vec<&BevyGpuComputeTask>,
+    ram_limit: &RamLimit,
+) -> Result<(), String> {
+    // the outputs themselves, and the staging buffers that each run that waits for its outputs copies them to
     let total_bytes = tasks.iter().fold(0, |sum, task| {
-        sum + task.runtime_state().max_output_bytes().get()
+        let staging_sets = staging_pool_capacity(task.configuration().readback_mode());
+        sum + task.runtime_state().max_output_bytes().get() * (1 + staging_sets)
     });
     let available_memory = ram_limit.total_mem;
     if total_bytes as f32 > available_memory as f32 * 0.9 {
@@ -15,7 +26,7 @@ pub fn verify_have_enough_memory(tasks: &Vec<&BevyGpuComputeTask>, ram_limit: &R
             available_memory as f32 / 1024.0 / 1024.0 / 1024.0,
             total_bytes as f32 / 1024.0 / 1024.0 / 1024.0
         );
-
-        panic!("Not enough memory to store all gpu compute task outputs");
+        return Err("Not enough memory to store all gpu compute task outputs".into());
     }
+    Ok(())
 }
@@ -0,0 +1,356 @@
+/*!
+Helpers for testing shader modules.
+
+`run_task_once` runs a task on the GPU without a bevy app, and returns its outputs:
+```ignore
+let results = run_task_once::<my_shader::Types, my_shader::OutputDataBuilder>(
+    my_shader::parsed(),
+    IterationSpace::new(64, 1, 1),
+    my_shader::MaxOutputLengthsBuilder::new().set_result(64).finish(),
+    my_shader::ConfigInputDataBuilder::new().finish(),
+    my_shader::InputDataBuilder::new().set_value(values).finish(),
+);
+```
+
+`DifferentialTest` runs a shader module as a task, on the GPU if there is one, and compares the results with the CPU version of the module, to catch places where the WGSL does not do what the rust code does.
+
+```ignore
+#[test]
+fn my_shader_matches_the_cpu() {
+    let mut app = App::new();
+    app.add_plugins((DefaultPlugins, BevyGpuComputePlugin::default()));
+    DifferentialTest::<my_shader::Types>::new(
+        my_shader::parsed(),
+        IterationSpace::new(64, 1, 1),
+        my_shader::MaxOutputLengthsBuilder::new().set_result(64).finish(),
+    )
+    .run(&mut app)
+    .assert_passed();
+}
+```
+*/
+use std::sync::OnceLock;
+#[cfg(feature = "bevy")]
+use std::{fmt, marker::PhantomData};
+
+#[cfg(feature = "bevy")]
+use bevy::{
+    app::{App, PluginsState},
+    ecs::system::RunSystemOnce,
+    prelude::Res,
+};
+#[cfg(feature = "bevy")]
+use bevy_gpu_compute_core::{CpuSchedule, TypeErasedArrayOutputData};
+use bevy_gpu_compute_core::{
+    MaxOutputLengths, OutputDataBuilderTrait, TypeErasedArrayInputData, TypeErasedConfigInputData,
+    TypesSpec, wgsl::shader_module::user_defined_portion::WgslShaderModuleUserPortion,
+};
+
+#[cfg(feature = "bevy")]
+use crate::prelude::{
+    BevyGpuComputeTaskCreator, BevyGpuComputeTaskDeleter, GpuTaskReader, GpuTaskRunner,
+};
+use crate::{
+    backend::{ActiveComputeBackend, ComputeBackendKind, wgpu_backend::WgpuBackend},
+    prelude::{GpuComputeContext, IterationSpace},
+};
+
+pub use bevy_gpu_compute_core::{
+    CompareFields, FieldDifference, FloatTolerance, RandomFields, SplitMix64,
+};
+
+#[cfg(feature = "bevy")]
+const TASK_NAME: &str = "bevy_gpu_compute_differential_test";
+
+/// The backend that `run_task_once` runs tasks on: a headless wgpu device that is created on first use, see `WgpuBackend::headless`. `None` if wgpu finds no adapter, not even a software one.
+pub fn headless_backend() -> Option<&'static ActiveComputeBackend> {
+    static BACKEND: OnceLock<Option<ActiveComputeBackend>> = OnceLock::new();
+    BACKEND
+        .get_or_init(|| WgpuBackend::headless().map(ActiveComputeBackend::new))
+        .as_ref()
+}
+
+/**
+Runs a task once on the GPU and returns its outputs, without a bevy app or window. The task runs in a `GpuComputeContext` on the `headless_backend`, and is dropped again afterwards.
+Meant for tests and offline tools. To run a task more than once, create it once in a `GpuComputeContext`, or with `BevyGpuComputeTaskCreator` in an app, so its buffers and pipelines are reused.
+
+# Panics
+If wgpu finds no adapter. Use `run_task_once_on` with `ComputeBackendKind::Cpu` to run the task on the CPU instead.
+*/
+pub fn run_task_once<Types: TypesSpec, OutputDataBuilder: OutputDataBuilderTrait>(
+    shader: WgslShaderModuleUserPortion,
+    iteration_space: IterationSpace,
+    max_output_lengths: MaxOutputLengths,
+    config_inputs: TypeErasedConfigInputData,
+    input_data: TypeErasedArrayInputData,
+) -> OutputDataBuilder {
+    run_task_once_on::<Types, OutputDataBuilder>(
+        ComputeBackendKind::Wgpu,
+        shader,
+        iteration_space,
+        max_output_lengths,
+        config_inputs,
+        input_data,
+    )
+}
+
+/// Like `run_task_once`, but on the given backend, so tests can pick the CPU themselves when there is no GPU
+pub fn run_task_once_on<Types: TypesSpec, OutputDataBuilder: OutputDataBuilderTrait>(
+    backend: ComputeBackendKind,
+    shader: WgslShaderModuleUserPortion,
+    iteration_space: IterationSpace,
+    max_output_lengths: MaxOutputLengths,
+    config_inputs: TypeErasedConfigInputData,
+    input_data: TypeErasedArrayInputData,
+) -> OutputDataBuilder {
+    let mut context = match backend {
+        ComputeBackendKind::Wgpu => GpuComputeContext::from_backend(
+            headless_backend()
+                .expect("wgpu found no GPU adapter, not even a software one. Install one, like lavapipe or llvmpipe, or run the task with `run_task_once_on(ComputeBackendKind::Cpu, ...)`")
+                .clone(),
+        ),
+        ComputeBackendKind::Cpu => GpuComputeContext::cpu(),
+    };
+    context.create_task::<Types>("run_task_once", shader, iteration_space, max_output_lengths);
+    context.set_config_inputs("run_task_once", config_inputs);
+    context.set_inputs("run_task_once", input_data);
+    context.run("run_task_once");
+    context
+        .read("run_task_once")
+        .expect("The task produced no output")
+}
+
+/// Generates the inputs of a test case
+pub type InputGenerator =
+    Box<dyn FnMut(&mut TestRng) -> (TypeErasedConfigInputData, TypeErasedArrayInputData)>;
+
+/// The random number generator that test inputs are generated with. It is deterministic, so failing test cases can be reproduced with the same seed. `rng.random::<T>()` gives a value of any input type, see `RandomFields`.
+pub type TestRng = SplitMix64;
+
+#[cfg(feature = "bevy")]
+/// Runs a shader module on the active compute backend and on the CPU with the same inputs, and compares the outputs field by field
+pub struct DifferentialTest<Types: TypesSpec> {
+    shader: WgslShaderModuleUserPortion,
+    iteration_space: IterationSpace,
+    max_output_lengths: MaxOutputLengths,
+    tolerance: FloatTolerance,
+    cases: usize,
+    seed: u64,
+    input_length: Option<usize>,
+    inputs: Option<InputGenerator>,
+    _types: PhantomData<Types>,
+}
+
+#[cfg(feature = "bevy")]
+impl<Types: TypesSpec> DifferentialTest<Types> {
+    pub fn new(
+        shader: WgslShaderModuleUserPortion,
+        iteration_space: IterationSpace,
+        max_output_lengths: MaxOutputLengths,
+    ) -> Self {
+        DifferentialTest {
+            shader,
+            iteration_space,
+            max_output_lengths,
+            tolerance: FloatTolerance::default(),
+            cases: 16,
+            seed: 0,
+            input_length: None,
+            inputs: None,
+            _types: PhantomData,
+        }
+    }
+    /// how far floats may be apart, `FloatTolerance::default()` if not set
+    pub fn with_tolerance(mut self, tolerance: FloatTolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+    /// how many sets of random inputs to test with, 16 if not set
+    pub fn with_cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    /// the length of the generated input arrays, one value per position of the iteration space if not set
+    pub fn with_input_length(mut self, input_length: usize) -> Self {
+        self.input_length = Some(input_length);
+        self
+    }
+    /// Generates the inputs yourself, for shaders that need inputs in certain ranges, like valid indices
+    pub fn with_inputs(
+        mut self,
+        inputs: impl FnMut(&mut TestRng) -> (TypeErasedConfigInputData, TypeErasedArrayInputData)
+        + 'static,
+    ) -> Self {
+        self.inputs = Some(Box::new(inputs));
+        self
+    }
+
+    /**
+    Creates a task from the shader in `app`, runs every test case on it and on the CPU, and deletes the task again.
+    The app needs the `BevyGpuComputePlugin`. The task runs on bevy's render device, or without one on a device that the plugin creates. Only if wgpu finds no adapter at all does it run on the CPU backend, which only tests the type erased data paths, and `DifferentialTestReport::backend` says so.
+    */
+    pub fn run(&mut self, app: &mut App) -> DifferentialTestReport {
+        if app.plugins_state() == PluginsState::Ready {
+            app.finish();
+            app.cleanup();
+        }
+        let backend = app
+            .world_mut()
+            .run_system_once(|backend: Res<ActiveComputeBackend>| backend.kind())
+            .expect("The BevyGpuComputePlugin has to be added to the app");
+        let shader = self.shader.clone();
+        let iteration_space = self.iteration_space;
+        let max_output_lengths = self.max_output_lengths.clone();
+        let entry_point = self
+            .shader
+            .entry_points()
+            .next()
+            .expect("The shader module has no entry point")
+            .name
+            .clone();
+        app.world_mut()
+            .run_system_once(move |mut task_creator: BevyGpuComputeTaskCreator| {
+                task_creator.create_task_from_rust_shader::<Types>(
+                    TASK_NAME,
+                    shader.clone(),
+                    iteration_space,
+                    max_output_lengths.clone(),
+                );
+            })
+            .unwrap();
+        // creates the output buffers
+        app.world_mut()
+            .run_system_once(|mut task_runner: GpuTaskRunner| {
+                let commands = task_runner.task(TASK_NAME).mutate(None, None);
+                task_runner.run_commands(commands);
+            })
+            .unwrap();
+        let mut rng = TestRng::new(self.seed);
+        let mut failures = Vec::new();
+        for case in 0..self.cases {
+            let (config_inputs, input_data) = match &mut self.inputs {
+                Some(inputs) => inputs(&mut rng),
+                None => self.random_inputs(&mut rng),
+            };
+            let expected = Types::run_entry_points_on_cpu(
+                std::slice::from_ref(&entry_point),
+                self.iteration_space,
+                &config_inputs,
+                &input_data,
+                &self.max_output_lengths,
+                &CpuSchedule::default(),
+            );
+            app.world_mut()
+                .run_system_once(move |mut task_runner: GpuTaskRunner| {
+                    let commands = task_runner
+                        .task(TASK_NAME)
+                        .set_config_inputs(copy_config_inputs(&config_inputs))
+                        .set_inputs(copy_input_data(&input_data))
+                        .run();
+                    task_runner.run_commands(commands);
+                })
+                .unwrap();
+            let actual = app
+                .world_mut()
+                .run_system_once(|mut task_reader: GpuTaskReader| {
+                    task_reader.latest_results::<TypeErasedArrayOutputData>(TASK_NAME)
+                })
+                .unwrap()
+                .expect("The task produced no output");
+            let differences = Types::compare_outputs(&expected, &actual, &self.tolerance);
+            if !differences.is_empty() {
+                failures.push(DifferentialTestFailure { case, differences });
+            }
+        }
+        app.world_mut()
+            .run_system_once(|mut task_deleter: BevyGpuComputeTaskDeleter| {
+                task_deleter.delete(TASK_NAME);
+            })
+            .unwrap();
+        DifferentialTestReport {
+            backend,
+            seed: self.seed,
+            cases: self.cases,
+            failures,
+        }
+    }
+
+    /// random values for every config input and input array, see `TypesSpec::random_inputs`
+    fn random_inputs(
+        &self,
+        rng: &mut TestRng,
+    ) -> (TypeErasedConfigInputData, TypeErasedArrayInputData) {
+        let input_length = self.input_length.unwrap_or(
+            self.iteration_space.x() * self.iteration_space.y() * self.iteration_space.z(),
+        );
+        Types::random_inputs(rng, input_length)
+    }
+}
+
+#[cfg(feature = "bevy")]
+fn copy_config_inputs(config_inputs: &TypeErasedConfigInputData) -> TypeErasedConfigInputData {
+    TypeErasedConfigInputData::new(config_inputs.get_map().clone())
+}
+
+#[cfg(feature = "bevy")]
+fn copy_input_data(input_data: &TypeErasedArrayInputData) -> TypeErasedArrayInputData {
+    TypeErasedArrayInputData::new(
+        input_data.get_map().clone(),
+        input_data.get_lengths().clone(),
+    )
+}
+
+#[cfg(feature = "bevy")]
+/// A test case whose outputs differed
+#[derive(Debug, Clone)]
+pub struct DifferentialTestFailure {
+    pub case: usize,
+    pub differences: Vec<FieldDifference>,
+}
+
+#[cfg(feature = "bevy")]
+/// What `DifferentialTest::run` found
+#[derive(Debug, Clone)]
+pub struct DifferentialTestReport {
+    /// the backend the task ran on, if this is `Cpu` the CPU version was only compared with itself
+    pub backend: ComputeBackendKind,
+    pub seed: u64,
+    pub cases: usize,
+    pub failures: Vec<DifferentialTestFailure>,
+}
+
+#[cfg(feature = "bevy")]
+impl DifferentialTestReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+    /// panics with the report if any test case failed
+    pub fn assert_passed(&self) {
+        assert!(self.passed(), "{}", self);
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl fmt::Display for DifferentialTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} cases differed between the {:?} backend and the CPU version (seed {})",
+            self.failures.len(),
+            self.cases,
+            self.backend,
+            self.seed
+        )?;
+        for failure in &self.failures {
+            writeln!(f, "case {}:", failure.case)?;
+            for difference in &failure.differences {
+                writeln!(f, "- {}", difference)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use bevy::{
+    MinimalPlugins,
+    app::{App, Startup, Update},
+    ecs::system::RunSystemOnce,
+    prelude::{IntoSystemConfigs, ResMut, Resource},
+    state::app::StatesPlugin,
+    tasks::{AsyncComputeTaskPool, Task, block_on},
+};
+use bevy_gpu_compute::prelude::*;
+
+#[wgsl_shader_module]
+mod scale_module {
+    use bevy_gpu_compute::prelude::*;
+
+    #[wgsl_config]
+    pub struct Scale {
+        pub factor: f32,
+    }
+    #[wgsl_input_array]
+    type Radius = f32;
+    #[wgsl_output_array]
+    type Scaled = f32;
+    #[wgsl_output_vec]
+    pub struct Big {
+        pub index: u32,
+    }
+    pub fn main(iter_pos: WgslIterationPosition) {
+        if iter_pos.x >= WgslVecInput::vec_len::<Radius>() {
+            return;
+        }
+        let scaled =
+            WgslVecInput::vec_val::<Radius>(iter_pos.x) * WgslConfigInput::get::<Scale>().factor;
+        WgslOutput::set::<Scaled>(iter_pos.x, scaled);
+        if scaled > 3.0 {
+            WgslOutput::push::<Big>(Big { index: iter_pos.x });
+        }
+    }
+    #[wgsl_entry]
+    pub fn double(iter_pos: WgslIterationPosition) {
+        if iter_pos.x >= WgslVecInput::vec_len::<Radius>() {
+            return;
+        }
+        WgslOutput::set::<Scaled>(
+            iter_pos.x,
+            WgslVecInput::vec_val::<Radius>(iter_pos.x) * 2.0,
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+struct Results(Vec<scale_module::OutputDataBuilder>);
+
+fn create_task(mut task_creator: BevyGpuComputeTaskCreator) {
+    task_creator.create_task_from_rust_shader::<scale_module::Types>(
+        "scale",
+        scale_module::parsed(),
+        IterationSpace::new(4, 1, 1),
+        scale_module::MaxOutputLengthsBuilder::new()
+            .set_scaled(4)
+            .set_big(4)
+            .finish(),
+    );
+}
+
+fn run_task(mut task_runner: GpuTaskRunner) {
+    let config = scale_module::ConfigInputDataBuilder::new()
+        .set_scale(scale_module::Scale { factor: 2.0 })
+        .into();
+    let inputs = scale_module::InputDataBuilder::new()
+        .set_radius(vec![1.0, 2.0, 3.0])
+        .into();
+    let commands = task_runner
+        .task("scale")
+        .set_config_inputs(config)
+        .set_inputs(inputs)
+        .run();
+    task_runner.run_commands(commands);
+}
+
+fn read_results(mut task_reader: GpuTaskReader, mut results: ResMut<Results>) {
+    if let Ok(r) = task_reader.latest_results::<scale_module::OutputDataBuilder>("scale") {
+        results.0.push(r);
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin))
+        .add_plugins(BevyGpuComputePlugin::default().with_backend(ComputeBackendKind::Cpu))
+        .init_resource::<Results>()
+        .add_systems(Startup, create_task);
+    app
+}
+
+#[test]
+fn test_cpu_backend_runs_tasks_without_a_gpu() {
+    let mut app = app();
+    app.add_systems(Update, (run_task, read_results).chain());
+    app.update();
+    app.update();
+    assert_eq!(
+        app.world().resource::<ActiveComputeBackend>().kind(),
+        ComputeBackendKind::Cpu
+    );
+    let results = &app.world().resource::<Results>().0;
+    assert_eq!(results.len(), 2);
+    for r in results {
+        assert_eq!(r.scaled, Some(vec![2.0, 4.0, 6.0, 0.0]));
+        let mut big: Vec<u32> = r.big.as_ref().unwrap().iter().map(|b| b.index).collect();
+        big.sort();
+        assert_eq!(big, vec![1, 2]);
+    }
+}
+
+#[test]
+fn test_default_backend_without_render_plugin_creates_its_own_device() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin))
+        .add_plugins(BevyGpuComputePlugin::default());
+    app.finish();
+    let expected = if GpuComputeContext::headless().is_some() {
+        ComputeBackendKind::Wgpu
+    } else {
+        ComputeBackendKind::Cpu
+    };
+    assert_eq!(
+        app.world().resource::<ActiveComputeBackend>().kind(),
+        expected
+    );
+}
+
+#[test]
+fn test_cpu_backend_runs_entry_points_in_order() {
+    let mut app = app();
+    app.add_systems(
+        Update,
+        (
+            |mut task_runner: GpuTaskRunner| {
+                let config = scale_module::ConfigInputDataBuilder::new()
+                    .set_scale(scale_module::Scale { factor: 3.0 })
+                    .into();
+                let inputs = scale_module::InputDataBuilder::new()
+                    .set_radius(vec![1.0, 2.0])
+                    .into();
+                let commands = task_runner
+                    .task("scale")
+                    .set_config_inputs(config)
+                    .set_inputs(inputs)
+                    .mutate(
+                        None,
+                        Some(
+                            scale_module::MaxOutputLengthsBuilder::new()
+                                .set_scaled(2)
+                                .set_big(2)
+                                .finish(),
+                        ),
+                    )
+                    .run_entry_points(&["main", "double"]);
+                task_runner.run_commands(commands);
+            },
+            read_results,
+        )
+            .chain(),
+    );
+    app.update();
+    let results = &app.world().resource::<Results>().0;
+    // `double` overwrites what `main` set, but cannot undo its pushes
+    assert_eq!(results[0].scaled, Some(vec![2.0, 4.0]));
+    assert_eq!(results[0].big.as_ref().unwrap().len(), 1);
+}
+
+#[test]
+fn test_run_async_on_the_async_compute_task_pool() {
+    let mut app = app();
+    app.update();
+    let task: Task<Result<scale_module::OutputDataBuilder, String>> = app
+        .world_mut()
+        .run_system_once(|mut task_runner: GpuTaskRunner| {
+            let config = scale_module::ConfigInputDataBuilder::new()
+                .set_scale(scale_module::Scale { factor: 2.0 })
+                .into();
+            let commands = task_runner.task("scale").set_config_inputs(config);
+            task_runner.run_commands(commands);
+            let inputs = scale_module::InputDataBuilder::new()
+                .set_radius(vec![1.0, 2.0])
+                .into();
+            AsyncComputeTaskPool::get()
+                .spawn(task_runner.run_async::<scale_module::OutputDataBuilder>("scale", inputs))
+        })
+        .unwrap();
+    let results = block_on(task).unwrap();
+    assert_eq!(results.scaled, Some(vec![2.0, 4.0, 0.0, 0.0]));
+    assert_eq!(results.big.unwrap().len(), 1);
+}
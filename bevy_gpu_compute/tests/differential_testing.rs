@@ -0,0 +1,137 @@
+use bevy::{MinimalPlugins, app::App, state::app::StatesPlugin};
+use bevy_gpu_compute::{
+    prelude::*,
+    testing::{DifferentialTest, TestRng, run_task_once},
+};
+
+#[wgsl_shader_module]
+mod square_module {
+    use bevy_gpu_compute::prelude::*;
+
+    #[wgsl_config]
+    pub struct Threshold {
+        pub value: f32,
+    }
+    #[wgsl_input_array]
+    pub struct Circle {
+        pub center_x: f32,
+        pub radius: f32,
+    }
+    #[wgsl_output_array]
+    pub struct Area {
+        pub index: u32,
+        pub area: f32,
+    }
+    #[wgsl_output_vec]
+    type Large = u32;
+    pub fn main(iter_pos: WgslIterationPosition) {
+        if iter_pos.x >= WgslVecInput::vec_len::<Circle>() {
+            return;
+        }
+        let circle = WgslVecInput::vec_val::<Circle>(iter_pos.x);
+        let area = 4.0 * circle.radius * circle.radius;
+        WgslOutput::set::<Area>(
+            iter_pos.x,
+            Area {
+                index: iter_pos.x,
+                area,
+            },
+        );
+        if area > WgslConfigInput::get::<Threshold>().value {
+            WgslOutput::push::<Large>(iter_pos.x);
+        }
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        BevyGpuComputePlugin::default(),
+    ));
+    app
+}
+
+fn test(cases: usize) -> DifferentialTest<square_module::Types> {
+    DifferentialTest::new(
+        square_module::parsed(),
+        IterationSpace::new(16, 1, 1),
+        square_module::MaxOutputLengthsBuilder::new()
+            .set_area(16)
+            .set_large(16)
+            .finish(),
+    )
+    .with_cases(cases)
+}
+
+#[test]
+fn test_differential_test_with_random_inputs() {
+    let mut app = app();
+    let report = test(4).run(&mut app);
+    // there is no render device, so the plugin created its own, if wgpu found an adapter
+    let expected = if GpuComputeContext::headless().is_some() {
+        ComputeBackendKind::Wgpu
+    } else {
+        ComputeBackendKind::Cpu
+    };
+    assert_eq!(report.backend, expected);
+    assert_eq!(report.cases, 4);
+    report.assert_passed();
+}
+
+#[test]
+fn test_differential_test_with_generated_inputs() {
+    let mut app = app();
+    let report = test(2)
+        .with_inputs(|rng: &mut TestRng| {
+            let circles = (0..10)
+                .map(|_| square_module::Circle {
+                    center_x: rng.next_f32(),
+                    radius: rng.next_f32() * 10.0,
+                })
+                .collect();
+            (
+                square_module::ConfigInputDataBuilder::new()
+                    .set_threshold(square_module::Threshold { value: 50.0 })
+                    .into(),
+                square_module::InputDataBuilder::new()
+                    .set_circle(circles)
+                    .into(),
+            )
+        })
+        .run(&mut app);
+    report.assert_passed();
+    // the task is deleted again, so the same app can run another test
+    test(1).run(&mut app).assert_passed();
+}
+
+#[test]
+fn test_run_task_once() {
+    let results = run_task_once::<square_module::Types, square_module::OutputDataBuilder>(
+        square_module::parsed(),
+        IterationSpace::new(4, 1, 1),
+        square_module::MaxOutputLengthsBuilder::new()
+            .set_area(4)
+            .set_large(4)
+            .finish(),
+        square_module::ConfigInputDataBuilder::new()
+            .set_threshold(square_module::Threshold { value: 10.0 })
+            .into(),
+        square_module::InputDataBuilder::new()
+            .set_circle(vec![
+                square_module::Circle {
+                    center_x: 0.0,
+                    radius: 1.0,
+                },
+                square_module::Circle {
+                    center_x: 0.0,
+                    radius: 2.0,
+                },
+            ])
+            .into(),
+    );
+    let areas: Vec<f32> = results.area.unwrap().iter().map(|a| a.area).collect();
+    assert_eq!(areas, vec![4.0, 16.0, 0.0, 0.0]);
+    assert_eq!(results.large, Some(vec![1]));
+}
@@ -0,0 +1,370 @@
+use bevy_gpu_compute::{prelude::*, testing::run_task_once_on};
+
+#[wgsl_shader_module]
+mod scale_module {
+    use bevy_gpu_compute::prelude::*;
+
+    #[wgsl_config]
+    pub struct Scale {
+        pub factor: f32,
+    }
+    #[wgsl_input_array]
+    type Radius = f32;
+    #[wgsl_output_array]
+    type Scaled = f32;
+    #[wgsl_output_vec]
+    pub struct Big {
+        pub index: u32,
+    }
+    pub fn main(iter_pos: WgslIterationPosition) {
+        if iter_pos.x >= WgslVecInput::vec_len::<Radius>() {
+            return;
+        }
+        let scaled =
+            WgslVecInput::vec_val::<Radius>(iter_pos.x) * WgslConfigInput::get::<Scale>().factor;
+        WgslOutput::set::<Scaled>(iter_pos.x, scaled);
+        if scaled > 3.0 {
+            WgslOutput::push::<Big>(Big { index: iter_pos.x });
+        }
+    }
+    #[wgsl_entry]
+    pub fn double(iter_pos: WgslIterationPosition) {
+        if iter_pos.x >= WgslVecInput::vec_len::<Radius>() {
+            return;
+        }
+        WgslOutput::set::<Scaled>(
+            iter_pos.x,
+            WgslVecInput::vec_val::<Radius>(iter_pos.x) * 2.0,
+        );
+    }
+}
+
+/// more entry points than the pipeline cache holds
+#[wgsl_shader_module]
+mod many_entry_points_module {
+    use bevy_gpu_compute::prelude::*;
+
+    #[wgsl_input_array]
+    type Unused = u32;
+    #[wgsl_output_array]
+    type Step = u32;
+    pub fn main(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 0u32);
+    }
+    #[wgsl_entry]
+    pub fn set_1(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 1u32);
+    }
+    #[wgsl_entry]
+    pub fn set_2(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 2u32);
+    }
+    #[wgsl_entry]
+    pub fn set_3(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 3u32);
+    }
+    #[wgsl_entry]
+    pub fn set_4(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 4u32);
+    }
+    #[wgsl_entry]
+    pub fn set_5(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 5u32);
+    }
+    #[wgsl_entry]
+    pub fn set_6(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 6u32);
+    }
+    #[wgsl_entry]
+    pub fn set_7(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 7u32);
+    }
+    #[wgsl_entry]
+    pub fn set_8(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 8u32);
+    }
+    #[wgsl_entry]
+    pub fn set_9(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 9u32);
+    }
+    #[wgsl_entry]
+    pub fn set_10(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 10u32);
+    }
+    #[wgsl_entry]
+    pub fn set_11(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Step>(iter_pos.x, 11u32);
+    }
+}
+
+fn contexts() -> Vec<GpuComputeContext> {
+    let mut contexts = vec![GpuComputeContext::cpu()];
+    contexts.extend(GpuComputeContext::headless());
+    contexts
+}
+
+fn create_task(context: &mut GpuComputeContext, factor: f32, radii: Vec<f32>) {
+    context.create_task::<scale_module::Types>(
+        "scale",
+        scale_module::parsed(),
+        IterationSpace::new(4, 1, 1),
+        scale_module::MaxOutputLengthsBuilder::new()
+            .set_scaled(4)
+            .set_big(4)
+            .finish(),
+    );
+    context.set_config_inputs(
+        "scale",
+        scale_module::ConfigInputDataBuilder::new()
+            .set_scale(scale_module::Scale { factor })
+            .into(),
+    );
+    context.set_inputs(
+        "scale",
+        scale_module::InputDataBuilder::new()
+            .set_radius(radii)
+            .into(),
+    );
+}
+
+#[test]
+fn test_context_runs_tasks_without_bevy() {
+    for mut context in contexts() {
+        create_task(&mut context, 2.0, vec![1.0, 2.0, 3.0]);
+        context.run("scale");
+        let results = context
+            .read::<scale_module::OutputDataBuilder>("scale")
+            .unwrap();
+        assert_eq!(results.scaled, Some(vec![2.0, 4.0, 6.0, 0.0]));
+        let mut big: Vec<u32> = results.big.unwrap().iter().map(|b| b.index).collect();
+        big.sort();
+        assert_eq!(big, vec![1, 2], "on {:?}", context.backend().kind());
+        // the results are cleared once read
+        assert!(
+            context
+                .read::<scale_module::OutputDataBuilder>("scale")
+                .is_err()
+        );
+        context.delete_task("scale");
+        assert!(!context.has_task("scale"));
+    }
+}
+
+#[test]
+fn test_context_uses_config_inputs_set_between_runs() {
+    for mut context in contexts() {
+        create_task(&mut context, 2.0, vec![1.0, 2.0]);
+        context.run("scale");
+        context.set_config_inputs(
+            "scale",
+            scale_module::ConfigInputDataBuilder::new()
+                .set_scale(scale_module::Scale { factor: 3.0 })
+                .into(),
+        );
+        context.run("scale");
+        let results = context
+            .read::<scale_module::OutputDataBuilder>("scale")
+            .unwrap();
+        assert_eq!(
+            results.scaled,
+            Some(vec![3.0, 6.0, 0.0, 0.0]),
+            "on {:?}",
+            context.backend().kind()
+        );
+    }
+}
+
+#[test]
+fn test_context_mutates_and_runs_entry_points() {
+    for mut context in contexts() {
+        create_task(&mut context, 3.0, vec![1.0, 2.0]);
+        context.mutate(
+            "scale",
+            None,
+            Some(
+                scale_module::MaxOutputLengthsBuilder::new()
+                    .set_scaled(2)
+                    .set_big(2)
+                    .finish(),
+            ),
+        );
+        context.run_entry_points("scale", &["main", "double"]);
+        let results = context
+            .read::<scale_module::OutputDataBuilder>("scale")
+            .unwrap();
+        // `double` overwrites what `main` set, but cannot undo its pushes
+        assert_eq!(results.scaled, Some(vec![2.0, 4.0]));
+        assert_eq!(results.big.unwrap().len(), 1);
+    }
+}
+
+#[test]
+#[should_panic(
+    expected = "Entry point tripple not found in task scale, the available entry points are [\"main\", \"double\"]"
+)]
+fn test_context_rejects_unknown_entry_points() {
+    let mut context = GpuComputeContext::cpu();
+    create_task(&mut context, 1.0, vec![1.0]);
+    context.run_entry_points("scale", &["main", "tripple"]);
+}
+
+#[test]
+#[should_panic(expected = "Config input Scale has not been set for task scale")]
+fn test_cpu_context_checks_that_config_inputs_are_set() {
+    let mut context = GpuComputeContext::cpu();
+    create_task(&mut context, 1.0, vec![1.0]);
+    context.set_config_inputs("scale", scale_module::ConfigInputDataBuilder::new().into());
+    context.run("scale");
+}
+
+#[test]
+fn test_context_runs_more_entry_points_than_the_pipeline_cache_holds() {
+    for mut context in contexts() {
+        context.create_task::<many_entry_points_module::Types>(
+            "steps",
+            many_entry_points_module::parsed(),
+            IterationSpace::new(2, 1, 1),
+            many_entry_points_module::MaxOutputLengthsBuilder::new()
+                .set_step(2)
+                .finish(),
+        );
+        context.set_inputs(
+            "steps",
+            many_entry_points_module::InputDataBuilder::new()
+                .set_unused(vec![0, 0])
+                .into(),
+        );
+        let mut entry_points = vec![String::from("main")];
+        entry_points.extend((1..12).map(|i| format!("set_{}", i)));
+        let entry_points: Vec<&str> = entry_points.iter().map(|e| e.as_str()).collect();
+        context.run_entry_points("steps", &entry_points);
+        let results = context
+            .read::<many_entry_points_module::OutputDataBuilder>("steps")
+            .unwrap();
+        assert_eq!(
+            results.step,
+            Some(vec![11, 11]),
+            "on {:?}",
+            context.backend().kind()
+        );
+    }
+}
+
+#[test]
+fn test_context_reads_outputs_back_asynchronously() {
+    for mut context in contexts() {
+        create_task(&mut context, 1.0, vec![0.0]);
+        context.set_readback_mode("scale", ReadbackMode::Async { max_in_flight: 2 });
+        for radius in 1..=3 {
+            context.set_inputs(
+                "scale",
+                scale_module::InputDataBuilder::new()
+                    .set_radius(vec![radius as f32])
+                    .into(),
+            );
+            context.run("scale");
+        }
+        // with two runs in flight, the third one had to wait for them
+        let mut latest = context
+            .read::<scale_module::OutputDataBuilder>("scale")
+            .unwrap()
+            .scaled
+            .unwrap()[0];
+        assert!(latest >= 2.0, "on {:?}", context.backend().kind());
+        let mut polls = 0;
+        while latest < 3.0 {
+            polls += 1;
+            assert!(polls < 1000, "The outputs of the last run never arrived");
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            if let Ok(results) = context.read::<scale_module::OutputDataBuilder>("scale") {
+                latest = results.scaled.unwrap()[0];
+            }
+        }
+        assert_eq!(latest, 3.0);
+    }
+}
+
+#[test]
+fn test_context_runs_tasks_from_async_code() {
+    for mut context in contexts() {
+        create_task(&mut context, 2.0, vec![0.0, 0.0]);
+        // the futures do not borrow the context, so the task can run again before the first one is awaited
+        let first = context.run_async::<scale_module::OutputDataBuilder>(
+            "scale",
+            scale_module::InputDataBuilder::new()
+                .set_radius(vec![1.0, 2.0])
+                .into(),
+        );
+        let second = context.run_async::<scale_module::OutputDataBuilder>(
+            "scale",
+            scale_module::InputDataBuilder::new()
+                .set_radius(vec![3.0, 1.0])
+                .into(),
+        );
+        let (first, second) = futures::executor::block_on(futures::future::join(first, second));
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_eq!(
+            first.scaled,
+            Some(vec![2.0, 4.0, 0.0, 0.0]),
+            "on {:?}",
+            context.backend().kind()
+        );
+        let big: Vec<u32> = first.big.unwrap().iter().map(|b| b.index).collect();
+        assert_eq!(big, vec![1]);
+        assert_eq!(second.scaled, Some(vec![6.0, 2.0, 0.0, 0.0]));
+        let big: Vec<u32> = second.big.unwrap().iter().map(|b| b.index).collect();
+        assert_eq!(big, vec![0]);
+        // the outputs went to the futures, not to the task
+        assert!(
+            context
+                .read::<scale_module::OutputDataBuilder>("scale")
+                .is_err()
+        );
+    }
+}
+
+#[test]
+fn test_context_run_async_keeps_the_outputs_of_earlier_runs() {
+    for mut context in contexts() {
+        create_task(&mut context, 2.0, vec![1.0]);
+        context.run("scale");
+        let future = context.run_async::<scale_module::OutputDataBuilder>(
+            "scale",
+            scale_module::InputDataBuilder::new()
+                .set_radius(vec![3.0])
+                .into(),
+        );
+        let results = context
+            .read::<scale_module::OutputDataBuilder>("scale")
+            .unwrap();
+        assert_eq!(
+            results.scaled.unwrap()[0],
+            2.0,
+            "on {:?}",
+            context.backend().kind()
+        );
+        let outputs = futures::executor::block_on(future).unwrap();
+        assert_eq!(outputs.scaled.unwrap()[0], 6.0);
+    }
+}
+
+#[test]
+fn test_run_task_once_on_the_cpu() {
+    let results = run_task_once_on::<scale_module::Types, scale_module::OutputDataBuilder>(
+        ComputeBackendKind::Cpu,
+        scale_module::parsed(),
+        IterationSpace::new(2, 1, 1),
+        scale_module::MaxOutputLengthsBuilder::new()
+            .set_scaled(2)
+            .set_big(2)
+            .finish(),
+        scale_module::ConfigInputDataBuilder::new()
+            .set_scale(scale_module::Scale { factor: 2.0 })
+            .into(),
+        scale_module::InputDataBuilder::new()
+            .set_radius(vec![1.0, 2.0])
+            .into(),
+    );
+    assert_eq!(results.scaled, Some(vec![2.0, 4.0]));
+    assert_eq!(results.big.unwrap().len(), 1);
+}
@@ -0,0 +1,279 @@
+use std::fmt::{self, Debug};
+
+use crate::wgsl::user_facing_api::PodF16;
+
+/// How far apart two floats may be and still count as equal, because the GPU is allowed to round differently than the CPU. Two floats are equal if they differ by at most `absolute`, or by at most `relative` times the larger of the two.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatTolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Default for FloatTolerance {
+    fn default() -> Self {
+        FloatTolerance {
+            absolute: 1e-5,
+            relative: 1e-4,
+        }
+    }
+}
+
+impl FloatTolerance {
+    pub fn new(absolute: f64, relative: f64) -> Self {
+        FloatTolerance { absolute, relative }
+    }
+    /// floats have to be bit for bit the same, except that any two NaNs are equal
+    pub fn exact() -> Self {
+        FloatTolerance::new(0.0, 0.0)
+    }
+    pub fn accepts(&self, a: f64, b: f64) -> bool {
+        if a.to_bits() == b.to_bits() || (a.is_nan() && b.is_nan()) {
+            return true;
+        }
+        if self.absolute == 0.0 && self.relative == 0.0 {
+            // `0.0` and `-0.0` are not the same bits
+            return false;
+        }
+        let difference = (a - b).abs();
+        difference <= self.absolute || difference <= self.relative * a.abs().max(b.abs())
+    }
+}
+
+/// A field that has a different value in two outputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDifference {
+    /// like `Collision[3].normal.x`
+    pub path: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for FieldDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}`: expected {}, got {}",
+            self.path, self.expected, self.actual
+        )
+    }
+}
+
+/// Compares two values field by field, allowing floats to differ by a `FloatTolerance`. `#[wgsl_shader_module]` implements this for every struct in the module.
+pub trait CompareFields {
+    /// adds every field that differs to `differences`, with `path` as the prefix of its path
+    fn compare_fields(
+        &self,
+        other: &Self,
+        path: &str,
+        tolerance: &FloatTolerance,
+        differences: &mut Vec<FieldDifference>,
+    );
+}
+
+macro_rules! impl_compare_fields_exact {
+    ($($t:ty),*) => {$(
+        impl CompareFields for $t {
+            fn compare_fields(
+                &self,
+                other: &Self,
+                path: &str,
+                _tolerance: &FloatTolerance,
+                differences: &mut Vec<FieldDifference>,
+            ) {
+                if self != other {
+                    differences.push(FieldDifference {
+                        path: path.to_string(),
+                        expected: format!("{:?}", self),
+                        actual: format!("{:?}", other),
+                    });
+                }
+            }
+        }
+    )*};
+}
+impl_compare_fields_exact!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool
+);
+
+macro_rules! impl_compare_fields_float {
+    ($($t:ty),*) => {$(
+        impl CompareFields for $t {
+            fn compare_fields(
+                &self,
+                other: &Self,
+                path: &str,
+                tolerance: &FloatTolerance,
+                differences: &mut Vec<FieldDifference>,
+            ) {
+                if !tolerance.accepts(*self as f64, *other as f64) {
+                    differences.push(FieldDifference {
+                        path: path.to_string(),
+                        expected: format!("{:?}", self),
+                        actual: format!("{:?}", other),
+                    });
+                }
+            }
+        }
+    )*};
+}
+impl_compare_fields_float!(f16, f32, f64);
+
+impl CompareFields for PodF16 {
+    fn compare_fields(
+        &self,
+        other: &Self,
+        path: &str,
+        tolerance: &FloatTolerance,
+        differences: &mut Vec<FieldDifference>,
+    ) {
+        self.value
+            .compare_fields(&other.value, path, tolerance, differences);
+    }
+}
+
+impl<T: CompareFields, const N: usize> CompareFields for [T; N] {
+    fn compare_fields(
+        &self,
+        other: &Self,
+        path: &str,
+        tolerance: &FloatTolerance,
+        differences: &mut Vec<FieldDifference>,
+    ) {
+        for (i, (a, b)) in self.iter().zip(other.iter()).enumerate() {
+            a.compare_fields(b, &format!("{}[{}]", path, i), tolerance, differences);
+        }
+    }
+}
+
+/**
+Compares the values of an output, named `name` in the paths of the differences.
+
+Output arrays are compared index by index. With `unordered`, which is meant for output vecs, each expected value may instead match any one actual value, since the order that invocations push values in is not fixed. The values are matched so that as many as possible find a partner, not just in the order they come in.
+*/
+pub fn compare_output_values<T: CompareFields + Debug>(
+    name: &str,
+    expected: &[T],
+    actual: &[T],
+    unordered: bool,
+    tolerance: &FloatTolerance,
+) -> Vec<FieldDifference> {
+    let mut differences = Vec::new();
+    if expected.len() != actual.len() {
+        differences.push(FieldDifference {
+            path: format!("{}.len()", name),
+            expected: expected.len().to_string(),
+            actual: actual.len().to_string(),
+        });
+    }
+    if !unordered {
+        for (i, (e, a)) in expected.iter().zip(actual.iter()).enumerate() {
+            e.compare_fields(a, &format!("{}[{}]", name, i), tolerance, &mut differences);
+        }
+        return differences;
+    }
+    // which expected value each actual value is matched with
+    let mut matched_with = vec![None; actual.len()];
+    for (i, e) in expected.iter().enumerate() {
+        let mut visited = vec![false; actual.len()];
+        if !find_match(
+            i,
+            expected,
+            actual,
+            tolerance,
+            &mut matched_with,
+            &mut visited,
+        ) {
+            differences.push(FieldDifference {
+                path: format!("{}[{}]", name, i),
+                expected: format!("{:?}", e),
+                actual: "no matching value".to_string(),
+            });
+        }
+    }
+    differences
+}
+
+/// Matches `expected[i]` with an actual value, taking a free one if it can, and otherwise moving the expected value that holds a matching one to another actual value (an augmenting path), so a value that the tolerance lets match several others does not take the only match of a later one
+fn find_match<T: CompareFields>(
+    i: usize,
+    expected: &[T],
+    actual: &[T],
+    tolerance: &FloatTolerance,
+    matched_with: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    let matches = |j: usize| {
+        let mut value_differences = Vec::new();
+        expected[i].compare_fields(&actual[j], "", tolerance, &mut value_differences);
+        value_differences.is_empty()
+    };
+    let mut candidates = Vec::new();
+    for j in 0..actual.len() {
+        if visited[j] || !matches(j) {
+            continue;
+        }
+        visited[j] = true;
+        if matched_with[j].is_none() {
+            matched_with[j] = Some(i);
+            return true;
+        }
+        candidates.push(j);
+    }
+    for j in candidates {
+        let other = matched_with[j].unwrap();
+        if find_match(other, expected, actual, tolerance, matched_with, visited) {
+            matched_with[j] = Some(i);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_float_tolerance() {
+        let tolerance = FloatTolerance::default();
+        assert!(tolerance.accepts(1.0, 1.0 + 1e-6));
+        assert!(tolerance.accepts(1e6, 1e6 + 10.0));
+        assert!(!tolerance.accepts(1.0, 1.001));
+        assert!(tolerance.accepts(f64::NAN, f64::NAN));
+        assert!(!FloatTolerance::exact().accepts(1.0, 1.0 + 1e-9));
+        assert!(!FloatTolerance::exact().accepts(0.0, -0.0));
+        assert!(tolerance.accepts(0.0, -0.0));
+    }
+
+    #[test]
+    fn test_compare_output_values() {
+        let expected = [[1.0f32, 2.0], [3.0, 4.0]];
+        let actual = [[3.0f32, 4.0], [1.0, 2.5]];
+        let tolerance = FloatTolerance::default();
+        let differences = compare_output_values("Pair", &expected, &actual, false, &tolerance);
+        assert_eq!(differences.len(), 4);
+        assert_eq!(differences[0].path, "Pair[0][0]");
+        assert_eq!(
+            differences[0].to_string(),
+            "`Pair[0][0]`: expected 1.0, got 3.0"
+        );
+        let differences = compare_output_values("Pair", &expected, &actual, true, &tolerance);
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].path, "Pair[0]");
+        let differences = compare_output_values("Pair", &expected, &actual[..1], true, &tolerance);
+        assert_eq!(differences[0].path, "Pair.len()");
+    }
+
+    #[test]
+    fn test_unordered_values_are_matched_with_the_only_value_they_fit() {
+        // 1.0 fits both actual values, 1.5 only the first, so taking the first match of 1.0 would leave 1.5 without one
+        let tolerance = FloatTolerance::new(0.6, 0.0);
+        let differences =
+            compare_output_values("Value", &[1.0f32, 1.5], &[1.4f32, 0.5], true, &tolerance);
+        assert!(differences.is_empty(), "{:?}", differences);
+        let differences =
+            compare_output_values("Value", &[1.0f32, 1.5], &[0.5f32, 0.4], true, &tolerance);
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].path, "Value[1]");
+    }
+}
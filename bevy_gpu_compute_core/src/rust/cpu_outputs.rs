@@ -0,0 +1,215 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use bytemuck::Zeroable;
+
+use crate::IterationSpace;
+use crate::wgsl::user_facing_api::WgslIterationPosition;
+
+/// Calls `f` once for every position of the iteration space.
+/// With the `rayon` feature the positions run in parallel, in no particular order, like invocations on the GPU. Without it they run one after another.
+pub fn for_each_position(
+    iteration_space: IterationSpace,
+    f: impl Fn(WgslIterationPosition) + Sync + Send,
+) {
+    let (x_len, y_len, z_len) = (
+        iteration_space.x() as u64,
+        iteration_space.y() as u64,
+        iteration_space.z() as u64,
+    );
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        (0..x_len * y_len * z_len)
+            .into_par_iter()
+            .for_each(|i| f(position_at(i, x_len, y_len)));
+    }
+    #[cfg(not(feature = "rayon"))]
+    (0..x_len * y_len * z_len).for_each(|i| f(position_at(i, x_len, y_len)));
+}
+
+/// the `i`th position, counting in u64 since the number of positions can exceed `u32::MAX` even though each coordinate fits in a u32
+fn position_at(i: u64, x_len: u64, y_len: u64) -> WgslIterationPosition {
+    WgslIterationPosition {
+        x: (i % x_len) as u32,
+        y: ((i / x_len) % y_len) as u32,
+        z: (i / (x_len * y_len)) as u32,
+    }
+}
+
+/// How the CPU version of an entry point writes its outputs.
+/// Implemented for `&mut Vec<T>`, for calling entry points directly, and for `&CpuOutputArray<T>` and `&CpuOutputVec<T>`, which `run_on_cpu` uses so that many positions can run at once.
+pub trait CpuOutput<T> {
+    fn set(&mut self, index: usize, value: T);
+    fn push(&mut self, value: T);
+    /// Like `push`, but returns the index the value was pushed to, which may be past the max length. Lets `CpuSchedule` record which invocation pushed each value.
+    fn push_and_get_index(&mut self, value: T) -> u32 {
+        let index = self.len();
+        self.push(value);
+        index
+    }
+    /// the number of values pushed so far
+    fn len(&self) -> u32;
+    fn max_len(&self) -> u32;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> CpuOutput<T> for &mut Vec<T> {
+    fn set(&mut self, index: usize, value: T) {
+        self[index] = value;
+    }
+    fn push(&mut self, value: T) {
+        Vec::push(self, value);
+    }
+    fn len(&self) -> u32 {
+        Vec::len(self) as u32
+    }
+    /// a plain vec has no max length, so this is its current length, which for output arrays is usually the length they were created with
+    fn max_len(&self) -> u32 {
+        Vec::len(self) as u32
+    }
+}
+
+/// A thread safe `#[wgsl_output_array]`. Like on the GPU, when several invocations set the same index the last write wins.
+pub struct CpuOutputArray<T> {
+    values: Vec<Mutex<T>>,
+}
+
+impl<T: Zeroable> CpuOutputArray<T> {
+    /// `max_len` zeroed values, like a freshly created GPU buffer
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            values: (0..max_len).map(|_| Mutex::new(T::zeroed())).collect(),
+        }
+    }
+    pub fn into_values(self) -> Vec<T> {
+        self.values
+            .into_iter()
+            .map(|v| v.into_inner().unwrap())
+            .collect()
+    }
+}
+
+impl<T> CpuOutput<T> for &CpuOutputArray<T> {
+    fn set(&mut self, index: usize, value: T) {
+        *self.values[index].lock().unwrap() = value;
+    }
+    fn push(&mut self, _value: T) {
+        unreachable!("output arrays are written to with `WgslOutput::set`")
+    }
+    fn len(&self) -> u32 {
+        self.values.len() as u32
+    }
+    fn max_len(&self) -> u32 {
+        self.values.len() as u32
+    }
+}
+
+/// A thread safe `#[wgsl_output_vec]`, with the same semantics as the atomic counter on the GPU: every push takes the next index, and values pushed past the max length are dropped.
+pub struct CpuOutputVec<T> {
+    counter: AtomicU32,
+    values: Vec<Mutex<T>>,
+}
+
+impl<T: Zeroable> CpuOutputVec<T> {
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            counter: AtomicU32::new(0),
+            values: (0..max_len).map(|_| Mutex::new(T::zeroed())).collect(),
+        }
+    }
+    /// the pushed values, at most max length of them
+    pub fn into_values(self) -> Vec<T> {
+        let count = self.counter.into_inner() as usize;
+        self.values
+            .into_iter()
+            .take(count)
+            .map(|v| v.into_inner().unwrap())
+            .collect()
+    }
+}
+
+impl<T> CpuOutput<T> for &CpuOutputVec<T> {
+    fn set(&mut self, _index: usize, _value: T) {
+        unreachable!("output vecs are written to with `WgslOutput::push`")
+    }
+    fn push(&mut self, value: T) {
+        self.push_and_get_index(value);
+    }
+    fn push_and_get_index(&mut self, value: T) -> u32 {
+        let index = self.counter.fetch_add(1, Ordering::Relaxed);
+        if let Some(slot) = self.values.get(index as usize) {
+            *slot.lock().unwrap() = value;
+        }
+        index
+    }
+    fn len(&self) -> u32 {
+        self.counter.load(Ordering::Relaxed)
+    }
+    fn max_len(&self) -> u32 {
+        self.values.len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_for_each_position_visits_every_position_once() {
+        let visited = Mutex::new(Vec::new());
+        for_each_position(IterationSpace::new(3, 2, 2), |pos| {
+            visited.lock().unwrap().push((pos.x, pos.y, pos.z));
+        });
+        let mut visited = visited.into_inner().unwrap();
+        visited.sort();
+        let mut expected = Vec::new();
+        for x in 0..3 {
+            for y in 0..2 {
+                for z in 0..2 {
+                    expected.push((x, y, z));
+                }
+            }
+        }
+        assert_eq!(visited, expected);
+    }
+
+    #[test]
+    fn test_position_at_past_u32_max() {
+        let (x_len, y_len) = (70_000, 70_000);
+        let i = 2 * x_len * y_len + 3 * x_len + 5;
+        assert!(i > u32::MAX as u64);
+        let pos = position_at(i, x_len, y_len);
+        assert_eq!((pos.x, pos.y, pos.z), (5, 3, 2));
+    }
+
+    #[test]
+    fn test_output_vec_drops_values_past_max_len() {
+        let output = CpuOutputVec::<u32>::new(3);
+        for_each_position(IterationSpace::new(10, 1, 1), |pos| {
+            let mut out = &output;
+            out.push(pos.x);
+        });
+        assert_eq!((&output).len(), 10);
+        let mut values = output.into_values();
+        assert_eq!(values.len(), 3);
+        values.sort();
+        values.dedup();
+        assert_eq!(values.len(), 3);
+    }
+
+    #[test]
+    fn test_output_array_is_zeroed_and_settable() {
+        let output = CpuOutputArray::<u32>::new(4);
+        for_each_position(IterationSpace::new(2, 1, 1), |pos| {
+            let mut out = &output;
+            out.set(pos.x as usize * 2, pos.x + 1);
+        });
+        assert_eq!((&output).max_len(), 4);
+        assert_eq!(output.into_values(), vec![1, 0, 2, 0]);
+    }
+}
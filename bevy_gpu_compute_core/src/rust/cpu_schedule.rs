@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::IterationSpace;
+use crate::wgsl::user_facing_api::WgslIterationPosition;
+
+use super::{CpuOutput, SplitMix64, for_each_position, for_each_position_in_lockstep};
+
+/// The order that the invocations of a CPU run happen in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvocationOrder {
+    /// in parallel with the `rayon` feature, otherwise one position after another
+    #[default]
+    Default,
+    /// one after another, in an order that is random but the same for the same seed.
+    /// Only whole invocations are shuffled: each one runs to completion before the next starts, so races between the steps of two invocations, like both reading `WgslOutput::len` before either pushes, are not reproduced. Entry points that call a barrier are the exception, the invocations of each workgroup run in lockstep up to every barrier.
+    Shuffled { seed: u64 },
+}
+
+/// An index of an output array during one dispatch of an entry point, or for output vecs the index a value was pushed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WriteTarget {
+    /// which of the entry points of the run this is, counting from 0
+    pub dispatch: usize,
+    pub entry_point: &'static str,
+    pub output: &'static str,
+    pub index: usize,
+}
+
+/// The invocations that set each index of each output array, or pushed the value at each index of each output vec, for every dispatch
+pub type RecordedWrites = HashMap<WriteTarget, Vec<WgslIterationPosition>>;
+
+#[derive(Default)]
+struct Recording {
+    /// the dispatch currently running, writes happen during the last one started
+    dispatch: Option<(usize, &'static str)>,
+    writes: RecordedWrites,
+}
+
+/// Controls how `run_entry_points_on_cpu` runs a module: in what order the invocations happen, and whether the `WgslOutput::set` and `WgslOutput::push` calls are recorded, to find data races.
+#[derive(Default)]
+pub struct CpuSchedule {
+    order: InvocationOrder,
+    /// the invocations that set or pushed each index of each output
+    writes: Option<Mutex<Recording>>,
+}
+
+impl CpuSchedule {
+    pub fn shuffled(seed: u64) -> Self {
+        CpuSchedule {
+            order: InvocationOrder::Shuffled { seed },
+            writes: None,
+        }
+    }
+    pub fn recording_writes(mut self) -> Self {
+        self.writes = Some(Mutex::new(Recording::default()));
+        self
+    }
+    pub fn order(&self) -> InvocationOrder {
+        self.order
+    }
+    /// Runs one dispatch of `entry_point`, calling `f` once for every position of the iteration space
+    pub fn for_each_position(
+        &self,
+        entry_point: &'static str,
+        iteration_space: IterationSpace,
+        f: impl Fn(WgslIterationPosition) + Sync + Send,
+    ) {
+        self.start_dispatch(entry_point);
+        match self.order {
+            InvocationOrder::Default => for_each_position(iteration_space, f),
+            InvocationOrder::Shuffled { seed } => {
+                shuffled_positions(iteration_space, seed)
+                    .into_iter()
+                    .for_each(f);
+            }
+        }
+    }
+    /// Like `for_each_position`, but for entry points that call a barrier: the invocations of each workgroup run in lockstep, see `for_each_position_in_lockstep`. A shuffled order shuffles the workgroups, and which invocation reaches a barrier first.
+    pub fn for_each_position_in_workgroups(
+        &self,
+        entry_point: &'static str,
+        iteration_space: IterationSpace,
+        f: impl Fn(WgslIterationPosition) + Sync + Send,
+    ) {
+        self.start_dispatch(entry_point);
+        let positions = match self.order {
+            InvocationOrder::Default => positions(iteration_space),
+            InvocationOrder::Shuffled { seed } => shuffled_positions(iteration_space, seed),
+        };
+        for_each_position_in_lockstep(iteration_space, positions, f);
+    }
+    fn start_dispatch(&self, entry_point: &'static str) {
+        if let Some(recording) = &self.writes {
+            let mut recording = recording.lock().unwrap();
+            let dispatch = recording.dispatch.map_or(0, |(d, _)| d + 1);
+            recording.dispatch = Some((dispatch, entry_point));
+        }
+    }
+    /// Wraps an output for a single invocation, so that its writes can be recorded
+    pub fn output<O>(
+        &self,
+        output: O,
+        name: &'static str,
+        invocation: WgslIterationPosition,
+    ) -> ScheduledOutput<'_, O> {
+        ScheduledOutput {
+            output,
+            name,
+            invocation,
+            schedule: self,
+        }
+    }
+    /// The invocations that set each index of each output array, or pushed to each index of each output vec, in each dispatch, in the order they did so. Empty unless the schedule was `recording_writes`.
+    pub fn into_writes(self) -> RecordedWrites {
+        self.writes
+            .map(|w| w.into_inner().unwrap().writes)
+            .unwrap_or_default()
+    }
+}
+
+/// An output as seen by a single invocation of a `CpuSchedule`
+pub struct ScheduledOutput<'a, O> {
+    output: O,
+    name: &'static str,
+    invocation: WgslIterationPosition,
+    schedule: &'a CpuSchedule,
+}
+
+impl<O> ScheduledOutput<'_, O> {
+    fn record(&self, index: usize) {
+        if let Some(recording) = &self.schedule.writes {
+            let mut recording = recording.lock().unwrap();
+            let (dispatch, entry_point) = recording
+                .dispatch
+                .expect("outputs can only be written during for_each_position");
+            recording
+                .writes
+                .entry(WriteTarget {
+                    dispatch,
+                    entry_point,
+                    output: self.name,
+                    index,
+                })
+                .or_default()
+                .push(self.invocation);
+        }
+    }
+}
+
+impl<T, O: CpuOutput<T>> CpuOutput<T> for ScheduledOutput<'_, O> {
+    fn set(&mut self, index: usize, value: T) {
+        self.record(index);
+        self.output.set(index, value);
+    }
+    fn push(&mut self, value: T) {
+        self.push_and_get_index(value);
+    }
+    fn push_and_get_index(&mut self, value: T) -> u32 {
+        let index = self.output.push_and_get_index(value);
+        self.record(index as usize);
+        index
+    }
+    fn len(&self) -> u32 {
+        self.output.len()
+    }
+    fn max_len(&self) -> u32 {
+        self.output.max_len()
+    }
+}
+
+/// every position of the iteration space, in a random order (a Fisher-Yates shuffle driven by `SplitMix64`)
+fn shuffled_positions(iteration_space: IterationSpace, seed: u64) -> Vec<WgslIterationPosition> {
+    let mut positions = positions(iteration_space);
+    let mut rng = SplitMix64::new(seed);
+    for i in (1..positions.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        positions.swap(i, j);
+    }
+    positions
+}
+
+/// every position of the iteration space, x changing fastest
+fn positions(iteration_space: IterationSpace) -> Vec<WgslIterationPosition> {
+    let mut positions =
+        Vec::with_capacity(iteration_space.x() * iteration_space.y() * iteration_space.z());
+    for z in 0..iteration_space.z() {
+        for y in 0..iteration_space.y() {
+            for x in 0..iteration_space.x() {
+                positions.push(WgslIterationPosition {
+                    x: x as u32,
+                    y: y as u32,
+                    z: z as u32,
+                });
+            }
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shuffled_order_is_a_permutation_that_depends_on_the_seed() {
+        let space = IterationSpace::new(5, 4, 1);
+        let a = shuffled_positions(space, 1);
+        let b = shuffled_positions(space, 2);
+        assert_eq!(a, shuffled_positions(space, 1));
+        assert_ne!(a, b);
+        let mut sorted = a.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 20);
+    }
+
+    #[test]
+    fn test_recording_writes() {
+        let output = crate::CpuOutputArray::<u32>::new(2);
+        let schedule = CpuSchedule::shuffled(3).recording_writes();
+        let target = |dispatch, entry_point, index| WriteTarget {
+            dispatch,
+            entry_point,
+            output: "out",
+            index,
+        };
+        schedule.for_each_position("main", IterationSpace::new(3, 1, 1), |pos| {
+            let mut out = schedule.output(&output, "out", pos);
+            out.set(pos.x as usize / 2, pos.x);
+        });
+        // a later dispatch overwriting the same index is not a conflict with the first
+        schedule.for_each_position("clear", IterationSpace::new(1, 1, 1), |pos| {
+            let mut out = schedule.output(&output, "out", pos);
+            out.set(0, 0);
+        });
+        let writes = schedule.into_writes();
+        let mut first_slot = writes[&target(0, "main", 0)].clone();
+        first_slot.sort();
+        assert_eq!(
+            first_slot.iter().map(|p| p.x).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(writes[&target(0, "main", 1)].len(), 1);
+        assert_eq!(writes[&target(1, "clear", 0)].len(), 1);
+    }
+
+    #[test]
+    fn test_recording_pushes() {
+        let output = crate::CpuOutputVec::<u32>::new(2);
+        let schedule = CpuSchedule::shuffled(5).recording_writes();
+        schedule.for_each_position("main", IterationSpace::new(3, 1, 1), |pos| {
+            let mut out = schedule.output(&output, "out", pos);
+            out.push(pos.x);
+        });
+        let writes = schedule.into_writes();
+        let values = output.into_values();
+        assert_eq!(values.len(), 2);
+        // the value past the max length is dropped, but who pushed it is still recorded
+        assert_eq!(writes.len(), 3);
+        for (index, value) in values.into_iter().enumerate() {
+            let target = WriteTarget {
+                dispatch: 0,
+                entry_point: "main",
+                output: "out",
+                index,
+            };
+            assert_eq!(writes[&target][0].x, value);
+        }
+    }
+}
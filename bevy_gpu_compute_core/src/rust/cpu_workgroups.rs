@@ -0,0 +1,219 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::IterationSpace;
+use crate::wgsl::user_facing_api::WgslIterationPosition;
+
+thread_local! {
+    /// the workgroup of the invocation running on this thread, if it runs in lockstep with the others
+    static WORKGROUP: RefCell<Option<Arc<WorkgroupBarrier>>> = const { RefCell::new(None) };
+}
+
+/// Called by `workgroupBarrier()` and `storageBarrier()` on the CPU. Waits until every other invocation of the workgroup reaches a barrier too, or finishes. Does nothing when the invocation does not run in lockstep, for example when an entry point is called directly.
+pub fn wait_for_workgroup() {
+    let workgroup = WORKGROUP.with(|w| w.borrow().clone());
+    if let Some(workgroup) = workgroup {
+        workgroup.wait();
+    }
+}
+
+/**
+Runs the positions in workgroups of the size the GPU uses for the iteration space, each workgroup after the other, in the order its first position has in `positions`. The invocations of a workgroup run on their own threads at the same time, and stop at every barrier until all of them reach it, so the code after a barrier sees what the others wrote before it, like on the GPU.
+A thread per invocation is slow, so the CPU runner only does this for entry points that call a barrier.
+*/
+pub fn for_each_position_in_lockstep(
+    iteration_space: IterationSpace,
+    positions: impl IntoIterator<Item = WgslIterationPosition>,
+    f: impl Fn(WgslIterationPosition) + Sync + Send,
+) {
+    for workgroup in workgroups(iteration_space, positions) {
+        run_workgroup(&workgroup, &f);
+    }
+}
+
+/// groups the positions by the workgroup they belong to, keeping their order
+fn workgroups(
+    iteration_space: IterationSpace,
+    positions: impl IntoIterator<Item = WgslIterationPosition>,
+) -> Vec<Vec<WgslIterationPosition>> {
+    let (x, y, z) = iteration_space.num_dimmensions().workgroup_size();
+    let mut index_of_workgroup: HashMap<(u32, u32, u32), usize> = HashMap::new();
+    let mut workgroups: Vec<Vec<WgslIterationPosition>> = Vec::new();
+    for position in positions {
+        let id = (position.x / x, position.y / y, position.z / z);
+        let index = *index_of_workgroup.entry(id).or_insert_with(|| {
+            workgroups.push(Vec::new());
+            workgroups.len() - 1
+        });
+        workgroups[index].push(position);
+    }
+    workgroups
+}
+
+fn run_workgroup(positions: &[WgslIterationPosition], f: &(impl Fn(WgslIterationPosition) + Sync)) {
+    let barrier = Arc::new(WorkgroupBarrier::new(positions.len()));
+    let panic = std::thread::scope(|scope| {
+        let handles: Vec<_> = positions
+            .iter()
+            .map(|&position| {
+                let barrier = barrier.clone();
+                // caught here, since the scope would replace the panic of the invocation with its own
+                scope.spawn(move || {
+                    catch_unwind(AssertUnwindSafe(|| {
+                        let _member = WorkgroupMember::join(barrier);
+                        f(position)
+                    }))
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().unwrap().err())
+            .find(|panic| !panic.is::<AnotherInvocationPanicked>())
+    });
+    if let Some(panic) = panic {
+        resume_unwind(panic);
+    }
+}
+
+/// Sets the workgroup of the current thread, and leaves it when the invocation finishes, so that the others do not wait for it at their next barrier
+struct WorkgroupMember(Arc<WorkgroupBarrier>);
+impl WorkgroupMember {
+    fn join(barrier: Arc<WorkgroupBarrier>) -> Self {
+        WORKGROUP.with(|w| *w.borrow_mut() = Some(barrier.clone()));
+        WorkgroupMember(barrier)
+    }
+}
+impl Drop for WorkgroupMember {
+    fn drop(&mut self) {
+        WORKGROUP.with(|w| *w.borrow_mut() = None);
+        self.0.leave(std::thread::panicking());
+    }
+}
+
+/// the panic of invocations that were waiting at a barrier when another one of their workgroup panicked
+struct AnotherInvocationPanicked;
+
+/// Like `std::sync::Barrier`, but invocations can leave it, and a panicking invocation makes the others panic instead of waiting forever
+struct WorkgroupBarrier {
+    state: Mutex<BarrierState>,
+    released: Condvar,
+}
+struct BarrierState {
+    /// invocations that have not finished yet
+    members: usize,
+    waiting: usize,
+    /// counts how often the waiting invocations were released
+    generation: u64,
+    panicked: bool,
+}
+
+impl WorkgroupBarrier {
+    fn new(members: usize) -> Self {
+        WorkgroupBarrier {
+            state: Mutex::new(BarrierState {
+                members,
+                waiting: 0,
+                generation: 0,
+                panicked: false,
+            }),
+            released: Condvar::new(),
+        }
+    }
+    fn wait(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.waiting += 1;
+        let generation = state.generation;
+        self.release_if_all_waiting(&mut state);
+        while state.generation == generation && !state.panicked {
+            state = self.released.wait(state).unwrap();
+        }
+        if state.panicked {
+            drop(state);
+            // unwinds without calling the panic hook, only the panic of the invocation that caused this is reported
+            resume_unwind(Box::new(AnotherInvocationPanicked));
+        }
+    }
+    fn leave(&self, panicked: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.members -= 1;
+        state.panicked |= panicked;
+        self.release_if_all_waiting(&mut state);
+        self.released.notify_all();
+    }
+    fn release_if_all_waiting(&self, state: &mut BarrierState) {
+        if state.waiting > 0 && state.waiting == state.members {
+            state.waiting = 0;
+            state.generation += 1;
+            self.released.notify_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    fn positions(iteration_space: IterationSpace) -> Vec<WgslIterationPosition> {
+        let mut positions = Vec::new();
+        for y in 0..iteration_space.y() as u32 {
+            for x in 0..iteration_space.x() as u32 {
+                positions.push(WgslIterationPosition { x, y, z: 0 });
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn test_workgroups_match_the_gpu() {
+        let space = IterationSpace::new(10, 9, 1);
+        let groups = workgroups(space, positions(space));
+        // 8x8 workgroups, so 2x2 of them, the ones on the edges only partly filled
+        let sizes: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+        assert_eq!(sizes, vec![64, 16, 8, 2]);
+        assert!(groups[1].iter().all(|p| p.x >= 8 && p.y < 8));
+    }
+
+    #[test]
+    fn test_invocations_wait_for_each_other_at_barriers() {
+        let space = IterationSpace::new(64, 1, 1);
+        let written = AtomicU32::new(0);
+        let seen = Mutex::new(Vec::new());
+        for_each_position_in_lockstep(space, positions(space), |_| {
+            written.fetch_add(1, Ordering::SeqCst);
+            wait_for_workgroup();
+            seen.lock().unwrap().push(written.load(Ordering::SeqCst));
+        });
+        assert_eq!(seen.into_inner().unwrap(), vec![64; 64]);
+    }
+
+    #[test]
+    fn test_finished_invocations_are_not_waited_for() {
+        let space = IterationSpace::new(4, 1, 1);
+        let passed = AtomicU32::new(0);
+        for_each_position_in_lockstep(space, positions(space), |position| {
+            if position.x == 0 {
+                return;
+            }
+            wait_for_workgroup();
+            passed.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(passed.into_inner(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "invocation 2 failed")]
+    fn test_panics_are_passed_on() {
+        let space = IterationSpace::new(4, 1, 1);
+        for_each_position_in_lockstep(space, positions(space), |position| {
+            if position.x == 2 {
+                panic!("invocation 2 failed");
+            }
+            wait_for_workgroup();
+        });
+    }
+}
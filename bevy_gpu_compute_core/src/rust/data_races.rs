@@ -0,0 +1,208 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use crate::wgsl::user_facing_api::WgslIterationPosition;
+
+use super::{
+    CpuSchedule, IterationSpace, MaxOutputLengths, OutputTypesMetadataTrait, RecordedWrites,
+    TypeErasedArrayInputData, TypeErasedArrayOutputData, TypeErasedConfigInputData, TypesSpec,
+};
+
+/// An index of an output array that more than one invocation of the same dispatch `WgslOutput::set`. On the GPU, which of the values ends up in the output depends on timing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictingWrite {
+    pub entry_point: String,
+    pub output: String,
+    pub index: usize,
+    pub invocations: Vec<WgslIterationPosition>,
+}
+
+/// What `detect_data_races` found
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataRaceReport {
+    pub conflicting_writes: Vec<ConflictingWrite>,
+    /// outputs that differed between invocation orders. Output vecs are compared without regard to the order of their values, since that is never fixed on the GPU, but with regard to which invocation pushed each value.
+    pub differing_outputs: Vec<String>,
+}
+
+impl DataRaceReport {
+    pub fn has_races(&self) -> bool {
+        !self.conflicting_writes.is_empty() || !self.differing_outputs.is_empty()
+    }
+    /// panics with the report if any races were found
+    pub fn assert_no_races(&self) {
+        assert!(!self.has_races(), "{}", self);
+    }
+}
+
+impl fmt::Display for DataRaceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.has_races() {
+            return write!(f, "No data races found");
+        }
+        writeln!(f, "Data races found:")?;
+        for write in &self.conflicting_writes {
+            let invocations: Vec<String> = write
+                .invocations
+                .iter()
+                .map(|p| format!("({}, {}, {})", p.x, p.y, p.z))
+                .collect();
+            writeln!(
+                f,
+                "- `{}`[{}] is set by {} invocations of `{}`: {}",
+                write.output,
+                write.index,
+                write.invocations.len(),
+                write.entry_point,
+                invocations.join(", ")
+            )?;
+        }
+        for output in &self.differing_outputs {
+            writeln!(
+                f,
+                "- `{}` differs depending on the order the invocations run in",
+                output
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/**
+Runs the CPU version of a shader module `orders` times, each time with the invocations in a different random order, and reports:
+- indices of output arrays that are set by more than one invocation of the same dispatch. Entry points that run one after another may set the same index, since dispatches do not overlap.
+- outputs that differ between orders, for example because more values are pushed to an output vec than fit, or because an invocation reads a value with `WgslOutput::len` that depends on the order. The values of output vecs are compared together with the invocation that pushed each of them, so pushing an order dependent value is found even if the same values are pushed overall.
+
+Only whole invocations are reordered, see `InvocationOrder::Shuffled`.
+
+Use the `detect_data_races` function generated in each `#[wgsl_shader_module]`, which runs the default entry point, or call this with `my_module::Types` to choose the entry points.
+*/
+pub fn detect_data_races<Types: TypesSpec>(
+    entry_points: &[String],
+    iteration_space: IterationSpace,
+    config_inputs: &TypeErasedConfigInputData,
+    input_data: &TypeErasedArrayInputData,
+    max_output_lengths: &MaxOutputLengths,
+    orders: usize,
+) -> DataRaceReport {
+    assert!(orders > 0, "At least one invocation order is required");
+    let mut conflicts: BTreeMap<(String, String, usize), BTreeSet<WgslIterationPosition>> =
+        BTreeMap::new();
+    let mut first_outputs = None;
+    let mut differing_outputs = BTreeSet::new();
+    for seed in 0..orders as u64 {
+        let schedule = CpuSchedule::shuffled(seed).recording_writes();
+        let outputs = Types::run_entry_points_on_cpu(
+            entry_points,
+            iteration_space,
+            config_inputs,
+            input_data,
+            max_output_lengths,
+            &schedule,
+        );
+        let writes = schedule.into_writes();
+        let outputs = comparable_outputs::<Types>(&outputs, &writes);
+        for (target, invocations) in writes {
+            let invocations: BTreeSet<_> = invocations.into_iter().collect();
+            if invocations.len() > 1 {
+                conflicts
+                    .entry((
+                        target.entry_point.to_string(),
+                        target.output.to_string(),
+                        target.index,
+                    ))
+                    .or_default()
+                    .extend(invocations);
+            }
+        }
+        match &first_outputs {
+            None => first_outputs = Some(outputs),
+            Some(first) => {
+                for (name, values) in &outputs {
+                    if first.get(name) != Some(values) {
+                        differing_outputs.insert(name.clone());
+                    }
+                }
+            }
+        }
+    }
+    DataRaceReport {
+        conflicting_writes: conflicts
+            .into_iter()
+            .map(
+                |((entry_point, output, index), invocations)| ConflictingWrite {
+                    entry_point,
+                    output,
+                    index,
+                    invocations: invocations.into_iter().collect(),
+                },
+            )
+            .collect(),
+        differing_outputs: differing_outputs.into_iter().collect(),
+    }
+}
+
+/// the bytes of each value of each output. The values of output vecs are sorted, and each one starts with the dispatch and the invocation that pushed it, so a value that depends on the order, like one that `WgslOutput::len` was read for, differs even if the same values are pushed overall.
+fn comparable_outputs<Types: TypesSpec>(
+    outputs: &TypeErasedArrayOutputData,
+    writes: &RecordedWrites,
+) -> BTreeMap<String, Vec<Vec<u8>>> {
+    let pushers: HashMap<(&str, usize), (usize, WgslIterationPosition)> = writes
+        .iter()
+        .filter_map(|(target, invocations)| {
+            let invocation = *invocations.first()?;
+            Some(((target.output, target.index), (target.dispatch, invocation)))
+        })
+        .collect();
+    Types::OutputArrayTypes::get_all()
+        .iter()
+        .map(|metadata| {
+            let name = metadata.name.name().to_string();
+            let bytes = outputs.get_bytes(&name).unwrap_or_default();
+            let mut values: Vec<Vec<u8>> = bytes
+                .chunks(metadata.bytes.max(1))
+                .map(|v| v.to_vec())
+                .collect();
+            if metadata.include_count {
+                for (index, value) in values.iter_mut().enumerate() {
+                    if let Some((dispatch, invocation)) = pushers.get(&(name.as_str(), index)) {
+                        let mut pusher = (*dispatch as u64).to_le_bytes().to_vec();
+                        for coordinate in [invocation.x, invocation.y, invocation.z] {
+                            pusher.extend(coordinate.to_le_bytes());
+                        }
+                        value.splice(0..0, pusher);
+                    }
+                }
+                values.sort();
+            }
+            (name, values)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_display() {
+        let mut report = DataRaceReport::default();
+        assert!(!report.has_races());
+        report.assert_no_races();
+        report.conflicting_writes.push(ConflictingWrite {
+            entry_point: "main".to_string(),
+            output: "Sum".to_string(),
+            index: 0,
+            invocations: vec![
+                WgslIterationPosition { x: 0, y: 0, z: 0 },
+                WgslIterationPosition { x: 1, y: 0, z: 0 },
+            ],
+        });
+        report.differing_outputs.push("Sum".to_string());
+        assert!(report.has_races());
+        assert_eq!(
+            report.to_string(),
+            "Data races found:\n- `Sum`[0] is set by 2 invocations of `main`: (0, 0, 0), (1, 0, 0)\n- `Sum` differs depending on the order the invocations run in\n"
+        );
+    }
+}
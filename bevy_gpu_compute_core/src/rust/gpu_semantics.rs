@@ -0,0 +1,243 @@
+/*
+The CPU version of a module with `#[wgsl_shader_module(gpu_semantics)]` calls these instead of using rust's operators, so that it computes the same results as the GPU, instead of panicking where the GPU would not.
+ */
+use bytemuck::Zeroable;
+
+use super::CpuOutput;
+
+/// Scalar arithmetic as defined by WGSL. Integers wrap on overflow, division by zero returns the dividend and the remainder of a division by zero is zero. Floats behave as they do in rust.
+/// `usize` and `isize` become `u32` and `i32` in WGSL, so they wrap at 32 bits.
+pub trait WgslArithmetic: Copy {
+    fn wgsl_add(self, rhs: Self) -> Self;
+    fn wgsl_sub(self, rhs: Self) -> Self;
+    fn wgsl_mul(self, rhs: Self) -> Self;
+    fn wgsl_div(self, rhs: Self) -> Self;
+    fn wgsl_rem(self, rhs: Self) -> Self;
+}
+
+/// Negation, which wraps for the most negative integer
+pub trait WgslNeg: Copy {
+    fn wgsl_neg(self) -> Self;
+}
+
+/// Shifts, where the shift amount is taken modulo the bit width
+pub trait WgslShift: Copy {
+    fn wgsl_shl(self, rhs: u32) -> Self;
+    fn wgsl_shr(self, rhs: u32) -> Self;
+}
+
+/// Conversions to `u32` and `i32`, as done by `u32(e)` and `i32(e)` in WGSL.
+/// Floats are rounded toward zero and clamped to the range of integers that the float type can represent exactly, so `f32::MAX` becomes `4294967040u32` instead of `u32::MAX`. NaN becomes 0. Integers are converted like with `as`.
+pub trait WgslConvert: Copy {
+    fn wgsl_as_u32(self) -> u32;
+    fn wgsl_as_i32(self) -> i32;
+}
+
+macro_rules! impl_for_integers {
+    ($($t:ty),+) => {$(
+        impl WgslArithmetic for $t {
+            fn wgsl_add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn wgsl_sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn wgsl_mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn wgsl_div(self, rhs: Self) -> Self {
+                if rhs == 0 { self } else { self.wrapping_div(rhs) }
+            }
+            fn wgsl_rem(self, rhs: Self) -> Self {
+                if rhs == 0 { 0 } else { self.wrapping_rem(rhs) }
+            }
+        }
+        impl WgslShift for $t {
+            fn wgsl_shl(self, rhs: u32) -> Self {
+                self.wrapping_shl(rhs)
+            }
+            fn wgsl_shr(self, rhs: u32) -> Self {
+                self.wrapping_shr(rhs)
+            }
+        }
+        impl WgslConvert for $t {
+            fn wgsl_as_u32(self) -> u32 {
+                self as u32
+            }
+            fn wgsl_as_i32(self) -> i32 {
+                self as i32
+            }
+        }
+    )+};
+}
+macro_rules! impl_neg_for_signed_integers {
+    ($($t:ty),+) => {$(
+        impl WgslNeg for $t {
+            fn wgsl_neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+    )+};
+}
+macro_rules! impl_for_floats {
+    ($($t:ty),+) => {$(
+        impl WgslArithmetic for $t {
+            fn wgsl_add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+            fn wgsl_sub(self, rhs: Self) -> Self {
+                self - rhs
+            }
+            fn wgsl_mul(self, rhs: Self) -> Self {
+                self * rhs
+            }
+            fn wgsl_div(self, rhs: Self) -> Self {
+                self / rhs
+            }
+            fn wgsl_rem(self, rhs: Self) -> Self {
+                self % rhs
+            }
+        }
+        impl WgslNeg for $t {
+            fn wgsl_neg(self) -> Self {
+                -self
+            }
+        }
+        impl WgslConvert for $t {
+            fn wgsl_as_u32(self) -> u32 {
+                (self as f32).clamp(0.0, 4294967040.0) as u32
+            }
+            fn wgsl_as_i32(self) -> i32 {
+                (self as f32).clamp(-2147483648.0, 2147483520.0) as i32
+            }
+        }
+    )+};
+}
+macro_rules! impl_as_32_bit {
+    ($($t:ty => $as:ty),+) => {$(
+        impl WgslArithmetic for $t {
+            fn wgsl_add(self, rhs: Self) -> Self {
+                (self as $as).wgsl_add(rhs as $as) as $t
+            }
+            fn wgsl_sub(self, rhs: Self) -> Self {
+                (self as $as).wgsl_sub(rhs as $as) as $t
+            }
+            fn wgsl_mul(self, rhs: Self) -> Self {
+                (self as $as).wgsl_mul(rhs as $as) as $t
+            }
+            fn wgsl_div(self, rhs: Self) -> Self {
+                (self as $as).wgsl_div(rhs as $as) as $t
+            }
+            fn wgsl_rem(self, rhs: Self) -> Self {
+                (self as $as).wgsl_rem(rhs as $as) as $t
+            }
+        }
+        impl WgslShift for $t {
+            fn wgsl_shl(self, rhs: u32) -> Self {
+                (self as $as).wgsl_shl(rhs) as $t
+            }
+            fn wgsl_shr(self, rhs: u32) -> Self {
+                (self as $as).wgsl_shr(rhs) as $t
+            }
+        }
+        impl WgslConvert for $t {
+            fn wgsl_as_u32(self) -> u32 {
+                self as u32
+            }
+            fn wgsl_as_i32(self) -> i32 {
+                self as i32
+            }
+        }
+    )+};
+}
+impl_for_integers!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+impl_as_32_bit!(usize => u32, isize => i32);
+impl_neg_for_signed_integers!(i8, i16, i32, i64, i128);
+impl WgslNeg for isize {
+    fn wgsl_neg(self) -> Self {
+        (self as i32).wrapping_neg() as isize
+    }
+}
+impl_for_floats!(f16, f32, f64);
+
+impl WgslConvert for bool {
+    fn wgsl_as_u32(self) -> u32 {
+        self as u32
+    }
+    fn wgsl_as_i32(self) -> i32 {
+        self as i32
+    }
+}
+
+/// Reads an input like wgpu's default bounds checks (naga's `Restrict` policy) do: out of range indices are clamped to the last element instead of panicking.
+/// Reading from an empty input returns a zeroed value.
+pub fn robust_read<T: Copy + Zeroable>(values: &[T], index: usize) -> T {
+    match values.len() {
+        0 => T::zeroed(),
+        len => values[index.min(len - 1)],
+    }
+}
+
+/// Writes an output like wgpu's default bounds checks (naga's `Restrict` policy) do: out of range indices are clamped to the last element instead of panicking.
+/// Writes to an output with a max length of 0 are dropped.
+pub fn robust_set<T>(output: &mut impl CpuOutput<T>, index: usize, value: T) {
+    match output.max_len() as usize {
+        0 => {}
+        len => output.set(index.min(len - 1), value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_arithmetic_wraps() {
+        assert_eq!(u32::MAX.wgsl_add(2), 1);
+        assert_eq!(0u32.wgsl_sub(1), u32::MAX);
+        assert_eq!(i32::MAX.wgsl_mul(2), -2);
+        assert_eq!(i32::MIN.wgsl_neg(), i32::MIN);
+        assert_eq!(i32::MIN.wgsl_div(-1), i32::MIN);
+        assert_eq!(1u32.wgsl_shl(33), 2);
+    }
+
+    #[test]
+    fn test_usize_and_isize_wrap_at_32_bits() {
+        assert_eq!((u32::MAX as usize).wgsl_add(2), 1);
+        assert_eq!(0usize.wgsl_sub(1), u32::MAX as usize);
+        assert_eq!((i32::MAX as isize).wgsl_add(1), i32::MIN as isize);
+        assert_eq!((i32::MIN as isize).wgsl_neg(), i32::MIN as isize);
+        assert_eq!(1usize.wgsl_shl(32), 1);
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        assert_eq!(7u32.wgsl_div(0), 7);
+        assert_eq!(7i32.wgsl_rem(0), 0);
+        assert_eq!(i32::MIN.wgsl_rem(-1), 0);
+    }
+
+    #[test]
+    fn test_float_to_int_conversion_clamps() {
+        assert_eq!(f32::MAX.wgsl_as_u32(), 4294967040);
+        assert_eq!((-1.5f32).wgsl_as_u32(), 0);
+        assert_eq!(f32::MAX.wgsl_as_i32(), 2147483520);
+        assert_eq!(f32::MIN.wgsl_as_i32(), i32::MIN);
+        assert_eq!((-2.7f32).wgsl_as_i32(), -2);
+        assert_eq!(f32::NAN.wgsl_as_i32(), 0);
+        assert_eq!((-1i32).wgsl_as_u32(), u32::MAX);
+    }
+
+    #[test]
+    fn test_robust_buffer_access() {
+        assert_eq!(robust_read(&[1u32, 2], 5), 2);
+        assert_eq!(robust_read::<u32>(&[], 5), 0);
+        let mut values = vec![0u32; 2];
+        robust_set(&mut &mut values, 0, 3);
+        robust_set(&mut &mut values, 5, 4);
+        assert_eq!(values, vec![3, 4]);
+        let mut empty: Vec<u32> = vec![];
+        robust_set(&mut &mut empty, 0, 4);
+        assert!(empty.is_empty());
+    }
+}
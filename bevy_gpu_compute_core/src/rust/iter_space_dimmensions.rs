@@ -12,4 +12,12 @@ impl IterSpaceDimmension {
             IterSpaceDimmension::ThreeD => 3,
         }
     }
+    /// the `@workgroup_size` of entry points dispatched over an iteration space with these dimmensions
+    pub fn workgroup_size(&self) -> (u32, u32, u32) {
+        match self {
+            IterSpaceDimmension::OneD => (64, 1, 1),
+            IterSpaceDimmension::TwoD => (8, 8, 1),
+            IterSpaceDimmension::ThreeD => (4, 4, 4),
+        }
+    }
 }
@@ -1,14 +1,30 @@
+mod compare_fields;
+mod cpu_outputs;
+mod cpu_schedule;
+mod cpu_workgroups;
+mod data_races;
+mod gpu_semantics;
 mod in_out_metadata;
 mod iter_space_dimmensions;
+mod iteration_space;
 mod max_output_lengths;
+mod random_fields;
 mod type_erased_array_input_data;
 mod type_erased_config_input_data;
 mod type_erased_output_data;
 mod type_safe_api_helpers;
 
+pub use compare_fields::*;
+pub use cpu_outputs::*;
+pub use cpu_schedule::*;
+pub use cpu_workgroups::*;
+pub use data_races::*;
+pub use gpu_semantics::*;
 pub use in_out_metadata::*;
 pub use iter_space_dimmensions::*;
+pub use iteration_space::*;
 pub use max_output_lengths::*;
+pub use random_fields::*;
 pub use type_erased_array_input_data::*;
 pub use type_erased_config_input_data::*;
 pub use type_erased_output_data::*;
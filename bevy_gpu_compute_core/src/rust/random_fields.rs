@@ -0,0 +1,124 @@
+use crate::wgsl::user_facing_api::PodF16;
+
+/// A small deterministic random number generator, so that the same seed always gives the same shuffled invocations or test inputs
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+    /// in `[0, 1)`
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+    /// in `[0, max)`
+    pub fn below(&mut self, max: u64) -> u64 {
+        self.next_u64() % max
+    }
+    /// a random value of a type that implements `RandomFields`
+    pub fn random<T: RandomFields>(&mut self) -> T {
+        T::random_fields(self)
+    }
+}
+
+/**
+Creates a value with every field set to a random value of its own type: integers below 256 (signed ones in `[-128, 128)`), and floats in `[-100, 100)`. `#[wgsl_shader_module]` implements this for every struct in the module.
+The ranges keep the arithmetic of a shader meaningful, and the floats normal, so the GPU does not flush them to zero.
+*/
+pub trait RandomFields {
+    fn random_fields(rng: &mut SplitMix64) -> Self;
+}
+
+macro_rules! impl_random_fields_unsigned {
+    ($($t:ty),*) => {$(
+        impl RandomFields for $t {
+            fn random_fields(rng: &mut SplitMix64) -> Self {
+                rng.below(256) as $t
+            }
+        }
+    )*};
+}
+impl_random_fields_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_random_fields_signed {
+    ($($t:ty),*) => {$(
+        impl RandomFields for $t {
+            fn random_fields(rng: &mut SplitMix64) -> Self {
+                (rng.below(256) as i16 - 128) as $t
+            }
+        }
+    )*};
+}
+impl_random_fields_signed!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_random_fields_float {
+    ($($t:ty),*) => {$(
+        impl RandomFields for $t {
+            fn random_fields(rng: &mut SplitMix64) -> Self {
+                (rng.next_f32() * 200.0 - 100.0) as $t
+            }
+        }
+    )*};
+}
+impl_random_fields_float!(f16, f32, f64);
+
+impl RandomFields for bool {
+    fn random_fields(rng: &mut SplitMix64) -> Self {
+        rng.next_u64() % 2 == 0
+    }
+}
+
+impl RandomFields for PodF16 {
+    fn random_fields(rng: &mut SplitMix64) -> Self {
+        PodF16::new(rng.random())
+    }
+}
+
+impl<T: RandomFields, const N: usize> RandomFields for [T; N] {
+    fn random_fields(rng: &mut SplitMix64) -> Self {
+        std::array::from_fn(|_| rng.random())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wgsl_helpers::Vec3F32;
+
+    #[test]
+    fn test_random_fields_are_in_range() {
+        let mut rng = SplitMix64::new(3);
+        for _ in 0..1000 {
+            assert!(rng.random::<u32>() < 256);
+            assert!((-128..128).contains(&rng.random::<i32>()));
+            let float: f32 = rng.random();
+            assert!((-100.0..100.0).contains(&float));
+            assert!(float == 0.0 || float.is_normal());
+            let vector: Vec3F32 = rng.random();
+            assert!((-100.0..100.0).contains(&vector.z));
+        }
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_values() {
+        let mut a = SplitMix64::new(7);
+        let mut b = SplitMix64::new(7);
+        let mut c = SplitMix64::new(8);
+        let values: [u64; 8] = std::array::from_fn(|_| a.next_u64());
+        assert_eq!(values, std::array::from_fn(|_| b.next_u64()));
+        assert_ne!(values, std::array::from_fn(|_| c.next_u64()));
+    }
+}
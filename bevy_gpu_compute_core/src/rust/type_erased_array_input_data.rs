@@ -26,6 +26,15 @@ impl TypeErasedArrayInputData {
             .get(input_name)
             .map(|v| v.as_slice())
     }
+    /// the values as their original type, used to run shaders on the CPU
+    pub fn get_values<T: Pod>(&self, input_name: &str) -> Option<Vec<T>> {
+        self.get_bytes(input_name).map(|bytes| {
+            bytes
+                .chunks_exact(std::mem::size_of::<T>())
+                .map(bytemuck::pod_read_unaligned)
+                .collect()
+        })
+    }
     pub fn get_length(&self, input_name: &str) -> Option<usize> {
         self.lengths_per_wgsl_input_type_name
             .get(input_name)
@@ -18,6 +18,11 @@ impl TypeErasedConfigInputData {
             .get(config_name)
             .map(|v| v.as_slice())
     }
+    /// the config as its original type, used to run shaders on the CPU
+    pub fn get<T: Pod>(&self, config_name: &str) -> Option<T> {
+        self.get_bytes(config_name)
+            .map(|bytes| bytemuck::pod_read_unaligned(bytes))
+    }
     pub fn get_map(&self) -> &HashMap<String, Vec<u8>> {
         &self.bytes_per_wgsl_config_type_name
     }
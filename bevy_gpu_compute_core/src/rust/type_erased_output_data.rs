@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+#[derive(Clone, Debug)]
 pub struct TypeErasedArrayOutputData {
     bytes_per_wgsl_output_type_name: HashMap<String, Vec<u8>>,
 }
@@ -23,3 +24,10 @@ impl TypeErasedArrayOutputData {
 pub trait OutputDataBuilderTrait {
     fn from(out_data: &TypeErasedArrayOutputData) -> Self;
 }
+
+/// for reading outputs without knowing their types, like `bevy_gpu_compute::testing` does
+impl OutputDataBuilderTrait for TypeErasedArrayOutputData {
+    fn from(out_data: &TypeErasedArrayOutputData) -> Self {
+        out_data.clone()
+    }
+}
@@ -1,4 +1,8 @@
-use super::{InputTypesMetadataTrait, OutputTypesMetadataTrait};
+use super::{
+    CpuSchedule, FieldDifference, FloatTolerance, InputTypesMetadataTrait, IterationSpace,
+    MaxOutputLengths, OutputTypesMetadataTrait, SplitMix64, TypeErasedArrayInputData,
+    TypeErasedArrayOutputData, TypeErasedConfigInputData,
+};
 /*
 The types in this file are used by the main crate to provide a type-safe API to the end user
  */
@@ -7,4 +11,24 @@ pub trait TypesSpec {
     type ConfigInputTypes: InputTypesMetadataTrait;
     type InputArrayTypes: InputTypesMetadataTrait;
     type OutputArrayTypes: OutputTypesMetadataTrait;
+    /// Runs the given entry points on the CPU, one after another over the same outputs. Used by the CPU compute backend, and by `detect_data_races`.
+    fn run_entry_points_on_cpu(
+        entry_points: &[String],
+        iteration_space: IterationSpace,
+        config_inputs: &TypeErasedConfigInputData,
+        input_data: &TypeErasedArrayInputData,
+        max_output_lengths: &MaxOutputLengths,
+        schedule: &CpuSchedule,
+    ) -> TypeErasedArrayOutputData;
+    /// Compares two sets of outputs field by field. Used by `bevy_gpu_compute::testing` to compare GPU and CPU results.
+    fn compare_outputs(
+        expected: &TypeErasedArrayOutputData,
+        actual: &TypeErasedArrayOutputData,
+        tolerance: &FloatTolerance,
+    ) -> Vec<FieldDifference>;
+    /// Random values for every config input, and `input_length` values for every input array, each field with a value of its own type. Used by `bevy_gpu_compute::testing` to generate test inputs.
+    fn random_inputs(
+        rng: &mut SplitMix64,
+        input_length: usize,
+    ) -> (TypeErasedConfigInputData, TypeErasedArrayInputData);
 }
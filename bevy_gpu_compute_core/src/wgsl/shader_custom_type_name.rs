@@ -1,7 +1,30 @@
+/// Converts a rust type or binding name to snake case, keeping acronyms together, so `HTTPData` becomes `http_data`, `Vec3F32` becomes `vec3_f32` and names that are already snake case are unchanged.
+pub fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake_case = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            let starts_word = previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower);
+            if starts_word && !snake_case.ends_with('_') {
+                snake_case.push('_');
+            }
+        }
+        snake_case.extend(c.to_lowercase());
+    }
+    snake_case
+}
+
 #[derive(Clone, Debug, PartialEq)]
 
 pub struct ShaderCustomTypeName {
+    /// identifies one specific binding of a type, all other binding related names are derived from this. It is the type name unless the binding was given an explicit name
     name: String,
+    /// the rust/wgsl type of the data
+    type_name: String,
     upper: String,
     lower: String,
     input_array_length: String,
@@ -14,10 +37,15 @@ pub struct ShaderCustomTypeName {
 
 impl ShaderCustomTypeName {
     pub fn new(name: &str) -> Self {
-        let upper = name.to_uppercase();
-        let lower = name.to_lowercase();
+        Self::new_named(name, name)
+    }
+    /// For types with an explicit binding name, like `#[wgsl_input_array(name = "prev")]`, so that the same type can be used for multiple bindings
+    pub fn new_named(type_name: &str, name: &str) -> Self {
+        let lower = to_snake_case(name);
+        let upper = lower.to_uppercase();
         Self {
             name: name.to_string(),
+            type_name: type_name.to_string(),
             upper: upper.clone(),
             lower: lower.clone(),
             input_array_length: format!("{}_INPUT_ARRAY_LENGTH", upper.clone()),
@@ -25,12 +53,15 @@ impl ShaderCustomTypeName {
             output_array_length: format!("{}_OUTPUT_ARRAY_LENGTH", upper),
             output_array: format!("{}_output_array", lower),
             counter: format!("{}_counter", lower),
-            uniform: lower.clone(),
+            uniform: format!("{}_config", lower),
         }
     }
     pub fn name(&self) -> &String {
         &self.name
     }
+    pub fn type_name(&self) -> &String {
+        &self.type_name
+    }
     pub fn upper(&self) -> &String {
         &self.upper
     }
@@ -58,3 +89,28 @@ impl ShaderCustomTypeName {
         self.uniform.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Position"), "position");
+        assert_eq!(to_snake_case("CollisionResult"), "collision_result");
+        assert_eq!(to_snake_case("HTTPData"), "http_data");
+        assert_eq!(to_snake_case("MyHTTP"), "my_http");
+        assert_eq!(to_snake_case("Vec3F32"), "vec3_f32");
+        assert_eq!(to_snake_case("prev_pos"), "prev_pos");
+        assert_eq!(to_snake_case("Prev_Pos"), "prev_pos");
+    }
+
+    #[test]
+    fn test_generated_names() {
+        let name = ShaderCustomTypeName::new("HTTPData");
+        assert_eq!(name.input_array(), "http_data_input_array");
+        assert_eq!(name.input_array_length(), "HTTP_DATA_INPUT_ARRAY_LENGTH");
+        assert_eq!(name.counter(), "http_data_counter");
+        assert_eq!(name.uniform(), "http_data_config");
+    }
+}
@@ -1,6 +1,8 @@
 use crate::IterSpaceDimmension;
 use crate::wgsl::shader_module::derived_portion::WgslShaderModuleDerivedPortion;
+use crate::wgsl::shader_module::pretty_print::pretty_wgsl_lines;
 use crate::wgsl::shader_module::user_defined_portion::WgslShaderModuleUserPortion;
+use crate::wgsl::shader_sections::WgslType;
 pub struct WgslShaderModule {
     pub user_portion: WgslShaderModuleUserPortion,
     pub library_portion: WgslShaderModuleDerivedPortion,
@@ -13,70 +15,191 @@ impl WgslShaderModule {
             library_portion,
         }
     }
+    /// The complete shader, formatted with `pretty_wgsl`. Doc comments are only present if the module was compiled with `emit_doc_comments`.
     pub fn wgsl_code(&self, iter_space_dimmensions: IterSpaceDimmension) -> String {
+        self.pretty_wgsl(iter_space_dimmensions, true)
+    }
+    /// The complete shader, with one statement per line and consistent indentation and spacing. `//` comments are removed unless `keep_comments` is true.
+    pub fn pretty_wgsl(
+        &self,
+        iter_space_dimmensions: IterSpaceDimmension,
+        keep_comments: bool,
+    ) -> String {
         let mut wgsl: String = String::new();
-        // first add user static consts
-        self.user_portion
-            .static_consts
+        self.formatted_lines(iter_space_dimmensions, keep_comments)
             .iter()
-            .for_each(|c| wgsl.push_str_w_newline(&c.code.wgsl_code.clone()));
-        // then add any miscelanious user helper types which are internal to the GPU only, not transfered to or from th CPU
-        self.user_portion.helper_types.iter().for_each(|t| {
-            wgsl.push_str_w_newline(&t.code.wgsl_code.clone());
+            .for_each(|(_, line, _)| wgsl.push_str_w_newline(line));
+        wgsl
+    }
+    /// The name of the user defined const, type or function that the given line (starting at 1) of `wgsl_code` belongs to.
+    pub fn section_name_at_line(
+        &self,
+        iter_space_dimmensions: IterSpaceDimmension,
+        wgsl_line: usize,
+    ) -> Option<&str> {
+        self.formatted_lines(iter_space_dimmensions, true)
+            .into_iter()
+            .nth(wgsl_line.checked_sub(1)?)?
+            .0
+    }
+    /// The `file:line` of the rust code that generated the given line (starting at 1) of `wgsl_code`, if it came from a user defined const, type or function.
+    pub fn rust_location(
+        &self,
+        iter_space_dimmensions: IterSpaceDimmension,
+        wgsl_line: usize,
+    ) -> Option<String> {
+        let (name, _, line_in_section) = self
+            .formatted_lines(iter_space_dimmensions, true)
+            .into_iter()
+            .nth(wgsl_line.checked_sub(1)?)?;
+        self.user_portion
+            .source_map
+            .section(name?)?
+            .rust_location(line_in_section)
+    }
+    /// Adds the rust location to every `name:line:column` location of the generated wgsl in a shader error report, like the ones from naga and wgpu.
+    pub fn add_rust_locations_to_error(
+        &self,
+        iter_space_dimmensions: IterSpaceDimmension,
+        error: &str,
+    ) -> String {
+        error
+            .lines()
+            .map(|line| {
+                let Some((_, location)) = line.split_once("┌─ ") else {
+                    return line.to_string();
+                };
+                let mut parts = location.trim().rsplitn(3, ':');
+                let (_column, wgsl_line) = (parts.next(), parts.next());
+                match wgsl_line
+                    .and_then(|l| l.parse::<usize>().ok())
+                    .and_then(|l| self.rust_location(iter_space_dimmensions, l))
+                {
+                    Some(rust_location) => format!("{} (from {})", line, rust_location),
+                    None => line.to_string(),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+    /// Each line of the formatted wgsl, with the name of the section it belongs to, and the line of the unformatted section code it came from.
+    /// Sections are formatted separately, so that every line belongs to exactly one section.
+    fn formatted_lines(
+        &self,
+        iter_space_dimmensions: IterSpaceDimmension,
+        keep_comments: bool,
+    ) -> Vec<(Option<&str>, String, usize)> {
+        self.wgsl_sections(iter_space_dimmensions)
+            .into_iter()
+            .flat_map(|(name, code)| {
+                pretty_wgsl_lines(&code, keep_comments)
+                    .into_iter()
+                    .map(move |(line, origin)| (name, line, origin))
+            })
+            .collect()
+    }
+    /// Every part of the generated wgsl, in order, with the name of the user defined const, type or function it belongs to.
+    fn wgsl_sections(
+        &self,
+        iter_space_dimmensions: IterSpaceDimmension,
+    ) -> Vec<(Option<&str>, String)> {
+        let mut sections: Vec<(Option<&str>, String)> = Vec::new();
+        // first add user static consts
+        self.user_portion.static_consts.iter().for_each(|c| {
+            sections.push((c.name(), c.code.wgsl_code.clone()));
         });
         // then add library pipeline consts
         // these include lengths of arrays, and workgroup sizes
         self.library_portion.pipeline_consts.iter().for_each(|c| {
-            wgsl.push_str_w_newline(&c.code.wgsl_code.clone());
+            sections.push((None, c.code.wgsl_code.clone()));
         });
-        // then add user uniform definitions
-        self.user_portion.uniforms.iter().for_each(|u| {
-            wgsl.push_str_w_newline(&u.code.wgsl_code.clone());
+        // then add all user type definitions (helper types, uniforms, input array types and output array types), ordered so that every type comes after the types it contains
+        self.user_types_in_dependency_order().iter().for_each(|t| {
+            sections.push((Some(t.name.type_name().as_str()), t.code.wgsl_code.clone()));
         });
         // then add library uniform definitions
         self.library_portion.uniforms.iter().for_each(|u| {
-            wgsl.push_str_w_newline(&u.code.wgsl_code.clone());
-        });
-        // then add user input array definitions
-        self.user_portion.input_arrays.iter().for_each(|a| {
-            wgsl.push_str_w_newline(&a.item_type.code.wgsl_code.clone());
-        });
-        // then add user output array definitions
-        self.user_portion.output_arrays.iter().for_each(|a| {
-            wgsl.push_str_w_newline(&a.item_type.code.wgsl_code.clone());
+            sections.push((None, u.code.wgsl_code.clone()));
         });
         // now add wgpu bindings
         self.library_portion.bindings.iter().for_each(|b| {
-            wgsl.push_str_w_newline(&b.to_string());
+            sections.push((None, b.to_string()));
         });
         // now add user helper functions
         self.user_portion.helper_functions.iter().for_each(|f| {
-            wgsl.push_str_w_newline(&f.code.wgsl_code.clone());
+            sections.push((Some(f.name.as_str()), f.code.wgsl_code.clone()));
         });
         // now add library helper functions
         self.library_portion.helper_functions.iter().for_each(|f| {
-            wgsl.push_str_w_newline(&f.code.wgsl_code.clone());
+            sections.push((None, f.code.wgsl_code.clone()));
+        });
+        // now add the main function and any other entry points
+        self.user_portion.entry_points().for_each(|f| {
+            let (x, y, z) = iter_space_dimmensions.workgroup_size();
+            sections.push((
+                None,
+                format!("@compute @workgroup_size({}, {}, {})", x, y, z),
+            ));
+            sections.push((Some(f.name.as_str()), f.code.wgsl_code.clone()));
         });
-        // now add the main function
-        if iter_space_dimmensions == IterSpaceDimmension::OneD {
-            wgsl.push_str_w_newline("@compute @workgroup_size(64, 1, 1)");
-        } else if iter_space_dimmensions == IterSpaceDimmension::TwoD {
-            wgsl.push_str_w_newline("@compute @workgroup_size(8, 8, 1)");
-        } else {
-            wgsl.push_str_w_newline("@compute @workgroup_size(4, 4, 4)");
+        sections
+    }
+    /// Types are first listed in their original order (helper types, uniforms, inputs, outputs), then each type is moved after all of the user types it references, so that nested structs and arrays of structs are always defined before use.
+    fn user_types_in_dependency_order(&self) -> Vec<&WgslType> {
+        let mut all_types: Vec<&WgslType> = Vec::new();
+        self.user_portion
+            .helper_types
+            .iter()
+            .chain(self.user_portion.uniforms.iter())
+            .chain(self.user_portion.input_arrays.iter().map(|a| &a.item_type))
+            .chain(self.user_portion.output_arrays.iter().map(|a| &a.item_type))
+            .for_each(|t| {
+                // the same type can be used in more than one role, but must only be defined once
+                if !all_types
+                    .iter()
+                    .any(|e| e.name.type_name() == t.name.type_name())
+                {
+                    all_types.push(t);
+                }
+            });
+        let mut ordered: Vec<&WgslType> = Vec::with_capacity(all_types.len());
+        let mut visiting: Vec<&String> = Vec::new();
+        for t in all_types.iter() {
+            add_type_after_dependencies(t, &all_types, &mut visiting, &mut ordered);
         }
-        wgsl.push_str_w_newline(
-            &self
-                .user_portion
-                .main_function
-                .as_ref()
-                .unwrap()
-                .code
-                .wgsl_code
-                .clone(),
-        );
-        wgsl
+        ordered
+    }
+}
+
+fn add_type_after_dependencies<'a>(
+    t: &'a WgslType,
+    all_types: &[&'a WgslType],
+    visiting: &mut Vec<&'a String>,
+    ordered: &mut Vec<&'a WgslType>,
+) {
+    if ordered
+        .iter()
+        .any(|o| o.name.type_name() == t.name.type_name())
+        || visiting.contains(&t.name.type_name())
+    {
+        // already added, or a recursive type definition which wgsl will reject anyway
+        return;
     }
+    visiting.push(t.name.type_name());
+    let identifiers: Vec<&str> = t
+        .code
+        .wgsl_code
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .collect();
+    for dependency in all_types.iter() {
+        if dependency.name.type_name() != t.name.type_name()
+            && identifiers.contains(&dependency.name.type_name().as_str())
+        {
+            add_type_after_dependencies(dependency, all_types, visiting, ordered);
+        }
+    }
+    visiting.pop();
+    ordered.push(t);
 }
 
 // implement push_str_w_newline for String
@@ -89,3 +212,96 @@ impl _PushStrWNewline for String {
         self.push('\n');
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::wgsl::{
+        shader_custom_type_name::ShaderCustomTypeName,
+        shader_sections::{
+            WgslFunction, WgslInputArray, WgslSectionSourceMap, WgslShaderModuleSectionCode,
+        },
+    };
+
+    use super::*;
+
+    fn wgsl_type(name: &str, code: &str) -> WgslType {
+        WgslType {
+            name: ShaderCustomTypeName::new(name),
+            code: WgslShaderModuleSectionCode {
+                wgsl_code: code.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_types_are_defined_after_their_dependencies() {
+        let mut user_portion = WgslShaderModuleUserPortion::empty();
+        user_portion.helper_types = vec![
+            wgsl_type("Pair", "struct Pair { a : Body, b : Body, }"),
+            wgsl_type("Shape", "struct Shape { radius : f32, }"),
+        ];
+        user_portion.input_arrays = vec![WgslInputArray {
+            item_type: wgsl_type(
+                "Body",
+                "struct Body { shape : Shape, verts : array < vec2 < f32 > , 4 > , }",
+            ),
+        }];
+        user_portion.binding_numbers_by_variable_name =
+            Some(HashMap::from([(String::from("body_input_array"), 1)]));
+        let module = WgslShaderModule::new(user_portion);
+        let names: Vec<&String> = module
+            .user_types_in_dependency_order()
+            .iter()
+            .map(|t| t.name.type_name())
+            .collect();
+        assert_eq!(names, vec!["Shape", "Body", "Pair"]);
+    }
+
+    #[test]
+    fn test_rust_locations_of_wgsl_lines() {
+        let mut user_portion = WgslShaderModuleUserPortion::empty();
+        user_portion.main_function = Some(WgslFunction {
+            name: "main".to_string(),
+            code: WgslShaderModuleSectionCode {
+                wgsl_code: "fn main(@builtin(global_invocation_id) iter_pos: vec3<u32>)\n{\n    let x = missing;\n}".to_string(),
+            },
+        });
+        user_portion.source_map.sections.push(WgslSectionSourceMap {
+            name: "main".to_string(),
+            file: "src/shader.rs".to_string(),
+            rust_lines: vec![10, 10, 11, 12],
+        });
+        user_portion.binding_numbers_by_variable_name = Some(HashMap::new());
+        let module = WgslShaderModule::new(user_portion);
+        let wgsl = module.wgsl_code(IterSpaceDimmension::OneD);
+        let wgsl_line = wgsl.lines().position(|l| l.contains("missing")).unwrap() + 1;
+        assert_eq!(
+            module.rust_location(IterSpaceDimmension::OneD, wgsl_line),
+            Some("src/shader.rs:11".to_string())
+        );
+        // the workgroup size attribute is generated, so it has no rust location
+        assert_eq!(
+            module.rust_location(IterSpaceDimmension::OneD, wgsl_line - 2),
+            None
+        );
+        assert_eq!(
+            module.section_name_at_line(IterSpaceDimmension::OneD, wgsl_line),
+            Some("main")
+        );
+        let error = format!(
+            "error: no definition in scope for identifier: `missing`\n  ┌─ wgsl:{}:13\n  │",
+            wgsl_line
+        );
+        assert_eq!(
+            module.add_rust_locations_to_error(IterSpaceDimmension::OneD, &error),
+            format!(
+                "error: no definition in scope for identifier: `missing`\n  ┌─ wgsl:{}:13 (from src/shader.rs:11)\n  │",
+                wgsl_line
+            )
+        );
+    }
+}
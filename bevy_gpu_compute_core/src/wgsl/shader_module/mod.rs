@@ -1,3 +1,4 @@
 pub mod complete_shader_module;
 pub mod derived_portion;
+pub mod pretty_print;
 pub mod user_defined_portion;
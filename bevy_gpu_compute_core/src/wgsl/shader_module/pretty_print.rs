@@ -0,0 +1,330 @@
+/// identifiers that take a template list, like `array<f32, 2>`, so that their `<` and `>` are not formatted as comparisons
+const TEMPLATED_IDENTIFIERS: [&str; 17] = [
+    "array", "atomic", "bitcast", "ptr", "var", "vec2", "vec3", "vec4", "mat2x2", "mat2x3",
+    "mat2x4", "mat3x2", "mat3x3", "mat3x4", "mat4x2", "mat4x3", "mat4x4",
+];
+const OPERATORS: [&str; 21] = [
+    "<<=", ">>=", "->", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=", "*=", "/=", "%=", "&=",
+    "|=", "^=", "<<", ">>", "++", "--",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// identifiers, keywords and number literals
+    Word(String),
+    Punct(String),
+    TemplateStart,
+    TemplateEnd,
+    Comment(String),
+}
+
+/// Formats wgsl with one statement per line, 4 space indentation, and the spacing of the WGSL spec examples, for example turning `array < f32 , 2 >` into `array<f32, 2>`.
+/// `//` comments are kept on their own line if `keep_comments` is true.
+pub fn pretty_wgsl(wgsl: &str, keep_comments: bool) -> String {
+    let mut out = String::new();
+    for (line, _) in pretty_wgsl_lines(wgsl, keep_comments) {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// The formatted lines, each with the index of the line of `wgsl` that it starts on.
+pub(crate) fn pretty_wgsl_lines(wgsl: &str, keep_comments: bool) -> Vec<(String, usize)> {
+    let tokens: Vec<(Token, usize)> = tokenize(wgsl)
+        .into_iter()
+        .filter(|(t, _)| keep_comments || !matches!(t, Token::Comment(_)))
+        .collect();
+    let mut printer = Printer::default();
+    for (i, (token, origin)) in tokens.iter().enumerate() {
+        let next = tokens.get(i + 1).map(|(t, _)| t);
+        printer.print(token, *origin, next);
+    }
+    printer.flush();
+    printer.lines
+}
+
+fn tokenize(wgsl: &str) -> Vec<(Token, usize)> {
+    let chars: Vec<char> = wgsl.chars().collect();
+    let mut tokens: Vec<(Token, usize)> = Vec::new();
+    let mut template_depth = 0;
+    let mut line = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            let start = i;
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            let comment: String = chars[start..i].iter().collect();
+            tokens.push((Token::Comment(comment.trim_end().to_string()), line));
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            let is_number = c.is_ascii_digit();
+            while i < chars.len() {
+                let ch = chars[i];
+                let exponent_sign = is_number
+                    && (ch == '+' || ch == '-')
+                    && matches!(chars[i - 1], 'e' | 'E')
+                    && !chars[start..i].contains(&'x');
+                if ch.is_alphanumeric() || ch == '_' || (is_number && ch == '.') || exponent_sign {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            tokens.push((Token::Word(chars[start..i].iter().collect()), line));
+        } else if c == '<'
+            && matches!(tokens.last(), Some((Token::Word(w), _)) if TEMPLATED_IDENTIFIERS.contains(&w.as_str()))
+        {
+            template_depth += 1;
+            tokens.push((Token::TemplateStart, line));
+            i += 1;
+        } else if c == '>' && template_depth > 0 {
+            template_depth -= 1;
+            tokens.push((Token::TemplateEnd, line));
+            i += 1;
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 3)].iter().collect();
+            let punct = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .map_or(c.to_string(), |op| op.to_string());
+            i += punct.chars().count();
+            tokens.push((Token::Punct(punct), line));
+        }
+    }
+    tokens
+}
+
+#[derive(Default)]
+struct Printer {
+    lines: Vec<(String, usize)>,
+    current: String,
+    current_origin: usize,
+    indent: usize,
+    /// depth of `(`, `[` and template lists, inside which statements and lists are not split over lines
+    paren_depth: usize,
+    previous: Option<Token>,
+    previous_is_unary: bool,
+}
+
+impl Printer {
+    fn print(&mut self, token: &Token, origin: usize, next: Option<&Token>) {
+        match token {
+            Token::Comment(comment) => {
+                self.flush();
+                self.push(comment, origin, false);
+                self.flush();
+            }
+            Token::Punct(p) if p == "{" => {
+                let space = !self.current.is_empty();
+                self.push("{", origin, space);
+                self.flush();
+                self.indent += 1;
+            }
+            Token::Punct(p) if p == "}" => {
+                self.flush();
+                self.indent = self.indent.saturating_sub(1);
+                self.push("}", origin, false);
+                let continues_line = match next {
+                    Some(Token::Word(w)) => w == "else",
+                    Some(Token::Punct(p)) => p == ";" || p == "," || p == ")",
+                    _ => false,
+                };
+                if !continues_line {
+                    self.flush();
+                }
+            }
+            Token::Punct(p) if p == ";" || p == "," => {
+                self.push(p, origin, false);
+                if self.paren_depth == 0 {
+                    self.flush();
+                }
+            }
+            _ => {
+                let space = self.space_before(token);
+                let text = match token {
+                    Token::Word(w) | Token::Punct(w) => w.as_str(),
+                    Token::TemplateStart => "<",
+                    Token::TemplateEnd => ">",
+                    Token::Comment(_) => unreachable!(),
+                };
+                let is_unary = self.is_unary(token);
+                self.push(text, origin, space);
+                match token {
+                    Token::TemplateStart => self.paren_depth += 1,
+                    Token::Punct(p) if p == "(" || p == "[" => self.paren_depth += 1,
+                    Token::TemplateEnd => self.paren_depth = self.paren_depth.saturating_sub(1),
+                    Token::Punct(p) if p == ")" || p == "]" => {
+                        self.paren_depth = self.paren_depth.saturating_sub(1)
+                    }
+                    _ => {}
+                }
+                self.previous = Some(token.clone());
+                self.previous_is_unary = is_unary;
+                return;
+            }
+        }
+        self.previous = Some(token.clone());
+        self.previous_is_unary = false;
+    }
+
+    fn push(&mut self, text: &str, origin: usize, space: bool) {
+        if self.current.is_empty() {
+            self.current = "    ".repeat(self.indent);
+            self.current_origin = origin;
+        } else if space {
+            self.current.push(' ');
+        }
+        self.current.push_str(text);
+    }
+
+    fn flush(&mut self) {
+        if !self.current.trim().is_empty() {
+            self.lines
+                .push((std::mem::take(&mut self.current), self.current_origin));
+        }
+        self.current.clear();
+    }
+
+    /// operators that come after another operator, an opening bracket or `return` are unary, like `-1` or `&counter`
+    fn is_unary(&self, token: &Token) -> bool {
+        let Token::Punct(p) = token else {
+            return false;
+        };
+        if !["-", "!", "~", "&", "*"].contains(&p.as_str()) {
+            return false;
+        }
+        match &self.previous {
+            None => true,
+            Some(Token::Word(w)) => w == "return",
+            Some(Token::Punct(prev)) => prev != ")" && prev != "]",
+            Some(Token::TemplateStart) => true,
+            Some(Token::TemplateEnd) | Some(Token::Comment(_)) => false,
+        }
+    }
+
+    fn space_before(&self, token: &Token) -> bool {
+        let Some(previous) = &self.previous else {
+            return false;
+        };
+        if self.previous_is_unary {
+            return false;
+        }
+        if let Token::Punct(prev) = previous {
+            if ["(", "[", ".", "@"].contains(&prev.as_str()) {
+                return false;
+            }
+        }
+        if *previous == Token::TemplateStart {
+            return false;
+        }
+        match token {
+            Token::TemplateStart | Token::TemplateEnd => false,
+            Token::Punct(p) => match p.as_str() {
+                ")" | "]" | ":" | "." => false,
+                "++" | "--" => {
+                    !matches!(previous, Token::Word(_))
+                        && *previous != Token::Punct(")".to_string())
+                        && *previous != Token::Punct("]".to_string())
+                }
+                "(" => match previous {
+                    Token::Word(w) => {
+                        ["if", "for", "while", "switch", "return"].contains(&w.as_str())
+                    }
+                    Token::TemplateEnd => false,
+                    Token::Punct(prev) => prev != ")",
+                    _ => true,
+                },
+                "[" => {
+                    !matches!(previous, Token::Word(_) | Token::TemplateEnd)
+                        && *previous != Token::Punct(")".to_string())
+                        && *previous != Token::Punct("]".to_string())
+                }
+                _ => true,
+            },
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_pretty_wgsl() {
+        let wgsl = "// A body\nstruct Body { shape : Shape, verts : array < vec2 < f32 > , 4 > , }\nfn main(@builtin(global_invocation_id) iter_pos: vec3<u32>)\n{\n    let i = iter_pos.x; if i >= LEN || i < 1u { return; } let x = - values [i] * 2.0; { let index = atomicAdd(& counter, 1u); if index < 3 { out [index] = Pair(x, 1e-3); } else { out [0] = Pair(- x, 0.0); } };\n}";
+        assert_eq!(
+            pretty_wgsl(wgsl, true),
+            "// A body
+struct Body {
+    shape: Shape,
+    verts: array<vec2<f32>, 4>,
+}
+fn main(@builtin(global_invocation_id) iter_pos: vec3<u32>) {
+    let i = iter_pos.x;
+    if i >= LEN || i < 1u {
+        return;
+    }
+    let x = -values[i] * 2.0;
+    {
+        let index = atomicAdd(&counter, 1u);
+        if index < 3 {
+            out[index] = Pair(x, 1e-3);
+        } else {
+            out[0] = Pair(-x, 0.0);
+        }
+    };
+}
+"
+        );
+        assert!(!pretty_wgsl(wgsl, false).contains("//"));
+    }
+
+    #[test]
+    fn test_pretty_wgsl_line_origins() {
+        let wgsl = "fn f() -> u32\n{\n    let a = 1u; return\n    a;\n}";
+        let origins: Vec<usize> = pretty_wgsl_lines(wgsl, true)
+            .into_iter()
+            .map(|(_, origin)| origin)
+            .collect();
+        assert_eq!(origins, vec![0, 2, 2, 4]);
+    }
+
+    #[test]
+    fn test_pretty_wgsl_bindings_and_loops() {
+        let wgsl = "@group(0) @binding(1) var<storage, read> prev_input_array: array < Particle >;\nfor (var i = 0u; i < 4u; i ++) { total += i; }";
+        assert_eq!(
+            pretty_wgsl(wgsl, true),
+            "@group(0) @binding(1) var<storage, read> prev_input_array: array<Particle>;
+for (var i = 0u; i < 4u; i++) {
+    total += i;
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_pretty_wgsl_matrix_templates() {
+        let wgsl =
+            "struct Bone { transforms : array < mat4x4 < f32 > , 4 > , offset : mat4x3 < f32 > , }";
+        assert_eq!(
+            pretty_wgsl(wgsl, true),
+            "struct Bone {
+    transforms: array<mat4x4<f32>, 4>,
+    offset: mat4x3<f32>,
+}
+"
+        );
+    }
+}
@@ -22,10 +22,15 @@ pub struct WgslShaderModuleUserPortion {
     /// any function that appears besides the one called "main"
     pub helper_functions: Vec<WgslFunction>,
     /// the main function, identified by its name: "main"
-    /// MUST contain a single parameter called "global_id" of type "WgslGlobalId"
-    /// look for any attempt to ASSIGN to the value of "global_id.x", "global_id.y", or "global_id.z" or just "global_id" and throw an error
+    /// MUST contain a single parameter of type "WgslIterationPosition", with any name or destructured into its fields
+    /// the parameter is read-only, any attempt to ASSIGN to it or to its fields is a compile error
     pub main_function: Option<WgslFunction>,
+    /// additional entry points, identified with a #[wgsl_entry] attribute above them
+    /// same rules as the main function, they share all types and bindings with it
+    pub entry_functions: Vec<WgslFunction>,
     pub binding_numbers_by_variable_name: Option<HashMap<String, u32>>,
+    /// the rust lines that each const, type and function was generated from, used to point shader errors at the user's code
+    pub source_map: WgslSourceMap,
 }
 impl WgslShaderModuleUserPortion {
     pub fn empty() -> Self {
@@ -37,7 +42,162 @@ impl WgslShaderModuleUserPortion {
             output_arrays: vec![],
             helper_functions: vec![],
             main_function: None,
+            entry_functions: vec![],
             binding_numbers_by_variable_name: None,
+            source_map: WgslSourceMap::empty(),
         }
     }
+    /// the main function (if there is one) followed by the `#[wgsl_entry]` functions, in the order they were defined
+    pub fn entry_points(&self) -> impl Iterator<Item = &WgslFunction> {
+        self.main_function.iter().chain(self.entry_functions.iter())
+    }
+    pub fn entry_point_names(&self) -> Vec<String> {
+        self.entry_points().map(|f| f.name.clone()).collect()
+    }
+    /// Adds the consts, helper types and helper functions of a `#[wgsl_shader_library]` module.
+    /// Definitions that already exist (for example because two imported libraries share a dependency) are skipped, as are library types and functions with the same name as one defined in this module.
+    ///
+    /// # Panics
+    /// If the library defines a const with the same name as an existing const but a different value, since WGSL cannot define a name twice. Modules generated by the macros already check this at compile time, see `WgslConstTable`.
+    pub fn import_library(&mut self, library: WgslShaderModuleUserPortion) {
+        for c in library.static_consts {
+            let existing = self.static_consts.iter().find(|existing| match c.name() {
+                Some(name) => existing.name() == Some(name),
+                None => *existing == &c,
+            });
+            match existing {
+                None => self.static_consts.push(c),
+                // doc comments may differ, for example if one library is only emitted with `emit_doc_comments`
+                Some(existing) if existing.declaration() == c.declaration() => {}
+                Some(existing) => {
+                    let name = c.name().unwrap_or_default();
+                    let location = |source_map: &WgslSourceMap| {
+                        source_map
+                            .section(name)
+                            .and_then(|s| s.rust_location(0))
+                            .unwrap_or_else(|| String::from("an unknown location"))
+                    };
+                    panic!(
+                        "The const `{}` is defined twice with different values: `{}` at {}, and `{}` at {} in an imported #[wgsl_shader_library]. Rename one of them.",
+                        name,
+                        existing.declaration().unwrap_or_default(),
+                        location(&self.source_map),
+                        c.declaration().unwrap_or_default(),
+                        location(&library.source_map),
+                    );
+                }
+            }
+        }
+        for t in library.helper_types {
+            if !self
+                .helper_types
+                .iter()
+                .any(|existing| existing.name.type_name() == t.name.type_name())
+            {
+                self.helper_types.push(t);
+            }
+        }
+        for f in library.helper_functions {
+            if !self
+                .helper_functions
+                .iter()
+                .any(|existing| existing.name == f.name)
+            {
+                self.helper_functions.push(f);
+            }
+        }
+        self.source_map.import(library.source_map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wgsl::shader_custom_type_name::ShaderCustomTypeName;
+
+    fn function(name: &str) -> WgslFunction {
+        WgslFunction {
+            name: name.to_string(),
+            code: WgslShaderModuleSectionCode {
+                wgsl_code: format!("fn {}() {{ }}", name),
+            },
+        }
+    }
+
+    #[test]
+    fn test_import_library_skips_existing_definitions() {
+        let mut library = WgslShaderModuleUserPortion::empty();
+        library
+            .static_consts
+            .push(WgslConstAssignment::new("SEED", "u32", "7"));
+        library.helper_types.push(WgslType {
+            name: ShaderCustomTypeName::new("Cell"),
+            code: WgslShaderModuleSectionCode {
+                wgsl_code: "struct Cell { x: i32, }".to_string(),
+            },
+        });
+        library.helper_functions.push(function("hash"));
+        library.helper_functions.push(function("cell_of"));
+        let mut module = WgslShaderModuleUserPortion::empty();
+        module.helper_functions.push(function("cell_of"));
+        module.import_library(library.clone());
+        module.import_library(library);
+        assert_eq!(module.static_consts.len(), 1);
+        assert_eq!(module.helper_types.len(), 1);
+        let names: Vec<&str> = module
+            .helper_functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["cell_of", "hash"]);
+    }
+
+    #[test]
+    fn test_import_library_skips_consts_with_the_same_name_and_value() {
+        let mut library = WgslShaderModuleUserPortion::empty();
+        library
+            .static_consts
+            .push(WgslConstAssignment::new("SEED", "u32", "7"));
+        let mut module = WgslShaderModuleUserPortion::empty();
+        module
+            .static_consts
+            .push(WgslConstAssignment::new("SEED", "u32", "7"));
+        module.import_library(library);
+        assert_eq!(module.static_consts.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The const `SEED` is defined twice with different values: `override SEED: u32 = 7;` at src/module.rs:3, and `override SEED: u32 = 8;` at src/library.rs:5"
+    )]
+    fn test_import_library_rejects_consts_with_the_same_name_and_different_values() {
+        let source_map = |file: &str, line: u32| WgslSourceMap {
+            sections: vec![WgslSectionSourceMap {
+                name: "SEED".to_string(),
+                file: file.to_string(),
+                rust_lines: vec![line],
+            }],
+        };
+        let mut library = WgslShaderModuleUserPortion::empty();
+        library
+            .static_consts
+            .push(WgslConstAssignment::new("SEED", "u32", "8"));
+        library.source_map = source_map("src/library.rs", 5);
+        let mut module = WgslShaderModuleUserPortion::empty();
+        module
+            .static_consts
+            .push(WgslConstAssignment::new("SEED", "u32", "7"));
+        module.source_map = source_map("src/module.rs", 3);
+        module.import_library(library);
+    }
+
+    #[test]
+    fn test_entry_point_names() {
+        let mut module = WgslShaderModuleUserPortion::empty();
+        module.entry_functions.push(function("clear"));
+        module.entry_functions.push(function("resolve"));
+        assert_eq!(module.entry_point_names(), vec!["clear", "resolve"]);
+        module.main_function = Some(function("main"));
+        assert_eq!(module.entry_point_names(), vec!["main", "clear", "resolve"]);
+    }
 }
@@ -13,6 +13,22 @@ impl WgslConstAssignment {
             },
         }
     }
+    /// The line that declares the const (`const NAME : type = value;` or `override NAME: type = value;`), after any doc comments
+    pub fn declaration(&self) -> Option<&str> {
+        self.code
+            .wgsl_code
+            .lines()
+            .map(|l| l.trim())
+            .find(|l| !l.starts_with("//"))
+    }
+    pub fn name(&self) -> Option<&str> {
+        self.declaration()?
+            .split_whitespace()
+            .nth(1)?
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .filter(|name| !name.is_empty())
+    }
     pub fn no_default(name: &str, scalar_type: &str) -> Self {
         Self {
             code: WgslShaderModuleSectionCode {
@@ -21,3 +37,27 @@ impl WgslConstAssignment {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name() {
+        assert_eq!(
+            WgslConstAssignment::new("SEED", "u32", "7").name(),
+            Some("SEED")
+        );
+        let documented = WgslConstAssignment {
+            code: WgslShaderModuleSectionCode {
+                wgsl_code: "/// the radius\n// of a cell\nconst CELL_RADIUS : f32 = 2.0;"
+                    .to_string(),
+            },
+        };
+        assert_eq!(documented.name(), Some("CELL_RADIUS"));
+        assert_eq!(
+            documented.declaration(),
+            Some("const CELL_RADIUS : f32 = 2.0;")
+        );
+    }
+}
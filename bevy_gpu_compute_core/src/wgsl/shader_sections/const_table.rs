@@ -0,0 +1,128 @@
+/// A const of a shader module or `#[wgsl_shader_library]`, as the macros see it at compile time
+pub struct WgslConstEntry {
+    pub name: &'static str,
+    pub declaration: &'static str,
+    /// the compile error if another definition of the const has a different value
+    pub conflict_message: &'static str,
+}
+
+/**
+The consts of a module and of every library it imports, generated by the macros so that two definitions of a const with different values are a compile error, instead of a panic in `WgslShaderModuleUserPortion::import_library` once the shader is created.
+The macros cannot see the contents of imported libraries, so each library exposes its table, and the module checks all of them in a const context.
+*/
+pub struct WgslConstTable {
+    pub consts: &'static [WgslConstEntry],
+    pub imports: &'static [&'static WgslConstTable],
+}
+
+impl WgslConstTable {
+    /// the number of consts, including those of the imported libraries
+    pub const fn len(&self) -> usize {
+        let mut len = self.consts.len();
+        let mut i = 0;
+        while i < self.imports.len() {
+            len += self.imports[i].len();
+            i += 1;
+        }
+        len
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// the consts of this table first, then those of each import in order
+    pub const fn get(&self, index: usize) -> Option<&WgslConstEntry> {
+        if index < self.consts.len() {
+            return Some(&self.consts[index]);
+        }
+        let mut index = index - self.consts.len();
+        let mut i = 0;
+        while i < self.imports.len() {
+            let len = self.imports[i].len();
+            if index < len {
+                return self.imports[i].get(index);
+            }
+            index -= len;
+            i += 1;
+        }
+        None
+    }
+    /// Fails to compile, when used in a const, if two consts have the same name and different declarations. The same declaration twice is allowed, for example when two imported libraries share a dependency.
+    pub const fn assert_no_conflicts(&self) {
+        let len = self.len();
+        let mut i = 0;
+        while i < len {
+            let Some(a) = self.get(i) else {
+                return;
+            };
+            let mut j = i + 1;
+            while j < len {
+                if let Some(b) = self.get(j) {
+                    if str_eq(a.name, b.name) && !str_eq(a.declaration, b.declaration) {
+                        panic!("{}", a.conflict_message);
+                    }
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn entry(name: &'static str, declaration: &'static str) -> WgslConstEntry {
+        WgslConstEntry {
+            name,
+            declaration,
+            conflict_message: "conflict",
+        }
+    }
+
+    const HASHING: WgslConstTable = WgslConstTable {
+        consts: &[entry("SEED", "const SEED : u32 = 7;")],
+        imports: &[],
+    };
+    const GRID: WgslConstTable = WgslConstTable {
+        consts: &[entry("CELL", "const CELL : f32 = 2.0;")],
+        imports: &[&HASHING],
+    };
+
+    #[test]
+    fn test_shared_dependencies_are_not_conflicts() {
+        const MODULE: WgslConstTable = WgslConstTable {
+            consts: &[entry("N", "const N : u32 = 4;")],
+            imports: &[&GRID, &HASHING],
+        };
+        assert_eq!(MODULE.len(), 4);
+        assert_eq!(MODULE.get(2).unwrap().name, "SEED");
+        assert!(MODULE.get(4).is_none());
+        MODULE.assert_no_conflicts();
+    }
+
+    #[test]
+    #[should_panic(expected = "conflict")]
+    fn test_conflicting_values() {
+        const MODULE: WgslConstTable = WgslConstTable {
+            consts: &[entry("SEED", "const SEED : u32 = 8;")],
+            imports: &[&GRID],
+        };
+        MODULE.assert_no_conflicts();
+    }
+}
@@ -1,17 +1,21 @@
 mod code;
 mod const_assignment;
+mod const_table;
 mod custom_type;
 mod function;
 mod input_array;
 mod output_array;
+mod source_map;
 mod wgpu_binding;
 mod workgroup_declaration;
 
 pub use code::*;
 pub use const_assignment::*;
+pub use const_table::*;
 pub use custom_type::*;
 pub use function::*;
 pub use input_array::*;
 pub use output_array::*;
+pub use source_map::*;
 pub use wgpu_binding::*;
 pub use workgroup_declaration::*;
@@ -0,0 +1,42 @@
+/// The rust source lines that the generated wgsl came from, so that shader errors can point at the user's code instead of the generated wgsl.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct WgslSourceMap {
+    pub sections: Vec<WgslSectionSourceMap>,
+}
+
+/// Source map of a single user defined const, type or function.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WgslSectionSourceMap {
+    /// name of the const, type or function, in the generated wgsl
+    pub name: String,
+    /// the rust file the section was defined in
+    pub file: String,
+    /// the rust line (starting at 1) of each line of the section's wgsl code
+    pub rust_lines: Vec<u32>,
+}
+
+impl WgslSourceMap {
+    pub fn empty() -> Self {
+        Self { sections: vec![] }
+    }
+    pub fn section(&self, name: &str) -> Option<&WgslSectionSourceMap> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+    /// Adds the sections of another source map, skipping names that already have one.
+    pub fn import(&mut self, other: WgslSourceMap) {
+        for section in other.sections {
+            if self.section(&section.name).is_none() {
+                self.sections.push(section);
+            }
+        }
+    }
+}
+
+impl WgslSectionSourceMap {
+    /// `file:line` of the rust code that generated line `line_in_section` (starting at 0) of the section
+    pub fn rust_location(&self, line_in_section: usize) -> Option<String> {
+        self.rust_lines
+            .get(line_in_section)
+            .map(|line| format!("{}:{}", self.file, line))
+    }
+}
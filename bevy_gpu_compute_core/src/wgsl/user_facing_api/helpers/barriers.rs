@@ -0,0 +1,15 @@
+#![allow(non_snake_case)]
+//! WGSL's synchronization builtins, named as they are in WGSL so that calls are copied into the shader unchanged.
+//! Every invocation of a workgroup has to reach them at the same time, so they cannot be called inside a branch that depends on the iteration position, or after some invocations returned early. The macro reports such calls as compile errors.
+//! On the CPU, the runner runs the invocations of each workgroup in lockstep when an entry point calls a barrier, so a barrier waits for the other invocations there too, see `for_each_position_in_lockstep`. Called outside of such a run, for example by calling an entry point directly, they do nothing.
+
+use crate::wait_for_workgroup;
+
+/// Waits until every invocation of the workgroup reaches this point, and makes their writes to workgroup memory visible to each other.
+pub fn workgroupBarrier() {
+    wait_for_workgroup();
+}
+/// Waits until every invocation of the workgroup reaches this point, and makes their writes to storage buffers, like outputs, visible to each other.
+pub fn storageBarrier() {
+    wait_for_workgroup();
+}
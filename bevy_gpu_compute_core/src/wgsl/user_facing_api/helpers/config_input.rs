@@ -4,4 +4,8 @@ impl WgslConfigInput {
     pub fn get<T>() -> T {
         unimplemented!()
     }
+    /// Same as `get`, for a config declared with an explicit binding name, like `#[wgsl_config(name = "previous_settings")]`. The name must be a string literal.
+    pub fn get_named<T>(_name: &str) -> T {
+        unimplemented!()
+    }
 }
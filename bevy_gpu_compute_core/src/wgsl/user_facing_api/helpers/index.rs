@@ -0,0 +1,6 @@
+/// Integer types that can be used as an index into inputs and outputs. On the GPU they are all 32 bit, `usize` becomes `u32` and `isize` becomes `i32`, so the same code can index rust arrays with `usize` and still be converted to WGSL.
+pub trait WgslIndex: Copy {}
+impl WgslIndex for u32 {}
+impl WgslIndex for i32 {}
+impl WgslIndex for usize {}
+impl WgslIndex for isize {}
@@ -1,6 +1,10 @@
+mod barriers;
 mod config_input;
+mod index;
 mod output;
 mod vec_input;
+pub use barriers::*;
 pub use config_input::*;
+pub use index::*;
 pub use output::*;
 pub use vec_input::*;
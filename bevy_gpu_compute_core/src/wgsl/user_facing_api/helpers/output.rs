@@ -1,3 +1,5 @@
+use super::WgslIndex;
+
 /**
  * All outputs are arrays/vectors.
  * No "get" type methods are implemented, sinc GPU operations are massively parallel, and you should not be READING from your outputs since you will have no way of knowing if another thread has already touched a certain output or not handled it yet.  //todo: (need to add a link to an article explaining this)
@@ -10,7 +12,7 @@ impl WgslOutput {
     pub fn push<T>(_val: T) {
         unimplemented!()
     }
-    pub fn set<T>(_index: u32, _val: T) {
+    pub fn set<T>(_index: impl WgslIndex, _val: T) {
         unimplemented!()
     }
     /// returns the user-input maximum number of elements that can be stored in the output for this specific type.
@@ -21,4 +23,17 @@ impl WgslOutput {
     pub fn len<T>() -> u32 {
         unimplemented!()
     }
+    /// The `_named` methods are the same as their unnamed counterparts, but for an output declared with an explicit binding name, like `#[wgsl_output_vec(name = "next")]`. The name must be a string literal.
+    pub fn push_named<T>(_name: &str, _val: T) {
+        unimplemented!()
+    }
+    pub fn set_named<T>(_name: &str, _index: impl WgslIndex, _val: T) {
+        unimplemented!()
+    }
+    pub fn max_len_named<T>(_name: &str) -> u32 {
+        unimplemented!()
+    }
+    pub fn len_named<T>(_name: &str) -> u32 {
+        unimplemented!()
+    }
 }
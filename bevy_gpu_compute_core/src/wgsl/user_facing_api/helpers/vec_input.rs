@@ -1,10 +1,20 @@
+use super::WgslIndex;
+
 /// These method are named "vec" because per this library API you input your data as variable-sized vectors. But keep in mind that on the actual GPU these are all fixed-length arrays.
 pub struct WgslVecInput {}
 impl WgslVecInput {
     pub fn vec_len<T>() -> u32 {
         unimplemented!()
     }
-    pub fn vec_val<T>(_index: u32) -> T {
+    pub fn vec_val<T>(_index: impl WgslIndex) -> T {
+        unimplemented!()
+    }
+    /// Same as `vec_len`, for an input declared with an explicit binding name, like `#[wgsl_input_array(name = "prev")]`. The name must be a string literal.
+    pub fn vec_len_named<T>(_name: &str) -> u32 {
+        unimplemented!()
+    }
+    /// Same as `vec_val`, for an input declared with an explicit binding name, like `#[wgsl_input_array(name = "prev")]`. The name must be a string literal.
+    pub fn vec_val_named<T>(_name: &str, _index: impl WgslIndex) -> T {
         unimplemented!()
     }
 }
@@ -1,4 +1,5 @@
 /// This is a representation of wgpu "GlobalId", but for ease of understanding we have renamed it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct WgslIterationPosition {
     pub x: u32,
     pub y: u32,
@@ -98,6 +98,7 @@ mod tests {
     }
     #[test]
     fn can_use_with_bytemuck() {
+        #[allow(dead_code)]
         pub mod test_module {
             use super::*;
             /// user types
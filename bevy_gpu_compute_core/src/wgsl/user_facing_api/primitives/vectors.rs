@@ -31,6 +31,33 @@ macro_rules! impl_vector {
             )+
         }
 
+        impl crate::RandomFields for $name {
+            fn random_fields(rng: &mut crate::SplitMix64) -> Self {
+                $(let $field = crate::RandomFields::random_fields(rng);)+
+                Self::new($($field),+)
+            }
+        }
+
+        impl crate::CompareFields for $name {
+            fn compare_fields(
+                &self,
+                other: &Self,
+                path: &str,
+                tolerance: &crate::FloatTolerance,
+                differences: &mut Vec<crate::FieldDifference>,
+            ) {
+                $(
+                    crate::CompareFields::compare_fields(
+                        &self.$field,
+                        &other.$field,
+                        &format!("{}.{}", path, stringify!($field)),
+                        tolerance,
+                        differences,
+                    );
+                )+
+            }
+        }
+
         impl std::ops::Index<i32> for $name {
             type Output = $type;
 
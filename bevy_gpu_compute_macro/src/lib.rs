@@ -1,6 +1,4 @@
-#![feature(allocator_api)]
-
-use pipeline::lib::CompilerPipeline;
+use pipeline::{lib::CompilerPipeline, module_options::ModuleOptions};
 use proc_macro::TokenStream;
 use proc_macro_error::{proc_macro_error, set_dummy};
 use syn::parse_macro_input;
@@ -52,17 +50,80 @@ let x = my_vec3.x();
     #use bevy_gpu_compute_corepu_compute_core::wgsl_in_rust_helpers::*;
     const MY_CONST: Vec3Bool = Vec3Bool::new(true, false, true);
     ```
+* Doc comments and attributes like `#[allow(...)]` or `#[inline]` are kept on the CPU version of the module, and removed from the generated WGSL. Doc comments on input, output and config types are also copied onto the generated builder methods. Use `#[wgsl_shader_module(emit_doc_comments)]` to keep the doc comments of functions, types and consts in the WGSL as `//` comments, which can help when reading the generated shader.
+* By default the CPU version of the module uses rust's semantics, so integer overflow panics in debug builds and so does reading an input out of range. With `#[wgsl_shader_module(gpu_semantics)]` it behaves like the GPU instead: integer arithmetic wraps, division by zero returns the dividend, `as u32` and `as i32` convert floats like WGSL does, out of range input reads return zeroed values and out of range output writes are dropped. Use it when CPU results are a reference for GPU results. Consts are not affected.
+* The `main` function takes a single `WgslIterationPosition` parameter. It can have any name, or be destructured like `fn main(WgslIterationPosition { x, y: row, .. }: WgslIterationPosition)`, but it cannot be assigned to.
+* The generated WGSL is parsed and validated with naga while compiling, so a shader that the GPU would reject fails `cargo build` with an error pointing at the rust code that produced it. Modules that import a `#[wgsl_shader_library]`, and shaders that use `f16`, can only be checked when the shader is created at runtime, where the error messages also point at the rust file and line that produced each reported line of WGSL.
+* Barriers like `workgroupBarrier()` must be reached by every invocation of a workgroup, so calling one inside a branch that depends on the iteration position, or after some invocations returned early, is a compile error. On the CPU, entry points that call a barrier run the invocations of each workgroup in lockstep, so barriers wait for the other invocations like on the GPU.
+* Functions cannot call themselves, directly or through other functions, since WGSL does not allow recursion.
+* Helper functions, types and consts that are not used by an entry point or a binding are left out of the generated WGSL, but are still part of the CPU version of the module. `#[wgsl_shader_library]` modules keep all of them.
+* Names that are keywords or reserved words in WGSL, like `target`, `filter`, `ptr` or `sampler`, cannot be used for functions, variables, consts, types or fields, even though they are valid in rust. The macro reports each of them as a compile error.
+* Misused WGSL helpers, like `WgslOutput::set` on a `#[wgsl_output_vec]` type, a misspelled helper or type name, or a helper that reads inputs outside of the main function, are all reported at once, each pointing at the offending call, often with a suggestion of what you meant.
 * If you see the error `the trait bound `bool: Pod` is not satisfied...` make sure you are not trying to use a `bool` in any input data or output data. The `bool` type CAN be used but only ON the GPU, it cannot be passed between the CPU and GPU.
  */
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn wgsl_shader_module(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn wgsl_shader_module(attr: TokenStream, item: TokenStream) -> TokenStream {
     set_dummy(item.clone().into());
+    let options = ModuleOptions::from_attribute_args(attr.into());
     let module = parse_macro_input!(item as syn::ItemMod);
     let compiler_pipeline = CompilerPipeline::default();
-    compiler_pipeline.compile(module).into()
+    compiler_pipeline.compile(module, options).into()
 }
 
+/**
+A reusable collection of WGSL helper functions, consts and helper types, that can be shared between shader modules instead of copy-pasting them into each one.
+
+Libraries follow the same rules as `#[wgsl_shader_module]`, except that they cannot have a `main` function or any `#[wgsl_config]`, `#[wgsl_input_array]`, `#[wgsl_output_array]` or `#[wgsl_output_vec]` types, since they have no bindings of their own.
+
+Import a library into a shader module (or into another library) with a glob import of its path. Its definitions are added to the generated WGSL, and are available to the CPU version of the module as normal rust items:
+```ignore
+#[wgsl_shader_library]
+mod hashing {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    const HASH_MULTIPLIER: u32 = 747796405;
+    fn hash(x: u32) -> u32 {
+        return x * HASH_MULTIPLIER + 2891336453;
+    }
+}
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use crate::hashing::*;
+    // ...
+}
+```
+A const defined with different values by the module and the libraries it imports is a compile error.
+*/
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn wgsl_shader_library(attr: TokenStream, item: TokenStream) -> TokenStream {
+    set_dummy(item.clone().into());
+    let options = ModuleOptions::from_attribute_args(attr.into());
+    let module = parse_macro_input!(item as syn::ItemMod);
+    let compiler_pipeline = CompilerPipeline::library();
+    compiler_pipeline.compile(module, options).into()
+}
+
+/**
+Marks a function as an additional entry point (kernel) of the shader module, alongside or instead of `main`. Entry points follow the same rules as `main`, and share all of the module's types and bindings, so several passes over the same data can live in one module:
+```ignore
+#[wgsl_entry]
+fn clear(iter_pos: WgslIterationPosition) {
+    WgslOutput::set::<Cell>(iter_pos.x, 0);
+}
+#[wgsl_entry]
+fn scatter(iter_pos: WgslIterationPosition) {
+    // ...
+}
+```
+Choose which entry points a task runs, in order, with `GpuTaskCommands::run_entry_points`. `GpuTaskCommands::run` runs `main`, or the first entry point if there is no `main`. On the CPU every entry point becomes a function with the same parameters as `main`.
+*/
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn wgsl_entry(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    return item;
+}
 /// used to help this library figure out what to do with user-defined types
 #[proc_macro_attribute]
 #[proc_macro_error]
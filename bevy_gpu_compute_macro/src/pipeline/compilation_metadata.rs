@@ -6,4 +6,6 @@ pub struct CompilationMetadata {
     pub custom_types: Option<Vec<CustomType>>,
     pub wgsl_module_user_portion: Option<WgslShaderModuleUserPortion>,
     pub typesafe_buffer_builders: Option<TokenStream>,
+    /// paths of the `#[wgsl_shader_library]` modules imported with `use path::*;`
+    pub library_imports: Option<Vec<syn::Path>>,
 }
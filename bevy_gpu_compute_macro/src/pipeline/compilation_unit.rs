@@ -2,12 +2,15 @@ use bevy_gpu_compute_core::wgsl::shader_module::user_defined_portion::WgslShader
 use proc_macro2::TokenStream;
 
 use super::{
-    compilation_metadata::CompilationMetadata,
+    compilation_metadata::CompilationMetadata, module_options::ModuleOptions,
     phases::custom_type_collector::custom_type::CustomType,
 };
 
 pub struct CompilationUnit {
     original_rust_module: syn::ItemMod,
+    /// true for `#[wgsl_shader_library]` modules, which have no entry point or bindings
+    is_library: bool,
+    options: ModuleOptions,
     rust_module_for_cpu: Option<syn::ItemMod>,
     rust_module_for_gpu: Option<syn::ItemMod>,
     compiled_tokens: Option<TokenStream>,
@@ -15,9 +18,15 @@ pub struct CompilationUnit {
 }
 
 impl CompilationUnit {
-    pub fn new(original_rust_module: syn::ItemMod) -> Self {
+    pub fn new(
+        original_rust_module: syn::ItemMod,
+        is_library: bool,
+        options: ModuleOptions,
+    ) -> Self {
         CompilationUnit {
             original_rust_module,
+            is_library,
+            options,
             rust_module_for_cpu: None,
             rust_module_for_gpu: None,
             compiled_tokens: None,
@@ -25,9 +34,16 @@ impl CompilationUnit {
                 custom_types: None,
                 wgsl_module_user_portion: None,
                 typesafe_buffer_builders: None,
+                library_imports: None,
             },
         }
     }
+    pub fn is_library(&self) -> bool {
+        self.is_library
+    }
+    pub fn options(&self) -> &ModuleOptions {
+        &self.options
+    }
     pub fn rust_module_for_gpu(&self) -> &syn::ItemMod {
         if self.rust_module_for_gpu.is_none() {
             panic!("rust_module_for_gpu is not set");
@@ -86,4 +102,13 @@ impl CompilationUnit {
         }
         self.metadata.typesafe_buffer_builders.as_ref().unwrap()
     }
+    pub fn set_library_imports(&mut self, library_imports: Vec<syn::Path>) {
+        self.metadata.library_imports = Some(library_imports);
+    }
+    pub fn library_imports(&self) -> &Vec<syn::Path> {
+        if self.metadata.library_imports.is_none() {
+            panic!("library_imports is not set");
+        }
+        self.metadata.library_imports.as_ref().unwrap()
+    }
 }
@@ -0,0 +1,12 @@
+use syn::ItemFn;
+
+pub const ENTRY_POINT_ATTRIBUTE_NAME: &str = "wgsl_entry";
+
+/// the function called "main" is always an entry point, any other function is one if it has a `#[wgsl_entry]` attribute
+pub fn is_entry_point(func: &ItemFn) -> bool {
+    func.sig.ident == "main"
+        || func
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident(ENTRY_POINT_ATTRIBUTE_NAME))
+}
@@ -8,11 +8,13 @@ use super::phases::{
     non_mutating_tree_validation::compiler_phase::NonMutatingTreeValidation,
     typesafe_buffer_builders_generator::compiler_phase::TypesafeBufferBuildersGenerator,
     wgsl_helper_transformer::compiler_phase::WgslHelperTransformer,
+    wgsl_validator::compiler_phase::WgslValidator,
 };
-use crate::pipeline::compilation_unit::CompilationUnit;
+use crate::pipeline::{compilation_unit::CompilationUnit, module_options::ModuleOptions};
 
 pub struct CompilerPipeline {
     phases: Vec<Box<dyn CompilerPhase>>,
+    is_library: bool,
 }
 
 impl Default for CompilerPipeline {
@@ -24,15 +26,31 @@ impl Default for CompilerPipeline {
                 Box::new(TypesafeBufferBuildersGenerator {}),
                 Box::new(WgslHelperTransformer {}),
                 Box::new(GpuResourceMngmntAndWgslGenerator {}),
+                Box::new(WgslValidator {}),
                 Box::new(ModuleForRustUsageCleaner {}),
                 Box::new(FinalStructureGenerator {}),
             ],
+            is_library: false,
         }
     }
 }
 impl CompilerPipeline {
-    pub fn compile(&self, module: syn::ItemMod) -> TokenStream {
-        let mut unit = CompilationUnit::new(module);
+    /// for `#[wgsl_shader_library]` modules, which have no bindings so no buffer builders are generated
+    pub fn library() -> Self {
+        Self {
+            phases: vec![
+                Box::new(NonMutatingTreeValidation {}),
+                Box::new(CustomTypeCollector {}),
+                Box::new(WgslHelperTransformer {}),
+                Box::new(GpuResourceMngmntAndWgslGenerator {}),
+                Box::new(ModuleForRustUsageCleaner {}),
+                Box::new(FinalStructureGenerator {}),
+            ],
+            is_library: true,
+        }
+    }
+    pub fn compile(&self, module: syn::ItemMod, options: ModuleOptions) -> TokenStream {
+        let mut unit = CompilationUnit::new(module, self.is_library, options);
         for phase in &self.phases {
             phase.execute(&mut unit);
         }
@@ -1,5 +1,8 @@
 mod allowed_types;
 mod compilation_metadata;
 mod compilation_unit;
+mod entry_points;
 pub mod lib;
+mod module_items;
+pub mod module_options;
 mod phases;
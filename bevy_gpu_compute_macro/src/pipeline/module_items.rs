@@ -0,0 +1,12 @@
+use syn::{Item, ItemMod};
+
+/// The const, struct, type alias or function with the given name, defined directly in the module
+pub fn find_item<'a>(module: &'a ItemMod, name: &str) -> Option<&'a Item> {
+    module.content.as_ref()?.1.iter().find(|item| match item {
+        Item::Fn(f) => f.sig.ident == name,
+        Item::Struct(s) => s.ident == name,
+        Item::Type(t) => t.ident == name,
+        Item::Const(c) => c.ident == name,
+        _ => false,
+    })
+}
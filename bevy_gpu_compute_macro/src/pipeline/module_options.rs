@@ -0,0 +1,35 @@
+use proc_macro_error::abort;
+use proc_macro2::TokenStream;
+use syn::meta::ParseNestedMeta;
+
+/// Arguments given to the `#[wgsl_shader_module(...)]` or `#[wgsl_shader_library(...)]` attribute
+#[derive(Default, Clone, Debug)]
+pub struct ModuleOptions {
+    /// emit the doc comments of functions, types and consts as `//` comments in the generated wgsl
+    pub emit_doc_comments: bool,
+    /// make the CPU version of the module compute the same results as the GPU, see `apply_gpu_semantics`
+    pub gpu_semantics: bool,
+}
+
+impl ModuleOptions {
+    pub fn from_attribute_args(args: TokenStream) -> Self {
+        let mut options = ModuleOptions::default();
+        let parser = syn::meta::parser(|meta: ParseNestedMeta| {
+            if meta.path.is_ident("emit_doc_comments") {
+                options.emit_doc_comments = true;
+                Ok(())
+            } else if meta.path.is_ident("gpu_semantics") {
+                options.gpu_semantics = true;
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "Unsupported argument, the supported arguments are `emit_doc_comments` and `gpu_semantics`",
+                ))
+            }
+        });
+        if let Err(e) = syn::parse::Parser::parse2(parser, args) {
+            abort!(e.span(), e.to_string());
+        }
+        options
+    }
+}
@@ -2,8 +2,10 @@
 
 // ItemStruct.ident or  ItemType.ident
 
+use proc_macro_error::abort;
+use proc_macro2::TokenStream;
 use quote::ToTokens;
-use syn::visit::Visit;
+use syn::{Attribute, Ident, visit::Visit};
 
 use super::custom_type::{CustomType, CustomTypeKind};
 
@@ -15,20 +17,12 @@ impl<'ast> Visit<'ast> for CustomTypesCollector {
     fn visit_item_struct(&mut self, i: &'ast syn::ItemStruct) {
         syn::visit::visit_item_struct(self, i);
 
-        self.custom_types.push(CustomType::new(
-            &i.ident,
-            CustomTypeKind::from(&i.attrs),
-            i.to_token_stream(),
-        ));
+        self.add(&i.ident, &i.attrs, i.to_token_stream());
     }
 
     fn visit_item_type(&mut self, i: &'ast syn::ItemType) {
         syn::visit::visit_item_type(self, i);
-        self.custom_types.push(CustomType::new(
-            &i.ident,
-            CustomTypeKind::from(&i.attrs),
-            i.to_token_stream(),
-        ));
+        self.add(&i.ident, &i.attrs, i.to_token_stream());
     }
 }
 
@@ -38,6 +32,46 @@ impl CustomTypesCollector {
             custom_types: vec![],
         }
     }
+    /// one custom type is added per binding the type is used for
+    fn add(&mut self, ident: &Ident, attrs: &[Attribute], rust_code: TokenStream) {
+        for (kind, binding_name) in CustomTypeKind::all_from_attributes(attrs) {
+            let custom_type =
+                CustomType::new_named(ident, binding_name.as_ref(), kind, rust_code.clone());
+            self.validate_binding_is_unique(&custom_type);
+            self.custom_types.push(custom_type);
+        }
+    }
+    /// the names of the generated builder methods and shader variables must not collide
+    fn validate_binding_is_unique(&self, new: &CustomType) {
+        let group = |kind: &CustomTypeKind| match kind {
+            CustomTypeKind::OutputArray | CustomTypeKind::OutputVec => Some(0),
+            CustomTypeKind::InputArray => Some(1),
+            CustomTypeKind::Uniform => Some(2),
+            CustomTypeKind::GpuOnlyHelperType
+            | CustomTypeKind::ArrayLengthVariable
+            | CustomTypeKind::LibraryType => None,
+        };
+        if group(&new.kind).is_none() {
+            return;
+        }
+        if let Some(existing) = self.custom_types.iter().find(|c| {
+            group(&c.kind) == group(&new.kind) && c.name.snake_case == new.name.snake_case
+        }) {
+            let span = new
+                .name
+                .binding_name
+                .as_ref()
+                .unwrap_or(&new.name.name)
+                .span();
+            abort!(
+                span,
+                "A {:?} binding named `{}` already exists (declared on `{}`). When the same type is used for more than one binding of the same kind, give each binding a distinct name, like `#[wgsl_input_array(name = \"prev\")]`.",
+                existing.kind,
+                new.name.key(),
+                existing.name.name
+            );
+        }
+    }
 }
 
 pub fn collect_custom_types(original_rust_module: &syn::ItemMod) -> Vec<CustomType> {
@@ -1,11 +1,24 @@
 use crate::pipeline::phases::custom_type_collector::collect::collect_custom_types;
+use crate::pipeline::phases::custom_type_collector::library_imports::{
+    collect_library_imports, collect_library_types,
+};
+use crate::pipeline::phases::custom_type_collector::validate_layouts::validate_layouts;
+use crate::pipeline::phases::custom_type_collector::validate_reserved_names::validate_reserved_names;
 use crate::pipeline::{compilation_unit::CompilationUnit, phases::compiler_phase::CompilerPhase};
 
 pub struct CustomTypeCollector;
 
 impl CompilerPhase for CustomTypeCollector {
     fn execute(&self, input: &mut CompilationUnit) {
-        let custom_types = collect_custom_types(input.original_rust_module());
+        let mut custom_types = collect_custom_types(input.original_rust_module());
+        let library_imports = collect_library_imports(input.original_rust_module());
+        if !library_imports.is_empty() {
+            let library_types = collect_library_types(input.original_rust_module(), &custom_types);
+            custom_types.extend(library_types);
+        }
+        validate_layouts(&custom_types);
+        validate_reserved_names(input.original_rust_module(), &custom_types);
         input.set_custom_types(custom_types);
+        input.set_library_imports(library_imports);
     }
 }
@@ -1,8 +1,7 @@
-use std::alloc::Global;
-
 use bevy_gpu_compute_core::wgsl::shader_sections::{WgslShaderModuleSectionCode, WgslType};
+use proc_macro_error::abort;
 use proc_macro2::TokenStream;
-use syn::{Attribute, Ident};
+use syn::{Attribute, Ident, LitStr, Meta};
 
 use crate::pipeline::phases::gpu_resource_mngmnt_and_wgsl_generator::to_wgsl_syntax::convert_file_to_wgsl;
 
@@ -16,24 +15,63 @@ pub enum CustomTypeKind {
     OutputArray,
     OutputVec,
     ArrayLengthVariable,
+    /// defined in an imported `#[wgsl_shader_library]`, so its definition is not known here
+    LibraryType,
+}
+
+impl CustomTypeKind {
+    /// A type can be used for several bindings at once, for example as both an input array and an output array, so every relevant attribute produces its own kind, along with the optional binding name given like `#[wgsl_input_array(name = "prev")]`.
+    /// Types without any of these attributes are helper types.
+    pub fn all_from_attributes(attrs: &[Attribute]) -> Vec<(CustomTypeKind, Option<Ident>)> {
+        let kinds: Vec<(CustomTypeKind, Option<Ident>)> = attrs
+            .iter()
+            .filter_map(|attr| {
+                let kind = if attr.path().is_ident("wgsl_config") {
+                    CustomTypeKind::Uniform
+                } else if attr.path().is_ident("wgsl_input_array") {
+                    CustomTypeKind::InputArray
+                } else if attr.path().is_ident("wgsl_output_array") {
+                    CustomTypeKind::OutputArray
+                } else if attr.path().is_ident("wgsl_output_vec") {
+                    CustomTypeKind::OutputVec
+                } else {
+                    return None;
+                };
+                Some((kind, binding_name_from_attribute(attr)))
+            })
+            .collect();
+        if kinds.is_empty() {
+            vec![(CustomTypeKind::GpuOnlyHelperType, None)]
+        } else {
+            kinds
+        }
+    }
 }
 
-impl From<&Vec<Attribute, Global>> for CustomTypeKind {
-    fn from(attrs: &Vec<Attribute, Global>) -> Self {
-        for attr in attrs {
-            if attr.path().is_ident("wgsl_config") {
-                return CustomTypeKind::Uniform;
-            } else if attr.path().is_ident("wgsl_input_array") {
-                return CustomTypeKind::InputArray;
-            } else if attr.path().is_ident("wgsl_output_array") {
-                return CustomTypeKind::OutputArray;
-            } else if attr.path().is_ident("wgsl_output_vec") {
-                return CustomTypeKind::OutputVec;
-            }
+fn binding_name_from_attribute(attr: &Attribute) -> Option<Ident> {
+    if let Meta::Path(_) = attr.meta {
+        return None;
+    }
+    let mut binding_name: Option<LitStr> = None;
+    if let Err(e) = attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            binding_name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("Unsupported argument, the only supported argument is `name = \"...\"`"))
         }
-        CustomTypeKind::GpuOnlyHelperType
+    }) {
+        abort!(e.span(), e.to_string());
     }
+    binding_name.map(|lit| match syn::parse_str::<Ident>(&lit.value()) {
+        Ok(ident) => Ident::new(&ident.to_string(), lit.span()),
+        Err(_) => abort!(
+            lit.span(),
+            "Binding names must be valid rust identifiers, since they are used to name the generated builder methods and shader variables"
+        ),
+    })
 }
+
 #[derive(Clone, Debug)]
 pub struct CustomType {
     pub name: CustomTypeIdents,
@@ -48,6 +86,52 @@ impl CustomType {
             rust_code: type_def_code,
         }
     }
+    pub fn new_named(
+        name: &Ident,
+        binding_name: Option<&Ident>,
+        kind: CustomTypeKind,
+        type_def_code: TokenStream,
+    ) -> Self {
+        Self {
+            name: CustomTypeIdents::new_named(name, binding_name),
+            kind,
+            rust_code: type_def_code,
+        }
+    }
+    /// the identifiers generated for this binding, with a description of what each is used for
+    pub fn generated_idents(&self) -> Vec<(Ident, &'static str)> {
+        match self.kind {
+            CustomTypeKind::Uniform => vec![(self.name.uniform(), "config")],
+            CustomTypeKind::InputArray => vec![
+                (self.name.input_array(), "input array"),
+                (self.name.input_array_length(), "input array length"),
+            ],
+            CustomTypeKind::OutputArray => vec![
+                (self.name.output_array(), "output array"),
+                (self.name.output_array_length(), "output array length"),
+            ],
+            CustomTypeKind::OutputVec => vec![
+                (self.name.output_array(), "output vec"),
+                (self.name.output_array_length(), "output vec length"),
+                (self.name.counter(), "output vec counter"),
+                (self.name.index(), "output vec index"),
+            ],
+            CustomTypeKind::GpuOnlyHelperType
+            | CustomTypeKind::ArrayLengthVariable
+            | CustomTypeKind::LibraryType => vec![],
+        }
+    }
+    /// the doc comments of the type definition, so that they can be copied onto the generated builder methods
+    pub fn doc_attributes(&self) -> Vec<Attribute> {
+        match syn::parse2::<syn::Item>(self.rust_code.clone()) {
+            Ok(syn::Item::Struct(s)) => s.attrs,
+            Ok(syn::Item::Type(t)) => t.attrs,
+            _ => vec![],
+        }
+        .into_iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .collect()
+    }
     pub fn into_wgsl_type(self, custom_types: &Vec<CustomType>) -> WgslType {
         WgslType {
             name: self.name.into(),
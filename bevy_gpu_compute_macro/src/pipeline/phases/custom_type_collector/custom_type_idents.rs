@@ -1,4 +1,4 @@
-use bevy_gpu_compute_core::wgsl::shader_custom_type_name::ShaderCustomTypeName;
+use bevy_gpu_compute_core::wgsl::shader_custom_type_name::{ShaderCustomTypeName, to_snake_case};
 use proc_macro2::Span;
 use quote::format_ident;
 use syn::Ident;
@@ -7,62 +7,60 @@ use syn::Ident;
 
 pub struct CustomTypeIdents {
     pub name: Ident,
+    /// set when the user gave this binding an explicit name, like `#[wgsl_input_array(name = "prev")]`, the other idents are then derived from it instead of from the type name
+    pub binding_name: Option<Ident>,
     pub upper: Ident,
-    pub lower: Ident,
+    /// acronym aware snake case of the binding name, used for the generated shader variables and builder methods
     pub snake_case: Ident,
 }
 impl CustomTypeIdents {
     pub fn new(name: &Ident) -> Self {
-        let upper = Ident::new(&name.to_string().to_uppercase(), Span::call_site());
-        let lower = Ident::new(&name.to_string().to_lowercase(), Span::call_site());
-        let snake_case = Self::pascal_case_to_snake_case(&name.to_string());
+        Self::new_named(name, None)
+    }
+    pub fn new_named(name: &Ident, binding_name: Option<&Ident>) -> Self {
+        let key = binding_name.unwrap_or(name).to_string();
+        let snake_case = to_snake_case(&key);
         Self {
             name: name.clone(),
-            upper,
-            lower,
-            snake_case,
+            binding_name: binding_name.cloned(),
+            upper: Ident::new(&snake_case.to_uppercase(), Span::call_site()),
+            snake_case: Ident::new(&snake_case, Span::call_site()),
         }
     }
+    /// identifies the data of this binding, for example in the maps of the type-erased input and output data
+    pub fn key(&self) -> String {
+        self.binding_name.as_ref().unwrap_or(&self.name).to_string()
+    }
     pub fn eq(&self, other: &Ident) -> bool {
         self.name == *other
     }
-    pub fn uniform(&self) -> &Ident {
-        &self.lower
+    pub fn uniform(&self) -> Ident {
+        format_ident!("{}_config", self.snake_case)
     }
     pub fn input_array_length(&self) -> Ident {
         format_ident!("{}_INPUT_ARRAY_LENGTH", self.upper)
     }
     pub fn input_array(&self) -> Ident {
-        format_ident!("{}_input_array", self.lower)
+        format_ident!("{}_input_array", self.snake_case)
     }
 
     pub fn output_array_length(&self) -> Ident {
         format_ident!("{}_OUTPUT_ARRAY_LENGTH", self.upper)
     }
     pub fn output_array(&self) -> Ident {
-        format_ident!("{}_output_array", self.lower)
+        format_ident!("{}_output_array", self.snake_case)
     }
 
     pub fn counter(&self) -> Ident {
-        format_ident!("{}_counter", self.lower)
+        format_ident!("{}_counter", self.snake_case)
     }
     pub fn index(&self) -> Ident {
-        format_ident!("{}_output_array_index", self.lower)
-    }
-    fn pascal_case_to_snake_case(pascal_case: &str) -> Ident {
-        let mut snake_case = String::new();
-        for (i, c) in pascal_case.chars().enumerate() {
-            if c.is_uppercase() && i != 0 {
-                snake_case.push('_');
-            }
-            snake_case.push(c.to_lowercase().next().unwrap());
-        }
-        format_ident!("{}", snake_case)
+        format_ident!("{}_output_array_index", self.snake_case)
     }
 }
 
 impl From<CustomTypeIdents> for ShaderCustomTypeName {
     fn from(val: CustomTypeIdents) -> Self {
-        ShaderCustomTypeName::new(&val.name.to_string())
+        ShaderCustomTypeName::new_named(&val.name.to_string(), &val.key())
     }
 }
@@ -0,0 +1,140 @@
+/*!
+The contents of a `#[wgsl_shader_library]` are not visible to the macro expanding a module that imports it, the library definitions are only added to the shader module at runtime. So here we just record which libraries are imported, and treat any type used in the module that is not defined in it as coming from one of those libraries.
+*/
+use proc_macro2::{Span, TokenStream};
+use syn::{Ident, Item, ItemMod, UseTree, visit::Visit};
+
+use crate::pipeline::allowed_types::WGSL_NATIVE_TYPES;
+use crate::pipeline::phases::non_mutating_tree_validation::validate_use_statements::is_library_import;
+
+use super::custom_type::{CustomType, CustomTypeKind};
+
+/// `crate::my_lib` for `use crate::my_lib::*;`
+pub fn collect_library_imports(module: &ItemMod) -> Vec<syn::Path> {
+    let mut paths = vec![];
+    if let Some((_, items)) = &module.content {
+        for item in items {
+            if let Item::Use(use_stmt) = item {
+                if is_library_import(use_stmt) {
+                    collect_glob_paths(&use_stmt.tree, &mut vec![], &mut paths);
+                }
+            }
+        }
+    }
+    paths
+}
+
+fn collect_glob_paths(tree: &UseTree, prefix: &mut Vec<Ident>, paths: &mut Vec<syn::Path>) {
+    match tree {
+        UseTree::Path(p) => {
+            prefix.push(p.ident.clone());
+            collect_glob_paths(&p.tree, prefix, paths);
+            prefix.pop();
+        }
+        UseTree::Group(g) => g
+            .items
+            .iter()
+            .for_each(|item| collect_glob_paths(item, prefix, paths)),
+        UseTree::Glob(_) => paths.push(syn::Path {
+            leading_colon: None,
+            segments: prefix.iter().cloned().map(syn::PathSegment::from).collect(),
+        }),
+        UseTree::Name(_) | UseTree::Rename(_) => {}
+    }
+}
+
+pub fn collect_library_types(module: &ItemMod, custom_types: &[CustomType]) -> Vec<CustomType> {
+    let mut collector = LibraryTypesCollector {
+        custom_types,
+        found: vec![],
+    };
+    collector.visit_item_mod(module);
+    collector
+        .found
+        .iter()
+        .map(|name| {
+            CustomType::new(
+                &Ident::new(name, Span::call_site()),
+                CustomTypeKind::LibraryType,
+                TokenStream::new(),
+            )
+        })
+        .collect()
+}
+
+struct LibraryTypesCollector<'a> {
+    custom_types: &'a [CustomType],
+    found: Vec<String>,
+}
+
+impl Visit<'_> for LibraryTypesCollector<'_> {
+    fn visit_type_path(&mut self, t: &syn::TypePath) {
+        syn::visit::visit_type_path(self, t);
+        let Some(ident) = t.path.get_ident() else {
+            return;
+        };
+        let name = ident.to_string();
+        let is_known = WGSL_NATIVE_TYPES.contains(&name.as_str())
+            || ["PodF16", "WgslIterationPosition", "Self"].contains(&name.as_str())
+            || self.custom_types.iter().any(|c| c.name.eq(ident));
+        if !is_known && name.starts_with(char::is_uppercase) && !self.found.contains(&name) {
+            self.found.push(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_collect_library_imports() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                use bevy_gpu_compute_core::wgsl_helpers::*;
+                use crate::libs::hashing::*;
+                use super::{grid::*, noise::*};
+            }
+        };
+        let paths: Vec<String> = collect_library_imports(&module)
+            .iter()
+            .map(|p| p.to_token_stream().to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "crate :: libs :: hashing",
+                "super :: grid",
+                "super :: noise"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_library_types() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                struct Local {
+                    cell: Cell,
+                    v: Vec2F32,
+                }
+                fn f(l: Local, c: Cell) -> Hash {
+                    return hash(l.v.x, c);
+                }
+            }
+        };
+        let local = CustomType::new(
+            &parse_quote!(Local),
+            CustomTypeKind::GpuOnlyHelperType,
+            TokenStream::new(),
+        );
+        let names: Vec<String> = collect_library_types(&module, &[local])
+            .iter()
+            .map(|c| c.name.name.to_string())
+            .collect();
+        assert_eq!(names, vec!["Cell", "Hash"]);
+    }
+}
@@ -2,3 +2,6 @@ pub mod collect;
 pub mod compiler_phase;
 pub mod custom_type;
 pub mod custom_type_idents;
+pub mod library_imports;
+pub mod validate_layouts;
+pub mod validate_reserved_names;
@@ -0,0 +1,390 @@
+/*!
+Types that are transfered between the CPU and the GPU are copied byte for byte (with bytemuck), so the `#[repr(C)]` layout that rust gives them has to be identical to the layout that wgsl gives them in the buffer they are bound to.
+
+For flat types built only from scalars and vectors this is the responsibility of the user, but once structs and arrays are nested inside of transfer types the wgsl alignment rules (vec3 aligned to 16 bytes, array strides rounded up to the element alignment, stricter rules for uniforms, etc.) become very easy to get wrong, so we compute both layouts for every nested member and report any difference at compile time.
+*/
+use proc_macro_error::abort;
+use syn::{Item, ItemStruct, Type, spanned::Spanned, visit::Visit};
+
+use super::custom_type::{CustomType, CustomTypeKind};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Layout {
+    size: u32,
+    align: u32,
+}
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct RustAndWgslLayout {
+    rust: Layout,
+    wgsl: Layout,
+}
+
+pub fn validate_layouts(custom_types: &[CustomType]) {
+    custom_types
+        .iter()
+        .filter(|c| {
+            c.kind != CustomTypeKind::GpuOnlyHelperType && c.kind != CustomTypeKind::LibraryType
+        })
+        .for_each(|c| {
+            let validator = LayoutValidator {
+                custom_types,
+                in_uniform: c.kind == CustomTypeKind::Uniform,
+            };
+            validator.validate_transfer_type(c);
+        });
+}
+
+struct LayoutValidator<'a> {
+    custom_types: &'a [CustomType],
+    /// the uniform address space has stricter alignment rules for nested structs and arrays
+    in_uniform: bool,
+}
+
+impl LayoutValidator<'_> {
+    fn validate_transfer_type(&self, custom_type: &CustomType) {
+        let item = syn::parse2::<Item>(custom_type.rust_code.clone()).unwrap();
+        self.validate_no_pointer_sized_integers(&item, &mut vec![]);
+        let has_nested_members = match &item {
+            Item::Struct(s) => s.fields.iter().any(|f| self.is_nested(&f.ty)),
+            // aliases of plain arrays of scalars or vectors, like `type Position = [f32; 2]`, are flat
+            Item::Type(t) => match &*t.ty {
+                Type::Array(arr) => self.is_nested(&arr.elem),
+                ty => self.is_nested(ty),
+            },
+            _ => false,
+        };
+        if !has_nested_members {
+            return;
+        }
+        let mut visiting = vec![];
+        let Some(layout) = self.item_layout(&item, &mut visiting) else {
+            return;
+        };
+        let wgsl_size = if self.in_uniform {
+            layout.wgsl.size
+        } else {
+            // input and output types are elements of a runtime sized array
+            round_up(layout.wgsl.align, layout.wgsl.size)
+        };
+        if layout.rust.size != wgsl_size {
+            abort!(
+                custom_type.name.name.span(),
+                "`{}` is {} bytes long in rust, but {} bytes long in wgsl, so its data would be corrupted when copied to or from the GPU. Add explicit padding fields (for example `_padding: u32`) to the end of it so that both sizes match.",
+                custom_type.name.name,
+                layout.rust.size,
+                wgsl_size
+            );
+        }
+    }
+
+    /// `usize` and `isize` become 32 bit integers in wgsl, but are usually 64 bits long in rust
+    fn validate_no_pointer_sized_integers(&self, item: &Item, visiting: &mut Vec<String>) {
+        struct TypeIdents(Vec<syn::Ident>);
+        impl<'ast> Visit<'ast> for TypeIdents {
+            fn visit_type_path(&mut self, t: &'ast syn::TypePath) {
+                self.0
+                    .extend(t.path.segments.iter().map(|s| s.ident.clone()));
+                syn::visit::visit_type_path(self, t);
+            }
+        }
+        let mut idents = TypeIdents(vec![]);
+        match item {
+            Item::Struct(s) => idents.visit_fields(&s.fields),
+            Item::Type(t) => idents.visit_type(&t.ty),
+            _ => {}
+        }
+        for ident in idents.0 {
+            if ident == "usize" || ident == "isize" {
+                abort!(
+                    ident.span(),
+                    "`{}` cannot be used in types that are copied to or from the GPU, since it is 32 bits long in wgsl but usually 64 bits long in rust. Use `{}` instead.",
+                    ident,
+                    if ident == "usize" { "u32" } else { "i32" }
+                );
+            }
+            let name = ident.to_string();
+            if visiting.contains(&name) {
+                continue;
+            }
+            if let Some(custom_type) = self.custom_types.iter().find(|c| c.name.eq(&ident)) {
+                if let Ok(nested) = syn::parse2::<Item>(custom_type.rust_code.clone()) {
+                    visiting.push(name);
+                    self.validate_no_pointer_sized_integers(&nested, visiting);
+                    visiting.pop();
+                }
+            }
+        }
+    }
+
+    fn is_nested(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Array(_) => true,
+            Type::Path(p) => p
+                .path
+                .get_ident()
+                .is_some_and(|i| self.custom_types.iter().any(|c| c.name.eq(i))),
+            _ => false,
+        }
+    }
+
+    /// returns None if the layout cannot be determined at compile time (for example an array length that is a constant instead of a literal), in which case no validation is done
+    fn item_layout(&self, item: &Item, visiting: &mut Vec<String>) -> Option<RustAndWgslLayout> {
+        match item {
+            Item::Struct(s) => self.struct_layout(s, visiting),
+            Item::Type(t) => self.type_layout(&t.ty, visiting),
+            _ => None,
+        }
+    }
+
+    fn struct_layout(
+        &self,
+        s: &ItemStruct,
+        visiting: &mut Vec<String>,
+    ) -> Option<RustAndWgslLayout> {
+        let mut rust = Layout { size: 0, align: 1 };
+        let mut wgsl = Layout { size: 0, align: 1 };
+        for field in s.fields.iter() {
+            let member = self.type_layout(&field.ty, visiting)?;
+            let member_wgsl_align = if self.in_uniform && self.is_nested(&field.ty) {
+                round_up(16, member.wgsl.align)
+            } else {
+                member.wgsl.align
+            };
+            let rust_offset = round_up(member.rust.align, rust.size);
+            let wgsl_offset = round_up(member_wgsl_align, wgsl.size);
+            if rust_offset != wgsl_offset || member.rust.size != member.wgsl.size {
+                let field_name = field
+                    .ident
+                    .as_ref()
+                    .map_or("unnamed".to_string(), |i| i.to_string());
+                abort!(
+                    field.ty.span(),
+                    "Field `{}` of `{}` starts at byte {} and is {} bytes long in rust, but starts at byte {} and is {} bytes long in wgsl{}. Add explicit padding fields (for example `_padding: u32`) before it, or inside arrays use types whose size is a multiple of their wgsl alignment (for example `Vec4F32` instead of `Vec3F32`).",
+                    field_name,
+                    s.ident,
+                    rust_offset,
+                    member.rust.size,
+                    wgsl_offset,
+                    member.wgsl.size,
+                    if self.in_uniform {
+                        " (structs and arrays nested in `#[wgsl_config]` types are aligned to 16 bytes)"
+                    } else {
+                        ""
+                    }
+                );
+            }
+            rust.size = rust_offset + member.rust.size;
+            rust.align = rust.align.max(member.rust.align);
+            wgsl.size = wgsl_offset + member.wgsl.size;
+            wgsl.align = wgsl.align.max(member_wgsl_align);
+        }
+        rust.size = round_up(rust.align, rust.size);
+        wgsl.size = round_up(wgsl.align, wgsl.size);
+        Some(RustAndWgslLayout { rust, wgsl })
+    }
+
+    fn type_layout(&self, ty: &Type, visiting: &mut Vec<String>) -> Option<RustAndWgslLayout> {
+        match ty {
+            Type::Array(arr) => {
+                let len = match &arr.len {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(i),
+                        ..
+                    }) => i.base10_parse::<u32>().ok()?,
+                    _ => return None,
+                };
+                let elem = self.type_layout(&arr.elem, visiting)?;
+                let wgsl_elem_align = if self.in_uniform {
+                    round_up(16, elem.wgsl.align)
+                } else {
+                    elem.wgsl.align
+                };
+                let wgsl_stride = round_up(wgsl_elem_align, elem.wgsl.size);
+                Some(RustAndWgslLayout {
+                    rust: Layout {
+                        size: elem.rust.size * len,
+                        align: elem.rust.align,
+                    },
+                    wgsl: Layout {
+                        size: wgsl_stride * len,
+                        align: wgsl_elem_align,
+                    },
+                })
+            }
+            Type::Path(p) => {
+                let ident = p.path.get_ident()?;
+                if let Some(custom_type) = self.custom_types.iter().find(|c| c.name.eq(ident)) {
+                    let name = ident.to_string();
+                    if visiting.contains(&name) {
+                        // recursive types are not valid in either language, rustc will report it
+                        return None;
+                    }
+                    visiting.push(name);
+                    let item = syn::parse2::<Item>(custom_type.rust_code.clone()).ok()?;
+                    let layout = self.item_layout(&item, visiting);
+                    visiting.pop();
+                    layout
+                } else {
+                    builtin_type_layout(&ident.to_string())
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+fn builtin_type_layout(name: &str) -> Option<RustAndWgslLayout> {
+    if let Some(scalar_size) = scalar_size(name) {
+        let layout = Layout {
+            size: scalar_size,
+            align: scalar_size,
+        };
+        return Some(RustAndWgslLayout {
+            rust: layout,
+            wgsl: layout,
+        });
+    }
+    if let Some(rest) = name.strip_prefix("Vec") {
+        let (n, scalar) = rest.split_at_checked(1)?;
+        let n = n.parse::<u32>().ok()?;
+        let s = scalar_size(scalar)?;
+        return Some(RustAndWgslLayout {
+            rust: Layout {
+                size: n * s,
+                align: s,
+            },
+            wgsl: wgsl_vector_layout(n, s),
+        });
+    }
+    if let Some(rest) = name.strip_prefix("Mat") {
+        let (dims, scalar) = rest.split_at_checked(3)?;
+        let (columns, rows) = dims.split_once('x')?;
+        let columns = columns.parse::<u32>().ok()?;
+        let rows = rows.parse::<u32>().ok()?;
+        let s = scalar_size(scalar)?;
+        let column = wgsl_vector_layout(rows, s);
+        return Some(RustAndWgslLayout {
+            rust: Layout {
+                size: columns * rows * s,
+                align: s,
+            },
+            wgsl: Layout {
+                size: columns * round_up(column.align, column.size),
+                align: column.align,
+            },
+        });
+    }
+    None
+}
+
+fn scalar_size(name: &str) -> Option<u32> {
+    match name {
+        "f32" | "i32" | "u32" | "F32" | "I32" | "U32" => Some(4),
+        "PodF16" | "F16" => Some(2),
+        _ => None,
+    }
+}
+
+fn wgsl_vector_layout(n: u32, scalar_size: u32) -> Layout {
+    let align = if n == 2 {
+        2 * scalar_size
+    } else {
+        4 * scalar_size
+    };
+    Layout {
+        size: n * scalar_size,
+        align,
+    }
+}
+
+fn round_up(align: u32, size: u32) -> u32 {
+    size.div_ceil(align) * align
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn custom_type(kind: CustomTypeKind, item: Item) -> CustomType {
+        let (ident, code) = match &item {
+            Item::Struct(s) => (s.ident.clone(), quote!(#s)),
+            Item::Type(t) => (t.ident.clone(), quote!(#t)),
+            _ => unreachable!(),
+        };
+        CustomType::new(&ident, kind, code)
+    }
+
+    #[test]
+    fn test_builtin_layouts() {
+        let vec3 = builtin_type_layout("Vec3F32").unwrap();
+        assert_eq!(vec3.rust, Layout { size: 12, align: 4 });
+        assert_eq!(
+            vec3.wgsl,
+            Layout {
+                size: 12,
+                align: 16
+            }
+        );
+        let mat3x2 = builtin_type_layout("Mat3x2F16").unwrap();
+        assert_eq!(mat3x2.rust, Layout { size: 12, align: 2 });
+        assert_eq!(mat3x2.wgsl, Layout { size: 12, align: 4 });
+        assert!(builtin_type_layout("Vec3Bool").is_none());
+    }
+
+    #[test]
+    fn test_nested_layouts_that_match() {
+        let types = vec![
+            custom_type(
+                CustomTypeKind::GpuOnlyHelperType,
+                parse_quote!(
+                    struct Shape {
+                        radius: f32,
+                        kind: u32,
+                    }
+                ),
+            ),
+            custom_type(
+                CustomTypeKind::InputArray,
+                parse_quote!(
+                    struct Body {
+                        shape: Shape,
+                        verts: [Vec2F32; 4],
+                    }
+                ),
+            ),
+        ];
+        let validator = LayoutValidator {
+            custom_types: &types,
+            in_uniform: false,
+        };
+        let item: Item = parse_quote!(
+            struct Body {
+                shape: Shape,
+                verts: [Vec2F32; 4],
+            }
+        );
+        let layout = validator.item_layout(&item, &mut vec![]).unwrap();
+        assert_eq!(layout.rust, Layout { size: 40, align: 4 });
+        assert_eq!(layout.wgsl, Layout { size: 40, align: 8 });
+    }
+
+    #[test]
+    fn test_uniform_arrays_are_aligned_to_16_bytes() {
+        let validator = LayoutValidator {
+            custom_types: &[],
+            in_uniform: true,
+        };
+        let layout = validator
+            .type_layout(&parse_quote!([Vec4F32; 2]), &mut vec![])
+            .unwrap();
+        assert_eq!(layout.rust.size, layout.wgsl.size);
+        let layout = validator
+            .type_layout(&parse_quote!([f32; 2]), &mut vec![])
+            .unwrap();
+        assert_eq!(layout.rust.size, 8);
+        assert_eq!(layout.wgsl.size, 32);
+    }
+}
@@ -0,0 +1,126 @@
+/*!
+The shader variables of each binding are named after the binding, like `position_input_array` and `POSITION_INPUT_ARRAY_LENGTH` for `#[wgsl_input_array] struct Position`. These names are reserved: a user function, const, variable or type with the same name would silently shadow, or be shadowed by, the generated one, so it is reported as a compile error instead.
+*/
+use proc_macro_error::{abort_if_dirty, emit_error};
+use syn::{Ident, ItemConst, ItemFn, ItemStatic, ItemStruct, ItemType, PatIdent, visit::Visit};
+
+use super::custom_type::CustomType;
+
+pub fn validate_reserved_names(original_rust_module: &syn::ItemMod, custom_types: &[CustomType]) {
+    let errors = find_reserved_name_collisions(original_rust_module, custom_types);
+    if !errors.is_empty() {
+        for error in errors {
+            emit_error!(error.span(), error.to_string());
+        }
+        abort_if_dirty();
+    }
+}
+
+pub fn find_reserved_name_collisions(
+    original_rust_module: &syn::ItemMod,
+    custom_types: &[CustomType],
+) -> Vec<syn::Error> {
+    let mut finder = ReservedNameFinder {
+        custom_types,
+        errors: Vec::new(),
+    };
+    finder.visit_item_mod(original_rust_module);
+    finder.errors
+}
+
+struct ReservedNameFinder<'a> {
+    custom_types: &'a [CustomType],
+    errors: Vec<syn::Error>,
+}
+
+impl ReservedNameFinder<'_> {
+    fn check(&mut self, ident: &Ident) {
+        for custom_type in self.custom_types {
+            if let Some((_, role)) = custom_type
+                .generated_idents()
+                .into_iter()
+                .find(|(generated, _)| generated == ident)
+            {
+                self.errors.push(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "`{}` is reserved, it is the generated name of the {} of the `{}` binding. Rename it to something else.",
+                        ident,
+                        role,
+                        custom_type.name.key()
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ReservedNameFinder<'_> {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.check(&i.sig.ident);
+        syn::visit::visit_item_fn(self, i);
+    }
+    fn visit_item_const(&mut self, i: &'ast ItemConst) {
+        self.check(&i.ident);
+        syn::visit::visit_item_const(self, i);
+    }
+    fn visit_item_static(&mut self, i: &'ast ItemStatic) {
+        self.check(&i.ident);
+        syn::visit::visit_item_static(self, i);
+    }
+    fn visit_item_struct(&mut self, i: &'ast ItemStruct) {
+        self.check(&i.ident);
+        syn::visit::visit_item_struct(self, i);
+    }
+    fn visit_item_type(&mut self, i: &'ast ItemType) {
+        self.check(&i.ident);
+        syn::visit::visit_item_type(self, i);
+    }
+    /// local variables and function parameters
+    fn visit_pat_ident(&mut self, i: &'ast PatIdent) {
+        self.check(&i.ident);
+        syn::visit::visit_pat_ident(self, i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro2::TokenStream;
+    use quote::format_ident;
+    use syn::{ItemMod, parse_quote};
+
+    use super::*;
+    use crate::pipeline::phases::custom_type_collector::custom_type::CustomTypeKind;
+
+    #[test]
+    fn test_reserved_name_collisions() {
+        let module: ItemMod = parse_quote! {
+            mod test {
+                const HTTP_DATA_INPUT_ARRAY_LENGTH: u32 = 3;
+                fn main(iter_pos: WgslIterationPosition) {
+                    let position_counter = 0;
+                    let http_data = 1;
+                }
+            }
+        };
+        let custom_types = vec![
+            CustomType::new(
+                &format_ident!("Position"),
+                CustomTypeKind::OutputVec,
+                TokenStream::new(),
+            ),
+            CustomType::new(
+                &format_ident!("HTTPData"),
+                CustomTypeKind::InputArray,
+                TokenStream::new(),
+            ),
+        ];
+        let errors: Vec<String> = find_reserved_name_collisions(&module, &custom_types)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].starts_with("`HTTP_DATA_INPUT_ARRAY_LENGTH` is reserved, it is the generated name of the input array length of the `HTTPData` binding"));
+        assert!(errors[1].starts_with("`position_counter` is reserved, it is the generated name of the output vec counter of the `Position` binding"));
+    }
+}
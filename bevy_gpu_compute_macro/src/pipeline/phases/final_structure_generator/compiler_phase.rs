@@ -2,7 +2,8 @@ use crate::pipeline::{compilation_unit::CompilationUnit, phases::compiler_phase:
 use quote::quote;
 
 use super::{
-    unaltered_module::generate_unaltered_module, user_facing_module::generate_user_facing_module,
+    unaltered_module::generate_unaltered_module,
+    user_facing_module::{generate_user_facing_library_module, generate_user_facing_module},
 };
 pub struct FinalStructureGenerator;
 
@@ -10,11 +11,20 @@ impl CompilerPhase for FinalStructureGenerator {
     fn execute(&self, input: &mut CompilationUnit) {
         let unaltered_module_to_ensure_complete_rust_compiler_checks =
             generate_unaltered_module(input.original_rust_module());
-        let user_facing_module = generate_user_facing_module(
-            &mut input.wgsl_module_user_portion().clone(),
-            input.rust_module_for_cpu(),
-            input.typesafe_buffer_builders(),
-        );
+        let user_facing_module = if input.is_library() {
+            generate_user_facing_library_module(
+                input.wgsl_module_user_portion(),
+                input.rust_module_for_cpu(),
+                input.library_imports(),
+            )
+        } else {
+            generate_user_facing_module(
+                &mut input.wgsl_module_user_portion().clone(),
+                input.rust_module_for_cpu(),
+                input.typesafe_buffer_builders(),
+                input.library_imports(),
+            )
+        };
         input.set_compiled_tokens(quote! {
                 #unaltered_module_to_ensure_complete_rust_compiler_checks
 
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+
+use bevy_gpu_compute_core::wgsl::shader_module::user_defined_portion::WgslShaderModuleUserPortion;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+/// `run_entry_points_on_cpu` takes the same type erased data that is given to the GPU, calls the CPU version of each given entry point once for every position of the iteration space and returns the outputs in the same shape as `GpuTaskReader::latest_results`. The CPU compute backend runs tasks through it.
+/// Outputs are shared between positions with the thread safe `CpuOutputArray` and `CpuOutputVec`, so the positions can run in any order, or in parallel. The `CpuSchedule` decides the order, and wraps each output so `detect_data_races` can record which invocations set each index.
+/// Entry points in `functions_calling_barriers` run each workgroup in lockstep instead, so that barriers wait for the other invocations like on the GPU.
+/// `run_on_cpu` runs the entry point that `run()` would dispatch: `main`, or the first `#[wgsl_entry]` function if there is no `main`.
+pub fn generate_cpu_runner(
+    wgsl_shader_module: &WgslShaderModuleUserPortion,
+    functions_calling_barriers: &HashSet<String>,
+) -> TokenStream {
+    let Some(default_entry_point) = wgsl_shader_module.entry_points().next() else {
+        return quote!();
+    };
+    let default_entry_point = default_entry_point.name.clone();
+    let entry_point_names = wgsl_shader_module.entry_point_names();
+    let mut setup = TokenStream::new();
+    let mut args = TokenStream::new();
+    let mut finish = TokenStream::new();
+    for uniform in wgsl_shader_module.uniforms.iter() {
+        let var = Ident::new(&uniform.name.uniform(), Span::call_site());
+        let ty = Ident::new(uniform.name.type_name(), Span::call_site());
+        let key = uniform.name.name();
+        let message = format!("the config input `{}` was not set", key);
+        setup.extend(quote! {
+            let #var: #ty = config_inputs.get(#key).expect(#message);
+        });
+        args.extend(quote!(#var,));
+    }
+    for array in wgsl_shader_module.input_arrays.iter() {
+        let var = Ident::new(&array.item_type.name.input_array(), Span::call_site());
+        let ty = Ident::new(array.item_type.name.type_name(), Span::call_site());
+        let key = array.item_type.name.name();
+        let message = format!("the input array `{}` was not set", key);
+        setup.extend(quote! {
+            let #var: Vec<#ty> = input_data.get_values(#key).expect(#message);
+        });
+        args.extend(quote!(&#var,));
+    }
+    for array in wgsl_shader_module.output_arrays.iter() {
+        let var = Ident::new(&array.item_type.name.output_array(), Span::call_site());
+        let max_len = Ident::new(
+            &array.item_type.name.output_array_length(),
+            Span::call_site(),
+        );
+        let ty = Ident::new(array.item_type.name.type_name(), Span::call_site());
+        let key = array.item_type.name.name();
+        let type_name = array.item_type.name.type_name();
+        setup.extend(quote! {
+            let #max_len = max_output_lengths.get_by_name(&ShaderCustomTypeName::new_named(#type_name, #key));
+        });
+        if array.atomic_counter_name.is_some() {
+            setup.extend(quote! {
+                let #var = CpuOutputVec::<#ty>::new(#max_len);
+            });
+        } else {
+            setup.extend(quote! {
+                let #var = CpuOutputArray::<#ty>::new(#max_len);
+            });
+        }
+        args.extend(quote!(schedule.output(&#var, #key, iter_pos),));
+        finish.extend(quote! {
+            bytes_per_wgsl_output_type_name.insert(#key.to_string(), bytemuck::cast_slice(&#var.into_values()).to_vec());
+        });
+    }
+    let dispatches: TokenStream = entry_point_names
+        .iter()
+        .map(|name| {
+            let entry_point = Ident::new(name, Span::call_site());
+            let for_each_position = if functions_calling_barriers.contains(name) {
+                quote!(for_each_position_in_workgroups)
+            } else {
+                quote!(for_each_position)
+            };
+            quote! {
+                #name => schedule.#for_each_position(#name, iteration_space, |iter_pos| {
+                    #entry_point(iter_pos, #args);
+                }),
+            }
+        })
+        .collect();
+    quote! {
+        /// Runs the shader on the CPU, once for every position of the iteration space. With the `rayon` feature the positions run in parallel.
+        /// Takes the same inputs as the GPU task, and returns the outputs in the same shape: output arrays are as long as their max output length, and output vecs contain at most that many values.
+        pub fn run_on_cpu(
+            iteration_space: IterationSpace,
+            config_inputs: &TypeErasedConfigInputData,
+            input_data: &TypeErasedArrayInputData,
+            max_output_lengths: &MaxOutputLengths,
+        ) -> OutputDataBuilder {
+            run_entry_points_on_cpu(
+                &[#default_entry_point.to_string()],
+                iteration_space,
+                config_inputs,
+                input_data,
+                max_output_lengths,
+                &CpuSchedule::default(),
+            )
+            .into()
+        }
+        /// Like `run_on_cpu`, but runs the given entry points one after another over the same outputs, like `GpuTaskCommands::run_entry_points`, and in the order the `schedule` gives.
+        pub fn run_entry_points_on_cpu(
+            entry_points: &[String],
+            iteration_space: IterationSpace,
+            config_inputs: &TypeErasedConfigInputData,
+            input_data: &TypeErasedArrayInputData,
+            max_output_lengths: &MaxOutputLengths,
+            schedule: &CpuSchedule,
+        ) -> TypeErasedArrayOutputData {
+            #setup
+            for entry_point in entry_points {
+                match entry_point.as_str() {
+                    #dispatches
+                    other => panic!(
+                        "Entry point {} not found, the available entry points are {:?}",
+                        other,
+                        [#(#entry_point_names),*]
+                    ),
+                }
+            }
+            let mut bytes_per_wgsl_output_type_name: HashMap<String, Vec<u8>> = HashMap::new();
+            #finish
+            TypeErasedArrayOutputData::new(bytes_per_wgsl_output_type_name)
+        }
+        /// Runs the default entry point on the CPU `orders` times, each time with the invocations in a different random order, and reports output indices that are set by more than one invocation, and outputs that depend on the order. See `bevy_gpu_compute_core::detect_data_races`.
+        pub fn detect_data_races(
+            iteration_space: IterationSpace,
+            config_inputs: &TypeErasedConfigInputData,
+            input_data: &TypeErasedArrayInputData,
+            max_output_lengths: &MaxOutputLengths,
+            orders: usize,
+        ) -> DataRaceReport {
+            bevy_gpu_compute_core::detect_data_races::<Types>(
+                &[#default_entry_point.to_string()],
+                iteration_space,
+                config_inputs,
+                input_data,
+                max_output_lengths,
+                orders,
+            )
+        }
+    }
+}
@@ -1,7 +1,10 @@
 pub mod compiler_phase;
+mod cpu_runner;
 mod generate_required_imports;
+mod output_comparison;
 mod per_component_expansion;
+mod random_inputs;
 mod shader_module_object;
-mod types_for_rust_usage;
+pub mod types_for_rust_usage;
 mod unaltered_module;
 mod user_facing_module;
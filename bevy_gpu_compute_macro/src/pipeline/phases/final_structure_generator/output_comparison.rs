@@ -0,0 +1,38 @@
+use bevy_gpu_compute_core::wgsl::shader_module::user_defined_portion::WgslShaderModuleUserPortion;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+/// `compare_outputs` compares two sets of outputs field by field, for example the results of the GPU task with those of `run_on_cpu`. The `bevy_gpu_compute::testing` harness reaches it through `TypesSpec::compare_outputs`.
+pub fn generate_output_comparison(wgsl_shader_module: &WgslShaderModuleUserPortion) -> TokenStream {
+    let comparisons: TokenStream = wgsl_shader_module
+        .output_arrays
+        .iter()
+        .map(|array| {
+            let field = Ident::new(array.item_type.name.lower(), Span::call_site());
+            let key = array.item_type.name.name();
+            let unordered = array.atomic_counter_name.is_some();
+            quote! {
+                differences.extend(compare_output_values(
+                    #key,
+                    expected.#field.as_deref().unwrap_or_default(),
+                    actual.#field.as_deref().unwrap_or_default(),
+                    #unordered,
+                    tolerance,
+                ));
+            }
+        })
+        .collect();
+    quote! {
+        /// Compares two sets of outputs field by field. Floats may differ by the `tolerance`, and the values of output vecs may be in any order.
+        pub fn compare_outputs(
+            expected: &OutputDataBuilder,
+            actual: &OutputDataBuilder,
+            tolerance: &FloatTolerance,
+        ) -> Vec<FieldDifference> {
+            let mut differences = Vec::new();
+            #comparisons
+            differences
+        }
+    }
+}
@@ -4,7 +4,7 @@ use bevy_gpu_compute_core::{
     wgsl::shader_custom_type_name::ShaderCustomTypeName,
     wgsl::shader_sections::{
         WgslConstAssignment, WgslFunction, WgslInputArray, WgslOutputArray,
-        WgslShaderModuleSectionCode, WgslType,
+        WgslShaderModuleSectionCode, WgslSourceMap, WgslType,
     },
 };
 use proc_macro2::TokenStream;
@@ -35,9 +35,16 @@ impl ToStructInitializer {
 
     pub fn custom_type_name(c: &ShaderCustomTypeName) -> TokenStream {
         let n = c.name();
-        quote!(
-            ShaderCustomTypeName::new(#n)
-        )
+        let t = c.type_name();
+        if n == t {
+            quote!(
+                ShaderCustomTypeName::new(#n)
+            )
+        } else {
+            quote!(
+                ShaderCustomTypeName::new_named(#t, #n)
+            )
+        }
     }
 
     pub fn wgsl_function(c: &WgslFunction) -> TokenStream {
@@ -101,4 +108,27 @@ impl ToStructInitializer {
             HashMap::from([#entries])
         )
     }
+    pub fn wgsl_source_map(c: &WgslSourceMap) -> TokenStream {
+        let sections: TokenStream = c
+            .sections
+            .iter()
+            .map(|s| {
+                let name = &s.name;
+                let file = &s.file;
+                let rust_lines = &s.rust_lines;
+                quote! {
+                    WgslSectionSourceMap {
+                        name: (#name).to_string(),
+                        file: (#file).to_string(),
+                        rust_lines: vec![#(#rust_lines),*],
+                    },
+                }
+            })
+            .collect();
+        quote!(
+            WgslSourceMap {
+                sections: [#sections].into(),
+            }
+        )
+    }
 }
@@ -0,0 +1,46 @@
+use bevy_gpu_compute_core::wgsl::shader_module::user_defined_portion::WgslShaderModuleUserPortion;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+/// `random_inputs` fills every config input and input array with values of its own type, see `RandomFields`. The `bevy_gpu_compute::testing` harness reaches it through `TypesSpec::random_inputs`.
+pub fn generate_random_inputs(wgsl_shader_module: &WgslShaderModuleUserPortion) -> TokenStream {
+    let config_inputs: TokenStream = wgsl_shader_module
+        .uniforms
+        .iter()
+        .map(|uniform| {
+            let ty = Ident::new(uniform.name.type_name(), Span::call_site());
+            let key = uniform.name.name();
+            quote! {
+                config_inputs.set::<#ty>(#key, RandomFields::random_fields(rng));
+            }
+        })
+        .collect();
+    let input_arrays: TokenStream = wgsl_shader_module
+        .input_arrays
+        .iter()
+        .map(|array| {
+            let ty = Ident::new(array.item_type.name.type_name(), Span::call_site());
+            let key = array.item_type.name.name();
+            quote! {
+                input_data.set::<#ty>(
+                    #key,
+                    (0..input_length).map(|_| RandomFields::random_fields(rng)).collect(),
+                );
+            }
+        })
+        .collect();
+    quote! {
+        /// Random values for every config input, and `input_length` random values for every input array, each field with a value of its own type
+        pub fn random_inputs(
+            rng: &mut SplitMix64,
+            input_length: usize,
+        ) -> (TypeErasedConfigInputData, TypeErasedArrayInputData) {
+            let mut config_inputs = TypeErasedConfigInputData::new(HashMap::new());
+            #config_inputs
+            let mut input_data = TypeErasedArrayInputData::new(HashMap::new(), HashMap::new());
+            #input_arrays
+            (config_inputs, input_data)
+        }
+    }
+}
@@ -4,8 +4,12 @@ use quote::quote;
 
 use crate::pipeline::phases::final_structure_generator::per_component_expansion::ToStructInitializer;
 
+/// For libraries the function is called `parsed_library` instead of `parsed`, so that it does not collide with the `parsed` function of the modules that import it.
+/// The definitions of imported libraries are added at runtime, since the macro cannot see them. Their consts are checked for conflicting values at compile time though, see `const_table`.
 pub fn generate_shader_module_object(
     wgsl_shader_module: &WgslShaderModuleUserPortion,
+    library_imports: &[syn::Path],
+    is_library: bool,
 ) -> TokenStream {
     let static_consts: TokenStream = wgsl_shader_module
         .static_consts
@@ -69,15 +73,28 @@ pub fn generate_shader_module_object(
                 let ts = ToStructInitializer::wgsl_function(func);
                 quote!(Some(#ts))
             });
-    let bindings_map: TokenStream = ToStructInitializer::hash_map(
-        wgsl_shader_module
-            .binding_numbers_by_variable_name
-            .as_ref()
-            .unwrap(),
-    );
-
-    quote!(
-        pub fn parsed() -> WgslShaderModuleUserPortion {
+    let entry_functions: TokenStream = wgsl_shader_module
+        .entry_functions
+        .iter()
+        .map(|func| {
+            let ts = ToStructInitializer::wgsl_function(func);
+            quote!(#ts,)
+        })
+        .collect();
+    let bindings_map: TokenStream = wgsl_shader_module
+        .binding_numbers_by_variable_name
+        .as_ref()
+        .map_or(quote!(None), |map| {
+            let ts = ToStructInitializer::hash_map(map);
+            quote!(Some(#ts))
+        });
+    let source_map = ToStructInitializer::wgsl_source_map(&wgsl_shader_module.source_map);
+    let function_name = if is_library {
+        quote!(parsed_library)
+    } else {
+        quote!(parsed)
+    };
+    let module = quote!(
             WgslShaderModuleUserPortion {
                 static_consts: [
                     #static_consts
@@ -103,12 +120,79 @@ pub fn generate_shader_module_object(
                     ]
                 .into(),
                 main_function: #main_function,
-                binding_numbers_by_variable_name: Some(#bindings_map),
+                entry_functions: [
+                    #entry_functions
+                    ]
+                .into(),
+                binding_numbers_by_variable_name: #bindings_map,
+                source_map: #source_map,
             }
+    );
+    let const_table = const_table(wgsl_shader_module, library_imports, is_library);
+    if library_imports.is_empty() {
+        return quote!(
+            pub fn #function_name() -> WgslShaderModuleUserPortion {
+                #module
+            }
+            #const_table
+        );
+    }
+    quote!(
+        pub fn #function_name() -> WgslShaderModuleUserPortion {
+            let mut module = #module;
+            #(module.import_library(#library_imports::parsed_library());)*
+            module
         }
+        #const_table
     )
 }
 
+/// Libraries expose their `WgslConstTable` as `WGSL_LIBRARY_CONSTS`, so that the modules importing them can check that no const is defined twice with different values. Without imports there is nothing to check.
+fn const_table(
+    wgsl_shader_module: &WgslShaderModuleUserPortion,
+    library_imports: &[syn::Path],
+    is_library: bool,
+) -> TokenStream {
+    let entries: TokenStream = wgsl_shader_module
+        .static_consts
+        .iter()
+        .filter_map(|c| {
+            let (name, declaration) = (c.name()?, c.declaration()?);
+            let location = wgsl_shader_module
+                .source_map
+                .section(name)
+                .and_then(|s| s.rust_location(0))
+                .unwrap_or_else(|| String::from("an unknown location"));
+            let conflict_message = format!(
+                "The const `{}` is defined more than once with different values, in this module and the #[wgsl_shader_library] modules it imports. One of them is `{}` at {}. Rename one of them.",
+                name, declaration, location
+            );
+            Some(quote!(WgslConstEntry {
+                name: #name,
+                declaration: #declaration,
+                conflict_message: #conflict_message,
+            },))
+        })
+        .collect();
+    let table = quote!(WgslConstTable {
+        consts: &[#entries],
+        imports: &[#(&#library_imports::WGSL_LIBRARY_CONSTS),*],
+    });
+    let check = if library_imports.is_empty() {
+        quote!()
+    } else {
+        quote!(const _: () = #table.assert_no_conflicts();)
+    };
+    if is_library {
+        quote!(
+            pub const WGSL_LIBRARY_CONSTS: WgslConstTable = #table;
+            #check
+        )
+    } else {
+        check
+    }
+}
+
 #[cfg(test)]
 mod test {
     use proc_macro_error::abort;
@@ -8,6 +8,8 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::Ident;
 
+use super::per_component_expansion::ToStructInitializer;
+
 pub fn define_types_for_use_in_rust_and_set_binding_numbers(
     wgsl_shader_module: &mut WgslShaderModuleUserPortion,
 ) -> TokenStream {
@@ -45,6 +47,38 @@ pub fn define_types_for_use_in_rust_and_set_binding_numbers(
             type ConfigInputTypes = _ConfigInputTypes;
             type InputArrayTypes = _InputArrayTypes;
             type OutputArrayTypes = _OutputArrayTypes;
+            fn run_entry_points_on_cpu(
+                entry_points: &[String],
+                iteration_space: IterationSpace,
+                config_inputs: &TypeErasedConfigInputData,
+                input_data: &TypeErasedArrayInputData,
+                max_output_lengths: &MaxOutputLengths,
+                schedule: &CpuSchedule,
+            ) -> TypeErasedArrayOutputData {
+                run_entry_points_on_cpu(
+                    entry_points,
+                    iteration_space,
+                    config_inputs,
+                    input_data,
+                    max_output_lengths,
+                    schedule,
+                )
+            }
+            fn compare_outputs(
+                expected: &TypeErasedArrayOutputData,
+                actual: &TypeErasedArrayOutputData,
+                tolerance: &FloatTolerance,
+            ) -> Vec<FieldDifference> {
+                let expected: OutputDataBuilder = OutputDataBuilderTrait::from(expected);
+                let actual: OutputDataBuilder = OutputDataBuilderTrait::from(actual);
+                compare_outputs(&expected, &actual, tolerance)
+            }
+            fn random_inputs(
+                rng: &mut SplitMix64,
+                input_length: usize,
+            ) -> (TypeErasedConfigInputData, TypeErasedArrayInputData) {
+                random_inputs(rng, input_length)
+            }
         }
 
     )
@@ -61,7 +95,7 @@ pub fn uniform_types(
         .map(|uniform| {
             *binding_num_counter += 1;
             binding_numbers_by_variable_name.insert(uniform.name.uniform(), *binding_num_counter);
-            get_single_input_type_metadata(*binding_num_counter, uniform.name.name())
+            get_single_input_type_metadata(*binding_num_counter, &uniform.name)
         })
         .collect();
 
@@ -77,13 +111,17 @@ pub fn uniform_types(
     })
 }
 
-fn get_single_input_type_metadata(binding_num: u32, input_type: &str) -> TokenStream {
-    let ident = Ident::new(input_type, Span::call_site());
+fn get_single_input_type_metadata(
+    binding_num: u32,
+    type_name: &ShaderCustomTypeName,
+) -> TokenStream {
+    let ident = Ident::new(type_name.type_name(), Span::call_site());
+    let name = ToStructInitializer::custom_type_name(type_name);
     quote!(
         InputTypeMetadata {
             bytes: std::mem::size_of::<#ident>(),
             binding_number: #binding_num,
-            name: ShaderCustomTypeName::new(#input_type ),
+            name: #name,
         },
     )
 }
@@ -93,17 +131,17 @@ fn get_single_output_type_metadata(
     type_name: &ShaderCustomTypeName,
     include_count: bool,
 ) -> TokenStream {
-    let ident = Ident::new(type_name.name(), Span::call_site());
+    let ident = Ident::new(type_name.type_name(), Span::call_site());
     let next_binding_num = *binding_num + 1;
     binding_numbers_by_variable_name.insert(type_name.output_array().to_string(), *binding_num);
-    let string_type_name = type_name.name();
+    let name = ToStructInitializer::custom_type_name(type_name);
     let res = quote!(
         OutputTypeMetadata {
             bytes: std::mem::size_of::<#ident>(),
             binding_number: #binding_num,
             include_count: #include_count,
             count_binding_number: Some(#next_binding_num),
-            name: ShaderCustomTypeName::new(#string_type_name ),
+            name: #name,
         },
     );
     if include_count {
@@ -125,7 +163,7 @@ pub fn input_array_types(
             *binding_num_counter += 1;
             binding_numbers_by_variable_name
                 .insert(in_arr.item_type.name.input_array(), *binding_num_counter);
-            get_single_input_type_metadata(*binding_num_counter, in_arr.item_type.name.name())
+            get_single_input_type_metadata(*binding_num_counter, &in_arr.item_type.name)
         })
         .collect();
 
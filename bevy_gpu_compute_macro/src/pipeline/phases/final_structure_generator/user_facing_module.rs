@@ -4,9 +4,11 @@ use quote::{ToTokens, quote};
 use syn::ItemMod;
 
 use crate::pipeline::phases::final_structure_generator::generate_required_imports::generate_required_imports;
+use crate::pipeline::phases::non_mutating_tree_validation::validate_uniformity::functions_calling_barriers;
 
 use super::{
-    shader_module_object::generate_shader_module_object,
+    cpu_runner::generate_cpu_runner, output_comparison::generate_output_comparison,
+    random_inputs::generate_random_inputs, shader_module_object::generate_shader_module_object,
     types_for_rust_usage::define_types_for_use_in_rust_and_set_binding_numbers,
 };
 
@@ -14,9 +16,17 @@ pub fn generate_user_facing_module(
     wgsl_shader_module: &mut WgslShaderModuleUserPortion,
     rust_module_for_cpu: &ItemMod,
     builders: &TokenStream,
+    library_imports: &[syn::Path],
 ) -> TokenStream {
     let generated_types = define_types_for_use_in_rust_and_set_binding_numbers(wgsl_shader_module);
-    let generated_shader_module_object = generate_shader_module_object(wgsl_shader_module);
+    let generated_shader_module_object =
+        generate_shader_module_object(wgsl_shader_module, library_imports, false);
+    let cpu_runner = generate_cpu_runner(
+        wgsl_shader_module,
+        &functions_calling_barriers(rust_module_for_cpu),
+    );
+    let output_comparison = generate_output_comparison(wgsl_shader_module);
+    let random_inputs = generate_random_inputs(wgsl_shader_module);
     let required_imports = generate_required_imports();
     let user_module_content: TokenStream = rust_module_for_cpu
         .content
@@ -41,6 +51,43 @@ pub fn generate_user_facing_module(
             #generated_shader_module_object
 
             #builders
+
+            #cpu_runner
+
+            #output_comparison
+
+            #random_inputs
+        }
+    }
+}
+
+/// libraries have no bindings, so there are no generated types or buffer builders
+pub fn generate_user_facing_library_module(
+    wgsl_shader_module: &WgslShaderModuleUserPortion,
+    rust_module_for_cpu: &ItemMod,
+    library_imports: &[syn::Path],
+) -> TokenStream {
+    let generated_shader_module_object =
+        generate_shader_module_object(wgsl_shader_module, library_imports, true);
+    let required_imports = generate_required_imports();
+    let user_module_content: TokenStream = rust_module_for_cpu
+        .content
+        .as_ref()
+        .unwrap()
+        .1
+        .iter()
+        .map(|item| item.to_token_stream())
+        .collect();
+    let vis = &rust_module_for_cpu.vis;
+    let ident = &rust_module_for_cpu.ident;
+    quote! {
+        #[allow(dead_code, unused_variables, unused_imports)]
+        #vis mod #ident {
+            #required_imports
+
+            #user_module_content
+
+            #generated_shader_module_object
         }
     }
 }
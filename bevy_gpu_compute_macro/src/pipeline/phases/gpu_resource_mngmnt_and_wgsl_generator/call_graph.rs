@@ -0,0 +1,319 @@
+/*!
+Which module level functions, consts and types refer to which others.
+
+WGSL does not allow recursion, so cycles between functions are reported as compile errors, at the call that closes the cycle. The graph also tells which items are reachable from the entry points and bindings, so that unused helper functions, types and consts can be left out of the generated WGSL. The CPU version of the module keeps all of them.
+*/
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use proc_macro2::Span;
+use syn::{Expr, ExprCall, Item, ItemFn, ItemMod, Path, visit::Visit};
+
+use crate::pipeline::entry_points::is_entry_point;
+use crate::pipeline::phases::custom_type_collector::custom_type::{CustomType, CustomTypeKind};
+
+pub struct CallGraph {
+    /// names of the functions, consts and types in source order
+    order: Vec<String>,
+    nodes: HashMap<String, Node>,
+    /// `None` if nothing should be pruned, like in libraries, whose items are used by the modules that import them
+    used: Option<HashSet<String>>,
+}
+
+struct Node {
+    is_function: bool,
+    /// functions called by this item, with the span of the call
+    calls: Vec<(String, Span)>,
+    /// every path used by this item, including calls, types and consts
+    references: HashSet<String>,
+}
+
+impl CallGraph {
+    pub fn new(module: &ItemMod, custom_types: &[CustomType], prune: bool) -> Self {
+        let mut graph = CallGraph {
+            order: Vec::new(),
+            nodes: HashMap::new(),
+            used: None,
+        };
+        let mut roots: Vec<String> = custom_types
+            .iter()
+            .filter(|c| {
+                matches!(
+                    c.kind,
+                    CustomTypeKind::Uniform
+                        | CustomTypeKind::InputArray
+                        | CustomTypeKind::OutputArray
+                        | CustomTypeKind::OutputVec
+                )
+            })
+            .map(|c| c.name.name.to_string())
+            .collect();
+        if let Some((_, items)) = &module.content {
+            for item in items {
+                graph.add_item(item, &mut roots);
+            }
+        }
+        if prune {
+            graph.used = Some(graph.reachable_from(roots));
+        }
+        graph
+    }
+
+    fn add_item(&mut self, item: &Item, roots: &mut Vec<String>) {
+        let mut collector = ReferenceCollector::default();
+        let (name, is_function) = match item {
+            Item::Fn(f) => {
+                collector.visit_signature(&f.sig);
+                collector.visit_block(&f.block);
+                if is_entry_point(f) {
+                    roots.push(f.sig.ident.to_string());
+                }
+                // nested functions are emitted as module level functions
+                for nested in nested_functions(f) {
+                    self.add_item(&Item::Fn(nested), roots);
+                }
+                (f.sig.ident.to_string(), true)
+            }
+            Item::Const(c) => {
+                collector.visit_type(&c.ty);
+                collector.visit_expr(&c.expr);
+                (c.ident.to_string(), false)
+            }
+            Item::Struct(s) => {
+                collector.visit_fields(&s.fields);
+                (s.ident.to_string(), false)
+            }
+            Item::Type(t) => {
+                collector.visit_type(&t.ty);
+                (t.ident.to_string(), false)
+            }
+            _ => return,
+        };
+        if self.nodes.contains_key(&name) {
+            return;
+        }
+        self.order.push(name.clone());
+        self.nodes.insert(
+            name,
+            Node {
+                is_function,
+                calls: collector.calls,
+                references: collector.references,
+            },
+        );
+    }
+
+    fn reachable_from(&self, roots: Vec<String>) -> HashSet<String> {
+        let mut used: HashSet<String> = HashSet::new();
+        let mut queue: VecDeque<String> = roots.into();
+        while let Some(name) = queue.pop_front() {
+            let Some(node) = self.nodes.get(&name) else {
+                continue;
+            };
+            if !used.insert(name) {
+                continue;
+            }
+            queue.extend(
+                node.references
+                    .iter()
+                    .filter(|r| !used.contains(*r))
+                    .cloned(),
+            );
+        }
+        used
+    }
+
+    /// whether the item with this name should be part of the generated WGSL
+    pub fn is_used(&self, name: &str) -> bool {
+        self.used.as_ref().is_none_or(|used| used.contains(name))
+    }
+
+    /// One error per cycle of function calls, pointing at the call in the first function of the cycle (in source order).
+    pub fn recursion_errors(&self) -> Vec<syn::Error> {
+        let mut in_reported_cycle: HashSet<&str> = HashSet::new();
+        let mut errors = Vec::new();
+        for name in self.order.iter() {
+            if in_reported_cycle.contains(name.as_str()) || !self.nodes[name].is_function {
+                continue;
+            }
+            let Some((cycle, span)) = self.shortest_cycle(name) else {
+                continue;
+            };
+            in_reported_cycle.extend(cycle.iter().map(|n| n.as_str()));
+            let path = cycle
+                .iter()
+                .chain(std::iter::once(&name))
+                .map(|n| format!("`{}`", n))
+                .collect::<Vec<String>>()
+                .join(" -> ");
+            let message = if cycle.len() == 1 {
+                format!(
+                    "`{}` calls itself, but recursion is not allowed in WGSL. Rewrite it with a loop instead",
+                    name
+                )
+            } else {
+                format!(
+                    "Recursion is not allowed in WGSL, but these functions call each other in a cycle: {}. Rewrite them with a loop instead",
+                    path
+                )
+            };
+            errors.push(syn::Error::new(span, message));
+        }
+        errors
+    }
+
+    /// the functions of the shortest cycle of calls starting and ending at `start`, starting with `start`, and the span of the first call of the cycle
+    fn shortest_cycle<'a>(&'a self, start: &'a String) -> Option<(Vec<&'a String>, Span)> {
+        let mut previous: HashMap<&String, (&String, Span)> = HashMap::new();
+        let mut queue: VecDeque<&String> = VecDeque::from([start]);
+        while let Some(name) = queue.pop_front() {
+            for (callee, span) in self.nodes[name].calls.iter() {
+                let Some((callee, _)) = self.nodes.get_key_value(callee) else {
+                    continue;
+                };
+                if callee == start {
+                    let mut cycle = vec![name];
+                    while let Some((caller, _)) = previous.get(*cycle.last().unwrap()) {
+                        cycle.push(caller);
+                    }
+                    cycle.reverse();
+                    let first_call = match cycle.get(1) {
+                        Some(second) => previous[second].1,
+                        None => *span,
+                    };
+                    return Some((cycle, first_call));
+                }
+                if !previous.contains_key(callee) {
+                    previous.insert(callee, (name, *span));
+                    queue.push_back(callee);
+                }
+            }
+        }
+        None
+    }
+}
+
+fn nested_functions(f: &ItemFn) -> Vec<ItemFn> {
+    struct NestedFunctions(Vec<ItemFn>);
+    impl Visit<'_> for NestedFunctions {
+        fn visit_item_fn(&mut self, f: &ItemFn) {
+            self.0.push(f.clone());
+        }
+    }
+    let mut nested = NestedFunctions(Vec::new());
+    nested.visit_block(&f.block);
+    nested.0
+}
+
+#[derive(Default)]
+struct ReferenceCollector {
+    calls: Vec<(String, Span)>,
+    references: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for ReferenceCollector {
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        if let Expr::Path(path) = &*call.func {
+            if let Some(ident) = path.path.get_ident() {
+                self.calls.push((ident.to_string(), ident.span()));
+            }
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+    fn visit_path(&mut self, path: &'ast Path) {
+        path.segments.iter().for_each(|segment| {
+            self.references.insert(segment.ident.to_string());
+        });
+        syn::visit::visit_path(self, path);
+    }
+    /// nested functions are nodes of their own
+    fn visit_item_fn(&mut self, _f: &'ast ItemFn) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_recursion_errors() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                fn fact(n: u32) -> u32 {
+                    if n == 0 {
+                        return 1;
+                    }
+                    return n * fact(n - 1);
+                }
+                fn even(n: u32) -> bool {
+                    return n == 0 || odd(n - 1);
+                }
+                fn odd(n: u32) -> bool {
+                    return n != 0 && even(n - 1);
+                }
+                fn main(iter_pos: WgslIterationPosition) {
+                    let fact = fact(iter_pos.x);
+                }
+            }
+        };
+        let errors: Vec<String> = CallGraph::new(&module, &[], true)
+            .recursion_errors()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "`fact` calls itself, but recursion is not allowed in WGSL. Rewrite it with a loop instead",
+                "Recursion is not allowed in WGSL, but these functions call each other in a cycle: `even` -> `odd` -> `even`. Rewrite them with a loop instead",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unused_items() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                const SCALE: f32 = 2.0;
+                const UNUSED: f32 = 3.0;
+                const SIZE: usize = 2;
+                struct Shape {
+                    radius: f32,
+                }
+                struct UnusedShape {
+                    radius: f32,
+                }
+                #[wgsl_input_array]
+                struct Body {
+                    shape: Shape,
+                    verts: [f32; SIZE],
+                }
+                fn scaled(v: f32) -> f32 {
+                    return v * SCALE;
+                }
+                fn unused(v: f32) -> f32 {
+                    return scaled(v);
+                }
+                fn main(iter_pos: WgslIterationPosition) {
+                    let v = scaled(1.0);
+                }
+            }
+        };
+        let custom_types = vec![CustomType::new(
+            &syn::parse_quote!(Body),
+            CustomTypeKind::InputArray,
+            proc_macro2::TokenStream::new(),
+        )];
+        let graph = CallGraph::new(&module, &custom_types, true);
+        let used: Vec<&str> = ["SCALE", "SIZE", "Shape", "Body", "scaled", "main"].into();
+        let unused: Vec<&str> = ["UNUSED", "UnusedShape", "unused"].into();
+        used.iter().for_each(|n| assert!(graph.is_used(n), "{}", n));
+        unused
+            .iter()
+            .for_each(|n| assert!(!graph.is_used(n), "{}", n));
+        let library = CallGraph::new(&module, &custom_types, false);
+        unused
+            .iter()
+            .for_each(|n| assert!(library.is_used(n), "{}", n));
+    }
+}
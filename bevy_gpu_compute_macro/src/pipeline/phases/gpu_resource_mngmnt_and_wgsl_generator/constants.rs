@@ -2,39 +2,45 @@ use bevy_gpu_compute_core::wgsl::shader_sections::{
     WgslConstAssignment, WgslShaderModuleSectionCode,
 };
 use quote::ToTokens;
-use syn::{ItemConst, ItemMod, visit::Visit};
+use syn::{ItemConst, ItemFn, ItemMod, visit::Visit};
 
+use super::call_graph::CallGraph;
 use super::to_wgsl_syntax::convert_file_to_wgsl;
 use crate::pipeline::phases::custom_type_collector::custom_type::CustomType;
 
-// todo ensure this only searches the module level, right now its searching within functions as well
-
+/// Only module level consts are extracted, consts inside of functions are converted along with the function. Consts that nothing uses are left out.
 pub fn extract_constants(
     rust_module_transformed_for_gpu: &ItemMod,
     custom_types: &Vec<CustomType>,
+    call_graph: &CallGraph,
 ) -> Vec<WgslConstAssignment> {
-    let mut extractor = ConstantsExtractor::new(custom_types);
+    let mut extractor = ConstantsExtractor::new(custom_types, call_graph);
     extractor.visit_item_mod(rust_module_transformed_for_gpu);
     extractor.results
 }
 
 struct ConstantsExtractor<'a> {
     custom_types: &'a Vec<CustomType>,
+    call_graph: &'a CallGraph,
     results: Vec<WgslConstAssignment>,
 }
 
 impl<'ast> Visit<'ast> for ConstantsExtractor<'ast> {
     fn visit_item_const(&mut self, c: &'ast syn::ItemConst) {
         syn::visit::visit_item_const(self, c);
-        self.results
-            .push(parse_const_assignment(c, self.custom_types));
+        if self.call_graph.is_used(&c.ident.to_string()) {
+            self.results
+                .push(parse_const_assignment(c, self.custom_types));
+        }
     }
+    fn visit_item_fn(&mut self, _f: &'ast ItemFn) {}
 }
 
 impl<'ast> ConstantsExtractor<'ast> {
-    pub fn new(custom_types: &'ast Vec<CustomType>) -> Self {
+    pub fn new(custom_types: &'ast Vec<CustomType>, call_graph: &'ast CallGraph) -> Self {
         ConstantsExtractor {
             custom_types,
+            call_graph,
             results: Vec::new(),
         }
     }
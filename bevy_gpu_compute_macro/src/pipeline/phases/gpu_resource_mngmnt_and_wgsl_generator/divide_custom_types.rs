@@ -58,6 +58,9 @@ pub fn generate_helper_types_inputs_and_outputs_for_wgsl_module_def(
             CustomTypeKind::ArrayLengthVariable => {
                 // do nothing
             }
+            CustomTypeKind::LibraryType => {
+                // added to the wgsl at runtime, along with the rest of the library
+            }
         }
     }
 
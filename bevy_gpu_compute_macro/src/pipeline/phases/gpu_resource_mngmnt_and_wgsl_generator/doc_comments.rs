@@ -0,0 +1,154 @@
+use bevy_gpu_compute_core::wgsl::{
+    shader_module::user_defined_portion::WgslShaderModuleUserPortion,
+    shader_sections::WgslShaderModuleSectionCode,
+};
+use syn::{Attribute, Expr, ExprLit, Item, ItemMod, Lit, Meta};
+
+/// the text of all `///` and `/** */` comments on an item, one entry per line
+pub fn doc_comment_lines(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .flat_map(|doc| {
+            doc.split('\n')
+                .map(|line| line.trim().to_string())
+                .collect::<Vec<String>>()
+        })
+        .collect()
+}
+
+/// Doc comments are removed along with all other attributes when converting to wgsl, this adds them back as `//` comments above the functions, types and consts they document.
+pub fn add_doc_comments(wgsl_module: &mut WgslShaderModuleUserPortion, rust_module: &ItemMod) {
+    let Some((_, items)) = &rust_module.content else {
+        return;
+    };
+    for item in items {
+        match item {
+            Item::Fn(f) => {
+                let name = f.sig.ident.to_string();
+                let lines = doc_comment_lines(&f.attrs);
+                wgsl_module
+                    .helper_functions
+                    .iter_mut()
+                    .chain(wgsl_module.main_function.iter_mut())
+                    .chain(wgsl_module.entry_functions.iter_mut())
+                    .filter(|func| func.name == name)
+                    .for_each(|func| prepend_comment(&mut func.code, &lines));
+            }
+            Item::Struct(_) | Item::Type(_) => {
+                let (name, attrs) = match item {
+                    Item::Struct(s) => (s.ident.to_string(), &s.attrs),
+                    Item::Type(t) => (t.ident.to_string(), &t.attrs),
+                    _ => unreachable!(),
+                };
+                let lines = doc_comment_lines(attrs);
+                wgsl_module
+                    .helper_types
+                    .iter_mut()
+                    .chain(wgsl_module.uniforms.iter_mut())
+                    .chain(
+                        wgsl_module
+                            .input_arrays
+                            .iter_mut()
+                            .map(|a| &mut a.item_type),
+                    )
+                    .chain(
+                        wgsl_module
+                            .output_arrays
+                            .iter_mut()
+                            .map(|a| &mut a.item_type),
+                    )
+                    .filter(|t| *t.name.type_name() == name)
+                    .for_each(|t| prepend_comment(&mut t.code, &lines));
+            }
+            Item::Const(c) => {
+                let name = c.ident.to_string();
+                let lines = doc_comment_lines(&c.attrs);
+                wgsl_module
+                    .static_consts
+                    .iter_mut()
+                    .filter(|constant| {
+                        constant.code.wgsl_code.split_whitespace().nth(1) == Some(name.as_str())
+                    })
+                    .for_each(|constant| prepend_comment(&mut constant.code, &lines));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn prepend_comment(code: &mut WgslShaderModuleSectionCode, lines: &[String]) {
+    if lines.is_empty() {
+        return;
+    }
+    let comment: String = lines
+        .iter()
+        .map(|line| {
+            if line.is_empty() {
+                "//\n".to_string()
+            } else {
+                format!("// {}\n", line)
+            }
+        })
+        .collect();
+    code.wgsl_code = format!("{}{}", comment, code.wgsl_code);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_gpu_compute_core::wgsl::shader_sections::{WgslConstAssignment, WgslFunction};
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_add_doc_comments() {
+        let rust_module: ItemMod = parse_quote! {
+            mod m {
+                /// the seed
+                const SEED: u32 = 3;
+                /** Hashes a value.
+                Not cryptographically secure */
+                fn hash(v: u32) -> u32 {
+                    return v * SEED;
+                }
+                fn undocumented() {}
+            }
+        };
+        let mut wgsl_module = WgslShaderModuleUserPortion::empty();
+        wgsl_module.static_consts.push(WgslConstAssignment {
+            code: WgslShaderModuleSectionCode {
+                wgsl_code: "const SEED : u32 = 3;".to_string(),
+            },
+        });
+        for name in ["hash", "undocumented"] {
+            wgsl_module.helper_functions.push(WgslFunction {
+                name: name.to_string(),
+                code: WgslShaderModuleSectionCode {
+                    wgsl_code: format!("fn {}() {{}}", name),
+                },
+            });
+        }
+        add_doc_comments(&mut wgsl_module, &rust_module);
+        assert_eq!(
+            wgsl_module.static_consts[0].code.wgsl_code,
+            "// the seed\nconst SEED : u32 = 3;"
+        );
+        assert_eq!(
+            wgsl_module.helper_functions[0].code.wgsl_code,
+            "// Hashes a value.\n// Not cryptographically secure\nfn hash() {}"
+        );
+        assert_eq!(
+            wgsl_module.helper_functions[1].code.wgsl_code,
+            "fn undocumented() {}"
+        );
+    }
+}
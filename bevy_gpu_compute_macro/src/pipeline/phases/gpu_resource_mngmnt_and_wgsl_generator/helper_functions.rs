@@ -1,40 +1,45 @@
+use super::call_graph::CallGraph;
 use super::to_wgsl_syntax::convert_file_to_wgsl;
 use bevy_gpu_compute_core::wgsl::shader_sections::{WgslFunction, WgslShaderModuleSectionCode};
 use quote::ToTokens;
 use syn::{ItemFn, ItemMod, visit::Visit};
 
+use crate::pipeline::entry_points::is_entry_point;
 use crate::pipeline::phases::custom_type_collector::custom_type::CustomType;
 
+/// Functions that are not called from an entry point are left out.
 pub fn extract_helper_functions(
     rust_module_transformed_for_gpu: &ItemMod,
     custom_types: &Vec<CustomType>,
+    call_graph: &CallGraph,
 ) -> Vec<WgslFunction> {
-    let mut extractor = HelperFunctionsExtractor::new(custom_types);
+    let mut extractor = HelperFunctionsExtractor::new(custom_types, call_graph);
     extractor.visit_item_mod(rust_module_transformed_for_gpu);
     extractor.results
 }
 
 struct HelperFunctionsExtractor<'a> {
     custom_types: &'a Vec<CustomType>,
+    call_graph: &'a CallGraph,
     results: Vec<WgslFunction>,
 }
 
 impl<'ast> Visit<'ast> for HelperFunctionsExtractor<'ast> {
     fn visit_item_fn(&mut self, c: &'ast syn::ItemFn) {
         syn::visit::visit_item_fn(self, c);
-        if c.sig.ident == "main" {
+        if is_entry_point(c) || !self.call_graph.is_used(&c.sig.ident.to_string()) {
             return;
         }
-        // ident from string
 
         self.results.push(parse_fn(c, self.custom_types));
     }
 }
 
 impl<'ast> HelperFunctionsExtractor<'ast> {
-    pub fn new(custom_types: &'ast Vec<CustomType>) -> Self {
+    pub fn new(custom_types: &'ast Vec<CustomType>, call_graph: &'ast CallGraph) -> Self {
         HelperFunctionsExtractor {
             custom_types,
+            call_graph,
             results: Vec::new(),
         }
     }
@@ -1,26 +1,50 @@
 use bevy_gpu_compute_core::wgsl::shader_module::user_defined_portion::WgslShaderModuleUserPortion;
 
+use proc_macro_error::{abort_if_dirty, emit_error};
+
 use crate::pipeline::phases::custom_type_collector::custom_type::CustomType;
 
+use super::call_graph::CallGraph;
 use super::constants::extract_constants;
 use super::divide_custom_types::generate_helper_types_inputs_and_outputs_for_wgsl_module_def;
+use super::doc_comments::add_doc_comments;
 use super::helper_functions::extract_helper_functions;
-use super::main_function::parse_main_function;
+use super::main_function::parse_entry_point_functions;
+use super::source_map::generate_source_map;
 
 /// This will also change custom_types
+/// Libraries have no entry points, and keep all of their items, since the modules that import them may use any of them
 pub fn parse_shader_module_for_gpu(
     rust_module_transformed_for_gpu: &syn::ItemMod,
     custom_types: &Vec<CustomType>,
+    is_library: bool,
+    emit_doc_comments: bool,
 ) -> (WgslShaderModuleUserPortion, Vec<CustomType>) {
+    let call_graph = CallGraph::new(rust_module_transformed_for_gpu, custom_types, !is_library);
+    let recursion_errors = call_graph.recursion_errors();
+    if !recursion_errors.is_empty() {
+        for error in recursion_errors {
+            emit_error!(error.span(), error.to_string());
+        }
+        abort_if_dirty();
+    }
     let mut out_module: WgslShaderModuleUserPortion = WgslShaderModuleUserPortion::empty();
-    out_module.main_function = Some(parse_main_function(
-        rust_module_transformed_for_gpu,
-        custom_types,
-    ));
-    out_module.static_consts = extract_constants(rust_module_transformed_for_gpu, custom_types);
+    if !is_library {
+        (out_module.main_function, out_module.entry_functions) =
+            parse_entry_point_functions(rust_module_transformed_for_gpu, custom_types);
+    }
+    out_module.static_consts =
+        extract_constants(rust_module_transformed_for_gpu, custom_types, &call_graph);
     out_module.helper_functions =
-        extract_helper_functions(rust_module_transformed_for_gpu, custom_types);
+        extract_helper_functions(rust_module_transformed_for_gpu, custom_types, &call_graph);
     let new_custom_types =
         generate_helper_types_inputs_and_outputs_for_wgsl_module_def(custom_types, &mut out_module);
+    out_module
+        .helper_types
+        .retain(|t| call_graph.is_used(t.name.type_name()));
+    if emit_doc_comments {
+        add_doc_comments(&mut out_module, rust_module_transformed_for_gpu);
+    }
+    out_module.source_map = generate_source_map(&out_module, rust_module_transformed_for_gpu);
     (out_module, new_custom_types)
 }
@@ -1,133 +1,230 @@
+use std::collections::HashSet;
+
 use super::to_wgsl_syntax::convert_file_to_wgsl;
+use crate::pipeline::entry_points::is_entry_point;
 use crate::pipeline::phases::custom_type_collector::custom_type::CustomType;
 use bevy_gpu_compute_core::wgsl::shader_sections::{WgslFunction, WgslShaderModuleSectionCode};
 use proc_macro::Span;
 use proc_macro_error::abort;
 use quote::ToTokens;
+use regex::Regex;
 use syn::{ItemFn, ItemMod, spanned::Spanned, visit::Visit};
 
-/// 0: for gpu, 1: for cpu
-pub fn parse_main_function(
+/// Returns the main function, if there is one, and the `#[wgsl_entry]` functions. At least one entry point is required.
+pub fn parse_entry_point_functions(
     rust_module_transformed_for_gpu: &ItemMod,
     custom_types: &Vec<CustomType>,
-) -> WgslFunction {
-    let mut extractor = MainFunctionsExtractor::new(custom_types);
+) -> (Option<WgslFunction>, Vec<WgslFunction>) {
+    let mut extractor = EntryPointFunctionsExtractor::new(custom_types);
     extractor.visit_item_mod(rust_module_transformed_for_gpu);
-
-    if let Some(mf) = extractor.result {
-        mf
-    } else {
-        abort!(Span::call_site(), "No main function found");
+    if extractor.main_function.is_none() && extractor.entry_functions.is_empty() {
+        abort!(
+            Span::call_site(),
+            "No main function found, add a function called `main` or mark one or more functions with `#[wgsl_entry]`"
+        );
     }
+    (extractor.main_function, extractor.entry_functions)
 }
 
-struct MainFunctionsExtractor<'a> {
-    count: usize,
+struct EntryPointFunctionsExtractor<'a> {
     custom_types: &'a Vec<CustomType>,
-    result: Option<WgslFunction>,
+    main_function: Option<WgslFunction>,
+    entry_functions: Vec<WgslFunction>,
 }
 
-impl<'ast> Visit<'ast> for MainFunctionsExtractor<'ast> {
+impl<'ast> Visit<'ast> for EntryPointFunctionsExtractor<'ast> {
     fn visit_item_fn(&mut self, c: &'ast syn::ItemFn) {
         syn::visit::visit_item_fn(self, c);
-        let name = c.sig.ident.to_string();
-        if name != "main" {
+        if !is_entry_point(c) {
             return;
         }
-        self.count += 1;
-        if self.count > 1 {
-            abort!(c.sig.ident.span(), "Only one main function is allowed");
+        let func = parse_entry_point_fn(c, self.custom_types);
+        if c.sig.ident == "main" {
+            if self.main_function.is_some() {
+                abort!(c.sig.ident.span(), "Only one main function is allowed");
+            }
+            self.main_function = Some(func);
+        } else {
+            self.entry_functions.push(func);
         }
-
-        self.result = Some(parse_main_fn(c, self.custom_types));
     }
 }
 
-impl<'ast> MainFunctionsExtractor<'ast> {
+impl<'ast> EntryPointFunctionsExtractor<'ast> {
     pub fn new(custom_types: &'ast Vec<CustomType>) -> Self {
-        MainFunctionsExtractor {
-            count: 0,
+        EntryPointFunctionsExtractor {
             custom_types,
-            result: None,
+            main_function: None,
+            entry_functions: Vec::new(),
         }
     }
 }
 
-fn parse_main_fn(func: &ItemFn, custom_types: &Vec<CustomType>) -> WgslFunction {
-    validate_main_function(func);
-    let func_clone = func.clone();
-    // alter the main function argument
+fn parse_entry_point_fn(func: &ItemFn, custom_types: &Vec<CustomType>) -> WgslFunction {
+    validate_entry_point_function(func);
+    let mut func_clone = func.clone();
+    let iter_pos_name = rewrite_iter_pos_parameter(&mut func_clone);
     WgslFunction {
         code: WgslShaderModuleSectionCode {
-            wgsl_code: alter_global_id_argument(convert_file_to_wgsl(
-                func_clone.to_token_stream(),
-                custom_types,
-                "main".to_string(),
-            )),
+            wgsl_code: alter_global_id_argument(
+                convert_file_to_wgsl(
+                    func_clone.to_token_stream(),
+                    custom_types,
+                    func.sig.ident.to_string(),
+                ),
+                &iter_pos_name,
+            ),
         },
         name: func_clone.sig.ident.to_string(),
     }
 }
-/// we have to alter the main function argument to match the wgsl spec by string replace instead of ast manipulation because the new argument is not a valid rust syntax
-fn alter_global_id_argument(func_string: String) -> String {
-    let match_patterns = [
-        "iter_pos: WgslIterationPosition",
-        "iter_pos : WgslIterationPosition",
-        "iter_pos:WgslIterationPosition",
-        "iter_pos:  WgslIterationPosition",
-    ];
-    let replace_pattern = "@builtin(global_invocation_id) iter_pos: vec3<u32>";
-    let mut new_func = func_string.clone();
-    let mut found = false;
-    for pattern in match_patterns.iter() {
-        if new_func.contains(pattern) {
-            found = true;
-            new_func = new_func.replace(pattern, replace_pattern);
+
+/// If the parameter was destructured, like `WgslIterationPosition { x, y, .. }`, it is replaced by a plain name here and the fields are bound to local variables at the start of the function body instead, since wgsl has no destructuring.
+/// Returns the name of the parameter.
+fn rewrite_iter_pos_parameter(func: &mut ItemFn) -> String {
+    let used_idents = collect_idents(func.to_token_stream());
+    let Some(syn::FnArg::Typed(pat_type)) = func.sig.inputs.first_mut() else {
+        unreachable!("the main function parameter is validated before this");
+    };
+    let (name, lets): (String, Vec<syn::Stmt>) = match &*pat_type.pat {
+        syn::Pat::Ident(pat_ident) => (pat_ident.ident.to_string(), vec![]),
+        syn::Pat::Wild(_) => ("iter_pos".to_string(), vec![]),
+        syn::Pat::Struct(pat_struct) => {
+            // the parameter and the function body share a scope in wgsl, so the name must not be used anywhere in the function
+            let mut name = "iter_pos".to_string();
+            while used_idents.contains(&name) {
+                name.push('_');
+            }
+            let name_ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
+            let lets = pat_struct
+                .fields
+                .iter()
+                .filter(|f| !matches!(&*f.pat, syn::Pat::Wild(_)))
+                .map(|f| {
+                    let (pat, member) = (&f.pat, &f.member);
+                    syn::parse_quote!(let #pat = #name_ident.#member;)
+                })
+                .collect();
+            (name, lets)
+        }
+        _ => unreachable!("the main function parameter is validated before this"),
+    };
+    let name_ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
+    *pat_type.pat = syn::parse_quote!(#name_ident);
+    func.block.stmts.splice(0..0, lets);
+    name
+}
+
+fn collect_idents(tokens: proc_macro2::TokenStream) -> HashSet<String> {
+    let mut idents = HashSet::new();
+    for token in tokens {
+        match token {
+            proc_macro2::TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
+            }
+            proc_macro2::TokenTree::Group(group) => idents.extend(collect_idents(group.stream())),
+            _ => {}
         }
     }
-    if !found {
-        let error_message = format!(
-            "Failed to find main function argument, we are looking for a string that exactly matches 'iter_pos: WgslIterationPosition', found {}",
-            new_func
+    idents
+}
+
+/// we have to alter the main function argument to match the wgsl spec by string replace instead of ast manipulation because the new argument is not a valid rust syntax
+fn alter_global_id_argument(func_string: String, iter_pos_name: &str) -> String {
+    let pattern = Regex::new(&format!(
+        r"\b{}\s*:\s*WgslIterationPosition\b",
+        regex::escape(iter_pos_name)
+    ))
+    .unwrap();
+    if !pattern.is_match(&func_string) {
+        abort!(
+            Span::call_site(),
+            "Failed to find main function argument, we are looking for '{}: WgslIterationPosition', found {}",
+            iter_pos_name,
+            func_string
         );
-        abort!(Span::call_site(), error_message);
     }
-    new_func
+    let replace_pattern = format!(
+        "@builtin(global_invocation_id) {}: vec3<u32>",
+        iter_pos_name
+    );
+    pattern
+        .replacen(&func_string, 1, regex::NoExpand(&replace_pattern))
+        .to_string()
 }
 
-fn validate_main_function(function: &ItemFn) {
+fn validate_entry_point_function(function: &ItemFn) {
     // Check that main has exactly one parameter
     if function.sig.inputs.len() != 1 {
         abort!(
             function.sig.span(),
-            "Main function must have exactly one parameter of type WgslIterationPosition"
+            "Entry point functions must have exactly one parameter of type WgslIterationPosition"
         );
     }
-    // Validate the parameter type is WgslIterationPosition called "global_id"
     if let syn::FnArg::Typed(pat_type) = &function.sig.inputs[0] {
-        match &*pat_type.pat {
-            syn::Pat::Ident(_) => {}
-            _ => abort!(
-                pat_type.pat.span(),
-                "Main function parameter must be called 'iter_pos'"
-            ),
-        }
+        validate_iter_pos_pattern(&pat_type.pat);
         if let syn::Type::Path(type_path) = &*pat_type.ty {
             if let Some(segment) = type_path.path.segments.last() {
                 if segment.ident != "WgslIterationPosition" {
                     abort!(
                         pat_type.ty.span(),
-                        "Main function parameter must be of type WgslIterationPosition"
+                        "Entry point function parameters must be of type WgslIterationPosition"
                     );
                 }
             }
         }
+    } else {
+        abort!(
+            function.sig.inputs.span(),
+            "Entry point functions must have exactly one parameter of type WgslIterationPosition"
+        );
     }
     // Check return type (should be void/unit)
     if let syn::ReturnType::Type(_, _) = &function.sig.output {
         abort!(
             function.sig.span(),
-            "Main function cannot have a return type"
+            "Entry point functions cannot have a return type"
         );
     }
 }
+
+/// any name, `_`, or a destructuring of the fields like `WgslIterationPosition { x, y: row, .. }`
+fn validate_iter_pos_pattern(pat: &syn::Pat) {
+    match pat {
+        syn::Pat::Ident(pat_ident) => {
+            if pat_ident.mutability.is_some()
+                || pat_ident.by_ref.is_some()
+                || pat_ident.subpat.is_some()
+            {
+                abort!(
+                    pat_ident.span(),
+                    "The main function parameter is read-only, remove the `mut` or `ref`"
+                );
+            }
+        }
+        syn::Pat::Wild(_) => {}
+        syn::Pat::Struct(pat_struct) => {
+            for field in pat_struct.fields.iter() {
+                let is_valid_member = matches!(&field.member, syn::Member::Named(m) if m == "x" || m == "y" || m == "z");
+                if !is_valid_member {
+                    abort!(
+                        field.member.span(),
+                        "WgslIterationPosition only has the fields `x`, `y` and `z`"
+                    );
+                }
+                match &*field.pat {
+                    syn::Pat::Ident(i) if i.by_ref.is_none() && i.subpat.is_none() => {}
+                    syn::Pat::Wild(_) => {}
+                    other => abort!(
+                        other.span(),
+                        "Fields of the main function parameter can only be bound to a name, like `x` or `x: column`"
+                    ),
+                }
+            }
+        }
+        other => abort!(
+            other.span(),
+            "The main function parameter must be a name like `iter_pos`, or a destructuring like `WgslIterationPosition {{ x, y, .. }}`"
+        ),
+    }
+}
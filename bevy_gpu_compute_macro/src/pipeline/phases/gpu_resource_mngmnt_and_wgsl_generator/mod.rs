@@ -1,7 +1,10 @@
+mod call_graph;
 pub mod compiler_phase;
 mod constants;
 mod divide_custom_types;
+pub mod doc_comments;
 mod helper_functions;
 mod lib;
 mod main_function;
+mod source_map;
 pub mod to_wgsl_syntax;
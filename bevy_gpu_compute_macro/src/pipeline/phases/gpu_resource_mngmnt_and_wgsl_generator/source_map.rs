@@ -0,0 +1,53 @@
+use bevy_gpu_compute_core::wgsl::{
+    shader_module::user_defined_portion::WgslShaderModuleUserPortion,
+    shader_sections::{WgslSectionSourceMap, WgslShaderModuleSectionCode, WgslSourceMap},
+};
+use quote::ToTokens;
+use syn::ItemMod;
+
+use crate::pipeline::module_items::find_item;
+
+use super::to_wgsl_syntax::source_map::rust_lines_of_wgsl;
+
+/// Records the rust line that each line of every user defined const, type and function was converted from, so that the runtime can report shader errors at the user's code.
+pub fn generate_source_map(
+    wgsl_module: &WgslShaderModuleUserPortion,
+    rust_module_transformed_for_gpu: &ItemMod,
+) -> WgslSourceMap {
+    let file = rust_module_transformed_for_gpu.ident.span().file();
+    let const_sections = wgsl_module
+        .static_consts
+        .iter()
+        .filter_map(|c| Some((c.name()?.to_string(), &c.code)));
+    let type_sections = wgsl_module
+        .helper_types
+        .iter()
+        .chain(wgsl_module.uniforms.iter())
+        .chain(wgsl_module.input_arrays.iter().map(|a| &a.item_type))
+        .chain(wgsl_module.output_arrays.iter().map(|a| &a.item_type))
+        .map(|t| (t.name.type_name().clone(), &t.code));
+    let function_sections = wgsl_module
+        .helper_functions
+        .iter()
+        .chain(wgsl_module.entry_points())
+        .map(|f| (f.name.clone(), &f.code));
+    let mut source_map = WgslSourceMap::empty();
+    const_sections
+        .chain(type_sections)
+        .chain(function_sections)
+        .for_each(|(name, code): (String, &WgslShaderModuleSectionCode)| {
+            if source_map.section(&name).is_some() {
+                // the same type can be used for more than one binding
+                return;
+            }
+            let Some(item) = find_item(rust_module_transformed_for_gpu, &name) else {
+                return;
+            };
+            source_map.sections.push(WgslSectionSourceMap {
+                rust_lines: rust_lines_of_wgsl(item.to_token_stream(), &code.wgsl_code),
+                name,
+                file: file.clone(),
+            });
+        });
+    source_map
+}
@@ -1,39 +1,24 @@
-use proc_macro_error::abort;
-use syn::{parse_quote, spanned::Spanned, visit_mut::VisitMut};
+use syn::{parse_quote, visit_mut::VisitMut};
 
+/// Converts every rust array type (`[T; N]`) into a wgsl array type (`array<T, N>`), wherever it appears: type aliases, struct fields, function parameters, return types and local variables.
 pub struct ArrayToWgslTransformer {}
 
 impl VisitMut for ArrayToWgslTransformer {
-    fn visit_item_type_mut(&mut self, t: &mut syn::ItemType) {
-        syn::visit_mut::visit_item_type_mut(self, t);
-        if let syn::Type::Array(arr) = *t.ty.clone() {
-            let type_path = array_to_wgsl(&arr);
-            *t.ty = syn::Type::Path(type_path);
-        }
-    }
-    fn visit_pat_type_mut(&mut self, t: &mut syn::PatType) {
-        syn::visit_mut::visit_pat_type_mut(self, t);
-        if let syn::Type::Array(arr) = *t.ty.clone() {
-            let type_path = array_to_wgsl(&arr);
-            *t.ty = syn::Type::Path(type_path);
+    fn visit_type_mut(&mut self, t: &mut syn::Type) {
+        // convert the innermost arrays first so that nested arrays like `[[f32; 2]; 3]` work
+        syn::visit_mut::visit_type_mut(self, t);
+        if let syn::Type::Array(arr) = t {
+            let type_path = array_to_wgsl(arr);
+            *t = syn::Type::Path(type_path);
         }
     }
 }
 
+/// The element type may be any type that is already valid wgsl, including other (already converted) arrays, vectors like `vec2<f32>`, and user defined structs.
 pub fn array_to_wgsl(arr: &syn::TypeArray) -> syn::TypePath {
-    let ident = match *arr.elem.clone() {
-        syn::Type::Path(p) => {
-            if let Some(f) = p.path.segments.first() {
-                f.ident.clone()
-            } else {
-                abort!(arr.elem.span(), "Array element type is not a path")
-            }
-        }
-        _ => abort!(arr.elem.span(), "Array element type is not a path"),
-    };
-    let len = arr.len.clone();
-
-    parse_quote!(array<#ident,#len>)
+    let elem = &arr.elem;
+    let len = &arr.len;
+    parse_quote!(array<#elem,#len>)
 }
 
 #[cfg(test)]
@@ -48,4 +33,26 @@ mod tests {
         let output = array_to_wgsl(&input);
         assert_eq!(output.to_token_stream().to_string(), "array < f32 , 4 >");
     }
+
+    #[test]
+    fn test_array_of_vectors_to_wgsl() {
+        let input: TypeArray = parse_quote! { [vec2<f32>; 4] };
+        let output = array_to_wgsl(&input);
+        assert_eq!(
+            output.to_token_stream().to_string(),
+            "array < vec2 < f32 > , 4 >"
+        );
+    }
+
+    #[test]
+    fn test_nested_arrays_and_struct_fields() {
+        let mut input: syn::ItemStruct = parse_quote! {
+            struct Body { shape: Shape, verts: [[f32; 2]; 4] }
+        };
+        ArrayToWgslTransformer {}.visit_item_struct_mut(&mut input);
+        assert_eq!(
+            input.to_token_stream().to_string(),
+            "struct Body { shape : Shape , verts : array < array < f32 , 2 > , 4 > }"
+        );
+    }
 }
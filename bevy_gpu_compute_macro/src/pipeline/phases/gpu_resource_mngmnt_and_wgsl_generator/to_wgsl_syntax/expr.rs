@@ -1,6 +1,8 @@
 use proc_macro_error::abort;
 use quote::ToTokens;
-use syn::{Expr, ExprCall, LitFloat, parse_quote, parse2, spanned::Spanned, visit_mut::VisitMut};
+use syn::{
+    Expr, ExprCall, Ident, LitFloat, parse_quote, parse2, spanned::Spanned, visit_mut::VisitMut,
+};
 
 use crate::pipeline::allowed_types::WGSL_NATIVE_TYPES;
 
@@ -31,7 +33,7 @@ pub fn expr_to_wgsl(expr: &syn::Expr) -> Option<Expr> {
                 } else if suffix == "f32" {
                     let value = l.base10_digits();
                     let value = LitFloat::new(value, l.span());
-                    return Some(parse_quote!(f32(#value)));
+                    Some(parse_quote!(f32(#value)))
                 } else {
                     abort!(
                         l.span(),
@@ -43,14 +45,14 @@ pub fn expr_to_wgsl(expr: &syn::Expr) -> Option<Expr> {
                 let suffix = l.suffix();
                 if suffix.is_empty() || suffix == "u" || suffix == "i" {
                     None
-                } else if suffix == "u32" {
+                } else if suffix == "u32" || suffix == "usize" {
                     let value = l.base10_digits();
                     let value = LitFloat::new(value, l.span());
-                    return Some(parse_quote!(u32(#value)));
-                } else if suffix == "i32" {
+                    Some(parse_quote!(u32(#value)))
+                } else if suffix == "i32" || suffix == "isize" {
                     let value = l.base10_digits();
                     let value = LitFloat::new(value, l.span());
-                    return Some(parse_quote!(i32(#value)));
+                    Some(parse_quote!(i32(#value)))
                 } else {
                     abort!(
                         l.span(),
@@ -76,7 +78,7 @@ pub fn expr_to_wgsl(expr: &syn::Expr) -> Option<Expr> {
                 "Await expressions are not supported in WGSL"
             )
         }
-        syn::Expr::Binary(bin) => None,
+        syn::Expr::Binary(bin) => compound_assignment_with_literal(bin),
         syn::Expr::Block(block) => None,
         syn::Expr::Break(break_expr) => None,
         syn::Expr::Call(call) => None,
@@ -86,6 +88,11 @@ pub fn expr_to_wgsl(expr: &syn::Expr) -> Option<Expr> {
             match *cast_type {
                 syn::Type::Path(path) => {
                     let ident = path.path.segments.first().unwrap().ident.clone();
+                    let ident = match ident.to_string().as_str() {
+                        "usize" => Ident::new("u32", ident.span()),
+                        "isize" => Ident::new("i32", ident.span()),
+                        _ => ident,
+                    };
                     Some(parse_quote! (#ident(#cast_expr)))
                 }
                 _ => None,
@@ -203,7 +210,7 @@ pub fn expr_to_wgsl(expr: &syn::Expr) -> Option<Expr> {
             } else {
                 abort!(struct_expr.span(), "Struct path is empty")
             };
-            let s = format!("{}({})", &struct_type_name.ident.to_string(), fields);
+            let s = format!("{}({})", struct_type_name.ident, fields);
             let expr: syn::ExprCall = parse2::<ExprCall>(s.parse().unwrap()).unwrap();
             // Some(syn::Expr::Verbatim(quote!(#s)))
             Some(Expr::Call(expr))
@@ -244,3 +251,68 @@ pub fn expr_to_wgsl(expr: &syn::Expr) -> Option<Expr> {
         }
     }
 }
+
+/// naga does not infer the type of an unsuffixed literal in a compound assignment like `count += 1` where `count` is a `u32`, but it does in `count = count + 1`, so those are written out in full.
+/// Only done if the left side can be evaluated twice without side effects, so `a[next()] += 1` is left alone.
+fn compound_assignment_with_literal(bin: &syn::ExprBinary) -> Option<Expr> {
+    if !matches!(&*bin.right, Expr::Lit(lit) if matches!(&lit.lit, syn::Lit::Int(_) | syn::Lit::Float(_)))
+        || !is_side_effect_free_place(&bin.left)
+    {
+        return None;
+    }
+    let op: syn::BinOp = match bin.op {
+        syn::BinOp::AddAssign(t) => syn::BinOp::Add(syn::Token![+](t.spans[0])),
+        syn::BinOp::SubAssign(t) => syn::BinOp::Sub(syn::Token![-](t.spans[0])),
+        syn::BinOp::MulAssign(t) => syn::BinOp::Mul(syn::Token![*](t.spans[0])),
+        syn::BinOp::DivAssign(t) => syn::BinOp::Div(syn::Token![/](t.spans[0])),
+        syn::BinOp::RemAssign(t) => syn::BinOp::Rem(syn::Token![%](t.spans[0])),
+        syn::BinOp::BitAndAssign(t) => syn::BinOp::BitAnd(syn::Token![&](t.spans[0])),
+        syn::BinOp::BitOrAssign(t) => syn::BinOp::BitOr(syn::Token![|](t.spans[0])),
+        syn::BinOp::BitXorAssign(t) => syn::BinOp::BitXor(syn::Token![^](t.spans[0])),
+        syn::BinOp::ShlAssign(t) => syn::BinOp::Shl(syn::Token![<<](t.spans[0])),
+        syn::BinOp::ShrAssign(t) => syn::BinOp::Shr(syn::Token![>>](t.spans[0])),
+        _ => return None,
+    };
+    let left = &bin.left;
+    let right = &bin.right;
+    Some(parse_quote!(#left = #left #op #right))
+}
+
+/// a variable, or fields and elements of one indexed by variables and literals, like `cells[i].count`
+fn is_side_effect_free_place(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(_) | Expr::Lit(_) => true,
+        Expr::Field(field) => is_side_effect_free_place(&field.base),
+        Expr::Index(index) => {
+            is_side_effect_free_place(&index.expr) && is_side_effect_free_place(&index.index)
+        }
+        Expr::Paren(paren) => is_side_effect_free_place(&paren.expr),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_compound_assignment_with_literal() {
+        let input: syn::ExprBinary = parse_quote!(cells[i].count += 1);
+        assert_eq!(
+            compound_assignment_with_literal(&input)
+                .unwrap()
+                .to_token_stream()
+                .to_string(),
+            "cells [i] . count = cells [i] . count + 1"
+        );
+    }
+
+    #[test]
+    fn test_compound_assignment_with_side_effects_is_not_rewritten() {
+        let input: syn::ExprBinary = parse_quote!(cells[next()] += 1);
+        assert!(compound_assignment_with_literal(&input).is_none());
+    }
+}
@@ -64,6 +64,7 @@ mod implicit_to_explicit_return;
 mod local_var;
 pub mod remove_attributes;
 mod remove_pub_from_struct_def;
+pub mod source_map;
 mod r#type;
 mod type_def;
 mod wgsl_builtin_constructors;
@@ -73,9 +74,7 @@ pub fn convert_file_to_wgsl(
     custom_types: &Vec<CustomType>,
     called_from: String,
 ) -> String {
-    let unprocessed_string = input.to_string();
-    let input_no_attributes = remove_attributes(unprocessed_string);
-    let processed_stream: TokenStream = input_no_attributes.parse().unwrap();
+    let processed_stream: TokenStream = remove_attributes(input);
     let debug_str = processed_stream.clone().to_string();
 
     let mut file = if let Ok(f) = parse::<File>(processed_stream.into()) {
@@ -1,5 +1,61 @@
-/// whenever `#[` is detected, remove everything up to the next `]` using regex, also remove the hash+square brackets
-pub fn remove_attributes(file: String) -> String {
-    let re = regex::Regex::new(r"#\[[^\]]*\]").unwrap();
-    re.replace_all(&file, "").to_string()
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+
+/// Removes every outer (`#[...]`) and inner (`#![...]`) attribute, including doc comments which the tokenizer turns into `#[doc = "..."]` attributes.
+/// This works on tokens instead of the string representation so that brackets inside of attributes, like ``/// see `arr[0]` ``, are handled correctly.
+pub fn remove_attributes(input: TokenStream) -> TokenStream {
+    let mut output: Vec<TokenTree> = Vec::new();
+    let mut tokens = input.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == '#' => {
+                let mut lookahead = tokens.clone();
+                if matches!(lookahead.peek(), Some(TokenTree::Punct(bang)) if bang.as_char() == '!')
+                {
+                    lookahead.next();
+                }
+                if matches!(lookahead.next(), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Bracket)
+                {
+                    tokens = lookahead;
+                } else {
+                    output.push(TokenTree::Punct(p));
+                }
+            }
+            TokenTree::Group(g) => {
+                let mut new_group = Group::new(g.delimiter(), remove_attributes(g.stream()));
+                new_group.set_span(g.span());
+                output.push(TokenTree::Group(new_group));
+            }
+            other => output.push(other),
+        }
+    }
+    output.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    fn test_remove_attributes() {
+        let input = quote! {
+            /// Returns `v[0]`, see [`other`]
+            #[inline]
+            #[allow(clippy::needless_return)]
+            fn first(v: [f32; 2]) -> f32 {
+                #![allow(unused)]
+                #[allow(unused_variables)]
+                let x = v[0];
+                return x;
+            }
+        };
+        let expected = quote! {
+            fn first(v: [f32; 2]) -> f32 {
+                let x = v[0];
+                return x;
+            }
+        };
+        assert_eq!(remove_attributes(input).to_string(), expected.to_string());
+    }
 }
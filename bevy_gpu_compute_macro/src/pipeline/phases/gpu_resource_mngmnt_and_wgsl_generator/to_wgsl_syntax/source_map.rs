@@ -0,0 +1,81 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+
+/// how far ahead in the rust identifiers to look for an identifier of the wgsl, so that common names like `x` are not matched to a far away use
+const MAX_LOOKAHEAD: usize = 32;
+
+/// The rust line of each line of `wgsl`, which was converted from `rust_tokens` by `convert_file_to_wgsl`.
+///
+/// The conversion goes through strings, so the spans are lost. Instead the identifiers of each wgsl line are matched, in order, to the identifiers of the rust code. Identifiers generated by the macro (for example by expanding WGSL helpers) are ignored, since they have no location in the user's code. Lines without a match (comments, closing braces, renamed types) get the line before them, or the first matched line if they come before any match.
+pub fn rust_lines_of_wgsl(rust_tokens: TokenStream, wgsl: &str) -> Vec<u32> {
+    let mut rust_idents: Vec<(String, u32)> = Vec::new();
+    collect_idents_with_lines(rust_tokens, &mut rust_idents);
+    let mut cursor = 0;
+    let matched: Vec<Option<u32>> = wgsl
+        .split('\n')
+        .map(|line| {
+            if line.trim_start().starts_with("//") {
+                return None;
+            }
+            let mut first_match = None;
+            for word in line
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .filter(|word| !word.is_empty())
+            {
+                let end = rust_idents.len().min(cursor + MAX_LOOKAHEAD);
+                if let Some(found) = rust_idents[cursor..end]
+                    .iter()
+                    .position(|(ident, _)| ident == word)
+                {
+                    first_match = first_match.or(Some(rust_idents[cursor + found].1));
+                    cursor += found + 1;
+                }
+            }
+            first_match
+        })
+        .collect();
+    let first = matched
+        .iter()
+        .flatten()
+        .next()
+        .copied()
+        .or(rust_idents.first().map(|(_, line)| *line))
+        .unwrap_or(0);
+    let mut previous = first;
+    matched
+        .into_iter()
+        .map(|line| {
+            previous = line.unwrap_or(previous);
+            previous
+        })
+        .collect()
+}
+
+fn collect_idents_with_lines(tokens: TokenStream, out: &mut Vec<(String, u32)>) {
+    let generated = Span::call_site();
+    for token in tokens {
+        match token {
+            TokenTree::Ident(i)
+                if i.span().start() != generated.start() || i.span().end() != generated.end() =>
+            {
+                out.push((i.to_string(), i.span().start().line as u32))
+            }
+            TokenTree::Group(g) => collect_idents_with_lines(g.stream(), out),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_lines_of_wgsl() {
+        let rust: TokenStream =
+            "fn add(a: u32, b: u32) -> u32 {\n    let sum = a + b;\n\n    return sum;\n}"
+                .parse()
+                .unwrap();
+        let wgsl = "// adds two numbers\nfn add(a : u32, b : u32) -> u32\n{ let sum = a + b;\n return sum;\n}";
+        assert_eq!(rust_lines_of_wgsl(rust, wgsl), vec![1, 1, 2, 4, 4]);
+    }
+}
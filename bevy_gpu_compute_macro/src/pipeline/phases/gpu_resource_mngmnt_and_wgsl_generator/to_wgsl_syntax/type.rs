@@ -33,6 +33,9 @@ fn convert_path_segment(segment: PathSegment, custom_types: &[CustomType]) -> Pa
             "f32" => segment.clone(),
             "i32" => segment.clone(),
             "u32" => segment.clone(),
+            // so that indices and lengths can be written the way rust expects them
+            "usize" => parse_quote!(u32),
+            "isize" => parse_quote!(i32),
             "PodF16" => parse_quote!(f16),
             "f16" => {
                 abort!(
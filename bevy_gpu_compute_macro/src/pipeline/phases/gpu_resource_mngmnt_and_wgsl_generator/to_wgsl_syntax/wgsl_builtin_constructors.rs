@@ -120,11 +120,11 @@ fn convert_single_constructor(input: &str) -> Option<(String, bool)> {
         else if let (Some(first_dim), second_dim, Some(type_str)) =
             (caps.get(5), caps.get(6), caps.get(7))
         {
-            let prefix = if second_dim.is_some() {
+            let prefix = if let Some(second_dim) = second_dim {
                 format!(
                     "mat{}x{}<{}>",
                     first_dim.as_str(),
-                    second_dim.unwrap().as_str(),
+                    second_dim.as_str(),
                     type_str.as_str()
                 )
             } else {
@@ -1,3 +1,4 @@
+use crate::pipeline::entry_points::is_entry_point;
 use bevy_gpu_compute_core::wgsl::shader_module::user_defined_portion::WgslShaderModuleUserPortion;
 use proc_macro2::Span;
 use syn::{FnArg, Ident, ItemFn, visit_mut::VisitMut};
@@ -18,8 +19,7 @@ struct MainFunctionMutator<'a> {
 impl VisitMut for MainFunctionMutator<'_> {
     fn visit_item_fn_mut(&mut self, c: &mut syn::ItemFn) {
         syn::visit_mut::visit_item_fn_mut(self, c);
-        let name = c.sig.ident.to_string();
-        if name != "main" {
+        if !is_entry_point(c) {
             return;
         }
         alter_main_function_for_cpu_usage(self.wgsl_shader_module_parsed, c);
@@ -46,7 +46,7 @@ fn add_params_to_main(
         .for_each(|uniform| {
             // turn into a PatType
             let param_name = Ident::new(uniform.name.uniform().as_str(), Span::call_site());
-            let param_type = Ident::new(uniform.name.name().as_str(), Span::call_site());
+            let param_type = Ident::new(uniform.name.type_name().as_str(), Span::call_site());
             // let r: FnArg = syn::parse_quote!(#param_name : #param_type);
             let r: FnArg = syn::parse_quote!( #param_name : #param_type );
 
@@ -61,8 +61,9 @@ fn add_params_to_main(
                 array.item_type.name.input_array().as_str(),
                 Span::call_site(),
             );
-            let param_type = Ident::new(array.item_type.name.name().as_str(), Span::call_site());
-            let r: FnArg = syn::parse_quote!(#param_name : Vec<#param_type>);
+            let param_type =
+                Ident::new(array.item_type.name.type_name().as_str(), Span::call_site());
+            let r: FnArg = syn::parse_quote!(#param_name : &[#param_type]);
             main_func.sig.inputs.push(r);
         });
     wgsl_shader_module_parsed
@@ -74,8 +75,9 @@ fn add_params_to_main(
                 array.item_type.name.output_array().as_str(),
                 Span::call_site(),
             );
-            let param_type = Ident::new(array.item_type.name.name().as_str(), Span::call_site());
-            let r: FnArg = syn::parse_quote!(mut #param_name : &mut Vec<#param_type>);
+            let param_type =
+                Ident::new(array.item_type.name.type_name().as_str(), Span::call_site());
+            let r: FnArg = syn::parse_quote!(mut #param_name : impl CpuOutput<#param_type>);
             main_func.sig.inputs.push(r);
         });
 }
@@ -117,7 +119,7 @@ fn add_array_lengths_to_main(
             );
             main_func.block.stmts.insert(
                 0,
-                syn::parse_quote!(let #var_name = #output_array_name .len();),
+                syn::parse_quote!(let #var_name = CpuOutput::max_len(&#output_array_name) as usize;),
             );
         })
 }
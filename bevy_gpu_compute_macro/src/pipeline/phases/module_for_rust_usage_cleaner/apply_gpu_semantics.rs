@@ -0,0 +1,168 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{
+    BinOp, Expr, Ident, ItemConst, ItemStatic, Type, UnOp, parse_quote, spanned::Spanned,
+    visit_mut::VisitMut,
+};
+
+/// For `#[wgsl_shader_module(gpu_semantics)]`: rewrites the arithmetic and integer casts of the CPU version of the module into calls to the `WgslArithmetic`, `WgslNeg`, `WgslShift` and `WgslConvert` traits from `bevy_gpu_compute_core`, so that integer overflow and division by zero behave like they do in WGSL, instead of panicking.
+/// `usize` and `isize` are `u32` and `i32` in WGSL, so casts to them go through `wgsl_as_u32` and `wgsl_as_i32` too.
+/// Consts and array lengths are left alone, since trait methods cannot be called in a const context, and overflow there is already a compile error.
+pub fn apply_gpu_semantics(input: &mut syn::ItemMod) {
+    GpuSemantics.visit_item_mod_mut(input);
+}
+
+struct GpuSemantics;
+
+impl VisitMut for GpuSemantics {
+    fn visit_item_const_mut(&mut self, _: &mut ItemConst) {}
+    fn visit_item_static_mut(&mut self, _: &mut ItemStatic) {}
+    fn visit_type_mut(&mut self, _: &mut Type) {}
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Const(_) => return,
+            Expr::Repeat(repeat) => {
+                self.visit_expr_mut(&mut repeat.expr);
+                return;
+            }
+            _ => syn::visit_mut::visit_expr_mut(self, expr),
+        }
+        if let Some(replacement) = gpu_semantics_replacement(expr) {
+            *expr = parse_quote!(#replacement);
+        }
+    }
+}
+
+fn gpu_semantics_replacement(expr: &Expr) -> Option<TokenStream> {
+    let span = expr.span();
+    match expr {
+        Expr::Binary(bin) => {
+            let (l, r) = (&bin.left, &bin.right);
+            let arithmetic = |method: &str, l: &TokenStream, r: &TokenStream| {
+                let method = Ident::new(method, span);
+                quote_spanned!(span=> WgslArithmetic::#method(#l, #r))
+            };
+            let shift = |method: &str, l: &TokenStream, r: &TokenStream| {
+                let method = Ident::new(method, span);
+                quote_spanned!(span=> WgslShift::#method(#l, (#r) as u32))
+            };
+            let (value, rhs) = (quote!(#l), quote!(#r));
+            // the place is evaluated once, after the right side like rust does for compound assignments to primitives, so `a[next()] += 1` only calls `next` once
+            let (place, rhs_var) = (quote!(*__wgsl_place), quote!(__wgsl_rhs));
+            let assign = |new_value: TokenStream| {
+                quote_spanned!(span=> {
+                    let __wgsl_rhs = #r;
+                    let __wgsl_place = &mut #l;
+                    *__wgsl_place = #new_value;
+                })
+            };
+            Some(match bin.op {
+                BinOp::Add(_) => arithmetic("wgsl_add", &value, &rhs),
+                BinOp::Sub(_) => arithmetic("wgsl_sub", &value, &rhs),
+                BinOp::Mul(_) => arithmetic("wgsl_mul", &value, &rhs),
+                BinOp::Div(_) => arithmetic("wgsl_div", &value, &rhs),
+                BinOp::Rem(_) => arithmetic("wgsl_rem", &value, &rhs),
+                BinOp::Shl(_) => shift("wgsl_shl", &value, &rhs),
+                BinOp::Shr(_) => shift("wgsl_shr", &value, &rhs),
+                BinOp::AddAssign(_) => assign(arithmetic("wgsl_add", &place, &rhs_var)),
+                BinOp::SubAssign(_) => assign(arithmetic("wgsl_sub", &place, &rhs_var)),
+                BinOp::MulAssign(_) => assign(arithmetic("wgsl_mul", &place, &rhs_var)),
+                BinOp::DivAssign(_) => assign(arithmetic("wgsl_div", &place, &rhs_var)),
+                BinOp::RemAssign(_) => assign(arithmetic("wgsl_rem", &place, &rhs_var)),
+                BinOp::ShlAssign(_) => assign(shift("wgsl_shl", &place, &rhs_var)),
+                BinOp::ShrAssign(_) => assign(shift("wgsl_shr", &place, &rhs_var)),
+                _ => return None,
+            })
+        }
+        // negative literals like `-1` cannot overflow
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+            if matches!(*unary.expr, Expr::Lit(_)) {
+                return None;
+            }
+            let operand = &unary.expr;
+            Some(quote_spanned!(span=> WgslNeg::wgsl_neg(#operand)))
+        }
+        Expr::Cast(cast) => {
+            let Type::Path(path) = &*cast.ty else {
+                return None;
+            };
+            let operand = &cast.expr;
+            if path.path.is_ident("u32") {
+                Some(quote_spanned!(span=> WgslConvert::wgsl_as_u32(#operand)))
+            } else if path.path.is_ident("i32") {
+                Some(quote_spanned!(span=> WgslConvert::wgsl_as_i32(#operand)))
+            } else if path.path.is_ident("usize") {
+                Some(quote_spanned!(span=> WgslConvert::wgsl_as_u32(#operand) as usize))
+            } else if path.path.is_ident("isize") {
+                Some(quote_spanned!(span=> WgslConvert::wgsl_as_i32(#operand) as isize))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+    use syn::{ItemMod, parse_quote};
+
+    use super::apply_gpu_semantics;
+
+    fn apply(mut input: ItemMod) -> String {
+        apply_gpu_semantics(&mut input);
+        input.to_token_stream().to_string()
+    }
+
+    #[test]
+    fn test_arithmetic_is_rewritten() {
+        let output = apply(parse_quote! {
+            mod test {
+                fn example(a: u32, b: u32) -> u32 {
+                    let mut c = a * b + 1;
+                    c -= a / b;
+                    c << 2
+                }
+            }
+        });
+        assert_eq!(
+            output,
+            "mod test { fn example (a : u32 , b : u32) -> u32 { let mut c = WgslArithmetic :: wgsl_add (WgslArithmetic :: wgsl_mul (a , b) , 1) ; { let __wgsl_rhs = WgslArithmetic :: wgsl_div (a , b) ; let __wgsl_place = & mut c ; * __wgsl_place = WgslArithmetic :: wgsl_sub (* __wgsl_place , __wgsl_rhs) ; } ; WgslShift :: wgsl_shl (c , (2) as u32) } }"
+        );
+    }
+
+    #[test]
+    fn test_casts_and_negation_are_rewritten() {
+        let output = apply(parse_quote! {
+            mod test {
+                fn example(a: f32, b: i32) -> i32 {
+                    let i = a as i32;
+                    let x = -1;
+                    let index = b as usize;
+                    -(i + x)
+                }
+            }
+        });
+        assert_eq!(
+            output,
+            "mod test { fn example (a : f32 , b : i32) -> i32 { let i = WgslConvert :: wgsl_as_i32 (a) ; let x = - 1 ; let index = WgslConvert :: wgsl_as_u32 (b) as usize ; WgslNeg :: wgsl_neg ((WgslArithmetic :: wgsl_add (i , x))) } }"
+        );
+    }
+
+    #[test]
+    fn test_consts_and_array_lengths_are_left_alone() {
+        let output = apply(parse_quote! {
+            mod test {
+                const N: u32 = 2 * 3;
+                fn example() {
+                    let a: [u32; 2 + 1] = [0; 2 + 1];
+                }
+            }
+        });
+        assert_eq!(
+            output,
+            "mod test { const N : u32 = 2 * 3 ; fn example () { let a : [u32 ; 2 + 1] = [0 ; 2 + 1] ; } }"
+        );
+    }
+}
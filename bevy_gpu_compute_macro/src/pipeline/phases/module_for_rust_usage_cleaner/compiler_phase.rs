@@ -2,7 +2,11 @@ use crate::pipeline::{compilation_unit::CompilationUnit, phases::compiler_phase:
 
 use super::{
     alter_main_function_for_cpu_usage::mutate_main_function_for_cpu_usage,
-    make_types_pod::make_types_pod, make_types_public::make_types_public,
+    apply_gpu_semantics::apply_gpu_semantics,
+    impl_compare_fields::impl_compare_fields,
+    impl_random_fields::impl_random_fields,
+    make_types_pod::make_types_pod,
+    make_types_public::{make_library_items_public, make_types_public},
     remove_internal_attributes::remove_internal_attributes,
 };
 
@@ -12,10 +16,18 @@ pub struct ModuleForRustUsageCleaner;
 impl CompilerPhase for ModuleForRustUsageCleaner {
     fn execute(&self, input: &mut CompilationUnit) {
         let mut m = input.rust_module_for_cpu().clone();
+        if input.options().gpu_semantics {
+            apply_gpu_semantics(&mut m);
+        }
         mutate_main_function_for_cpu_usage(input.wgsl_module_user_portion(), &mut m);
         remove_internal_attributes(&mut m);
         make_types_pod(&mut m);
+        impl_compare_fields(&mut m);
+        impl_random_fields(&mut m);
         make_types_public(&mut m);
+        if input.is_library() {
+            make_library_items_public(&mut m);
+        }
         input.set_rust_module_for_cpu(m.clone());
     }
 }
@@ -0,0 +1,81 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Item, ItemStruct, Member, parse_quote};
+
+/// implement `CompareFields` for every struct in the module, so outputs of the GPU and the CPU can be compared field by field
+pub fn impl_compare_fields(input: &mut syn::ItemMod) {
+    let Some((_, items)) = input.content.as_mut() else {
+        return;
+    };
+    let impls: Vec<Item> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(s) => Some(compare_fields_impl(s)),
+            _ => None,
+        })
+        .collect();
+    items.extend(impls);
+}
+
+fn compare_fields_impl(item: &ItemStruct) -> Item {
+    let ident = &item.ident;
+    let comparisons: TokenStream = item
+        .fields
+        .members()
+        .map(|member| {
+            let name = match &member {
+                Member::Named(ident) => ident.to_string(),
+                Member::Unnamed(index) => index.index.to_string(),
+            };
+            quote! {
+                CompareFields::compare_fields(
+                    &self.#member,
+                    &other.#member,
+                    &format!("{}.{}", path, #name),
+                    tolerance,
+                    differences,
+                );
+            }
+        })
+        .collect();
+    parse_quote! {
+        impl CompareFields for #ident {
+            fn compare_fields(
+                &self,
+                other: &Self,
+                path: &str,
+                tolerance: &FloatTolerance,
+                differences: &mut Vec<FieldDifference>,
+            ) {
+                #comparisons
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+
+    use super::*;
+
+    #[test]
+    fn test_impl_compare_fields() {
+        let mut module: syn::ItemMod = parse_quote! {
+            mod m {
+                struct Collision {
+                    entity: u32,
+                    normal: Vec2F32,
+                }
+                type Radius = f32;
+            }
+        };
+        impl_compare_fields(&mut module);
+        let items = &module.content.as_ref().unwrap().1;
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[2].to_token_stream().to_string(),
+            "impl CompareFields for Collision { fn compare_fields (& self , other : & Self , path : & str , tolerance : & FloatTolerance , differences : & mut Vec < FieldDifference > ,) { CompareFields :: compare_fields (& self . entity , & other . entity , & format ! (\"{}.{}\" , path , \"entity\") , tolerance , differences ,) ; CompareFields :: compare_fields (& self . normal , & other . normal , & format ! (\"{}.{}\" , path , \"normal\") , tolerance , differences ,) ; } }"
+        );
+    }
+}
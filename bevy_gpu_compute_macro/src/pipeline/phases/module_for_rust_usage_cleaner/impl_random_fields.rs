@@ -0,0 +1,70 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Fields, Item, ItemStruct, parse_quote};
+
+/// implement `RandomFields` for every struct in the module, so `bevy_gpu_compute::testing` can generate inputs with a fitting value for each field
+pub fn impl_random_fields(input: &mut syn::ItemMod) {
+    let Some((_, items)) = input.content.as_mut() else {
+        return;
+    };
+    let impls: Vec<Item> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(s) => Some(random_fields_impl(s)),
+            _ => None,
+        })
+        .collect();
+    items.extend(impls);
+}
+
+fn random_fields_impl(item: &ItemStruct) -> Item {
+    let ident = &item.ident;
+    let value: TokenStream = match &item.fields {
+        Fields::Named(fields) => {
+            let fields = fields.named.iter().map(|f| &f.ident);
+            quote!(Self { #(#fields: RandomFields::random_fields(rng),)* })
+        }
+        Fields::Unnamed(fields) => {
+            let fields = fields
+                .unnamed
+                .iter()
+                .map(|_| quote!(RandomFields::random_fields(rng)));
+            quote!(Self(#(#fields,)*))
+        }
+        Fields::Unit => quote!(Self),
+    };
+    parse_quote! {
+        impl RandomFields for #ident {
+            fn random_fields(rng: &mut SplitMix64) -> Self {
+                #value
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+
+    use super::*;
+
+    #[test]
+    fn test_impl_random_fields() {
+        let mut module: syn::ItemMod = parse_quote! {
+            mod m {
+                struct Collision {
+                    entity: u32,
+                    normal: Vec2F32,
+                }
+                type Radius = f32;
+            }
+        };
+        impl_random_fields(&mut module);
+        let items = &module.content.as_ref().unwrap().1;
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[2].to_token_stream().to_string(),
+            "impl RandomFields for Collision { fn random_fields (rng : & mut SplitMix64) -> Self { Self { entity : RandomFields :: random_fields (rng) , normal : RandomFields :: random_fields (rng) , } } }"
+        );
+    }
+}
@@ -1,10 +1,19 @@
-use syn::{ItemStruct, ItemType, Visibility, spanned::Spanned, token::Pub, visit_mut::VisitMut};
+use syn::{
+    ItemConst, ItemFn, ItemStruct, ItemType, Visibility, spanned::Spanned, token::Pub,
+    visit_mut::VisitMut,
+};
 
 pub fn make_types_public(input: &mut syn::ItemMod) {
     let mut transformer = MakeTypesPublicTransformer;
     transformer.visit_item_mod_mut(input);
 }
 
+/// everything in a `#[wgsl_shader_library]` has to be visible to the modules that glob import it
+pub fn make_library_items_public(input: &mut syn::ItemMod) {
+    let mut transformer = MakeLibraryItemsPublicTransformer;
+    transformer.visit_item_mod_mut(input);
+}
+
 struct MakeTypesPublicTransformer;
 
 impl VisitMut for MakeTypesPublicTransformer {
@@ -17,3 +26,20 @@ impl VisitMut for MakeTypesPublicTransformer {
         i.vis = Visibility::Public(Pub { span: i.span() });
     }
 }
+
+struct MakeLibraryItemsPublicTransformer;
+
+impl VisitMut for MakeLibraryItemsPublicTransformer {
+    fn visit_item_fn_mut(&mut self, i: &mut ItemFn) {
+        // functions nested within functions are left alone
+        i.vis = Visibility::Public(Pub { span: i.span() });
+    }
+    fn visit_item_const_mut(&mut self, i: &mut ItemConst) {
+        i.vis = Visibility::Public(Pub { span: i.span() });
+    }
+    fn visit_item_struct_mut(&mut self, i: &mut ItemStruct) {
+        i.fields.iter_mut().for_each(|f| {
+            f.vis = Visibility::Public(Pub { span: f.span() });
+        });
+    }
+}
@@ -1,5 +1,8 @@
 mod alter_main_function_for_cpu_usage;
+mod apply_gpu_semantics;
 pub mod compiler_phase;
+mod impl_compare_fields;
+mod impl_random_fields;
 mod make_types_pod;
 mod make_types_public;
 mod remove_internal_attributes;
@@ -1,6 +1,7 @@
 use syn::visit_mut::VisitMut;
-const INTERNAL_ATTRIBUTE_NAMES: [&str; 4] = [
+const INTERNAL_ATTRIBUTE_NAMES: [&str; 5] = [
     "wgsl_config",
+    "wgsl_entry",
     "wgsl_input_array",
     "wgsl_output_array",
     "wgsl_output_vec",
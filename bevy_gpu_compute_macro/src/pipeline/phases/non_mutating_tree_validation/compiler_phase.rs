@@ -1,16 +1,22 @@
 use crate::pipeline::{compilation_unit::CompilationUnit, phases::compiler_phase::CompilerPhase};
 
-use super::validate_no_doc_comments::validate_no_doc_comments;
+use super::validate_library_contents::validate_library_contents;
 use super::validate_no_iter_pos_assignments::validate_no_iter_pos_assignments;
+use super::validate_uniformity::validate_uniformity;
 use super::validate_use_statements::validate_use_statements;
+use super::validate_wgsl_identifiers::validate_wgsl_identifiers;
 
 /// any sort of input validation that can be done on the original tree that doesn't require mutation
 pub struct NonMutatingTreeValidation;
 
 impl CompilerPhase for NonMutatingTreeValidation {
     fn execute(&self, input: &mut CompilationUnit) {
-        validate_no_doc_comments(input.original_rust_module());
         validate_no_iter_pos_assignments(input.original_rust_module());
         validate_use_statements(input.original_rust_module());
+        validate_wgsl_identifiers(input.original_rust_module());
+        validate_uniformity(input.original_rust_module());
+        if input.is_library() {
+            validate_library_contents(input.original_rust_module());
+        }
     }
 }
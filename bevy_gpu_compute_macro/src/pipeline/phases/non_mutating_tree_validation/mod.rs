@@ -1,4 +1,6 @@
 pub mod compiler_phase;
-mod validate_no_doc_comments;
+mod validate_library_contents;
 mod validate_no_iter_pos_assignments;
-mod validate_use_statements;
+pub mod validate_uniformity;
+pub mod validate_use_statements;
+mod validate_wgsl_identifiers;
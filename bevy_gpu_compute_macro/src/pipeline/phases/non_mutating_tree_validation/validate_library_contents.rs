@@ -0,0 +1,42 @@
+use proc_macro_error::abort;
+
+use crate::pipeline::entry_points::is_entry_point;
+use syn::{ItemMod, spanned::Spanned, visit::Visit};
+
+const BINDING_ATTRIBUTE_NAMES: [&str; 4] = [
+    "wgsl_config",
+    "wgsl_input_array",
+    "wgsl_output_array",
+    "wgsl_output_vec",
+];
+
+/// libraries are merged into the shader modules that import them, so they cannot define an entry point or bindings of their own
+pub fn validate_library_contents(original_rust_module: &ItemMod) {
+    let mut checker = LibraryContentsChecker {};
+    checker.visit_item_mod(original_rust_module);
+}
+
+struct LibraryContentsChecker {}
+impl Visit<'_> for LibraryContentsChecker {
+    fn visit_item_fn(&mut self, f: &syn::ItemFn) {
+        syn::visit::visit_item_fn(self, f);
+        if is_entry_point(f) {
+            abort!(
+                f.sig.ident.span(),
+                "`#[wgsl_shader_library]` modules cannot have a main function or `#[wgsl_entry]` functions, put them in a `#[wgsl_shader_module]` that imports this library instead"
+            );
+        }
+    }
+    fn visit_attribute(&mut self, attr: &syn::Attribute) {
+        syn::visit::visit_attribute(self, attr);
+        if BINDING_ATTRIBUTE_NAMES
+            .iter()
+            .any(|name| attr.path().is_ident(name))
+        {
+            abort!(
+                attr.span(),
+                "`#[wgsl_shader_library]` modules cannot declare bindings, only helper types, consts and functions. Declare the binding in the `#[wgsl_shader_module]` that imports this library instead"
+            );
+        }
+    }
+}
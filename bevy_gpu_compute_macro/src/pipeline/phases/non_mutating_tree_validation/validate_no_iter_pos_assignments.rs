@@ -1,36 +1,98 @@
+use crate::pipeline::entry_points::is_entry_point;
 use proc_macro_error::abort;
-use syn::{ExprAssign, spanned::Spanned, visit::Visit};
+use syn::{BinOp, Expr, ExprAssign, ExprBinary, FnArg, Pat, spanned::Spanned, visit::Visit};
 
+/// the iteration position is a builtin on the GPU, so it cannot be changed
 pub fn validate_no_iter_pos_assignments(original_rust_module: &syn::ItemMod) {
-    let mut checker = IterPosAssignmentChecker {};
+    let mut checker = IterPosAssignmentChecker {
+        iter_pos_name: None,
+    };
     checker.visit_item_mod(original_rust_module);
 }
 
-struct IterPosAssignmentChecker {}
+struct IterPosAssignmentChecker {
+    /// the name the entry point function gave to its `WgslIterationPosition` parameter, while visiting an entry point function
+    iter_pos_name: Option<String>,
+}
 impl<'ast> Visit<'ast> for IterPosAssignmentChecker {
-    fn visit_expr_assign(&mut self, c: &'ast syn::ExprAssign) {
+    fn visit_item_fn(&mut self, f: &'ast syn::ItemFn) {
+        if !is_entry_point(f) {
+            syn::visit::visit_item_fn(self, f);
+            return;
+        }
+        // destructured fields are copies, so assigning to them does not matter
+        if let Some(FnArg::Typed(pat_type)) = f.sig.inputs.first() {
+            if let Pat::Ident(pat_ident) = &*pat_type.pat {
+                self.iter_pos_name = Some(pat_ident.ident.to_string());
+            }
+        }
+        syn::visit::visit_item_fn(self, f);
+        self.iter_pos_name = None;
+    }
+    fn visit_expr_assign(&mut self, c: &'ast ExprAssign) {
         syn::visit::visit_expr_assign(self, c);
-        check_for_iter_pos_assignment(c);
+        self.check_for_iter_pos_assignment(&c.left, c.span());
+    }
+    fn visit_expr_binary(&mut self, c: &'ast ExprBinary) {
+        syn::visit::visit_expr_binary(self, c);
+        let is_compound_assignment = matches!(
+            c.op,
+            BinOp::AddAssign(_)
+                | BinOp::SubAssign(_)
+                | BinOp::MulAssign(_)
+                | BinOp::DivAssign(_)
+                | BinOp::RemAssign(_)
+                | BinOp::BitXorAssign(_)
+                | BinOp::BitAndAssign(_)
+                | BinOp::BitOrAssign(_)
+                | BinOp::ShlAssign(_)
+                | BinOp::ShrAssign(_)
+        );
+        if is_compound_assignment {
+            self.check_for_iter_pos_assignment(&c.left, c.span());
+        }
     }
 }
 
-fn check_for_iter_pos_assignment(assign: &ExprAssign) {
-    // Check direct assignments to iter_pos
-    if let syn::Expr::Path(path) = &*assign.left {
-        if let Some(ident) = path.path.segments.last() {
-            if ident.ident == "iter_pos" {
-                abort!(assign.span(), "Cannot assign to iter_pos");
+impl IterPosAssignmentChecker {
+    fn check_for_iter_pos_assignment(&self, left: &Expr, span: proc_macro2::Span) {
+        let Some(name) = &self.iter_pos_name else {
+            return;
+        };
+        let is_iter_pos = |e: &Expr| matches!(e, Expr::Path(path) if path.path.is_ident(name));
+        // Check direct assignments to the parameter
+        if is_iter_pos(left) {
+            abort!(span, "Cannot assign to {}", name);
+        }
+        // Check field assignments like iter_pos.x
+        if let Expr::Field(field) = left {
+            if is_iter_pos(&field.base) {
+                abort!(span, "Cannot assign to {} components", name);
             }
         }
     }
-    // Check field assignments like iter_pos.x
-    if let syn::Expr::Field(field) = &*assign.left {
-        if let syn::Expr::Path(path) = &*field.base {
-            if let Some(ident) = path.path.segments.last() {
-                if ident.ident == "iter_pos" {
-                    abort!(assign.span(), "Cannot assign to iter_pos components");
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_assignments_to_other_names_are_allowed() {
+        let module: syn::ItemMod = parse_quote! {
+            mod m {
+                fn helper(iter_pos: u32) {
+                    let mut gid = iter_pos;
+                    gid = 2;
+                }
+                fn main(gid: WgslIterationPosition) {
+                    let mut iter_pos = gid.x;
+                    iter_pos += 1;
                 }
             }
-        }
+        };
+        validate_no_iter_pos_assignments(&module);
     }
 }
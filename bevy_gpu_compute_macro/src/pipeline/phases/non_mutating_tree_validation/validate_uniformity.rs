@@ -0,0 +1,587 @@
+use std::collections::{HashMap, HashSet};
+
+use proc_macro_error::{abort_if_dirty, emit_error};
+use proc_macro2::Span;
+use syn::{
+    BinOp, Block, Expr, ExprAssign, ExprBinary, ExprBreak, ExprCall, ExprContinue, ExprForLoop,
+    ExprIf, ExprLoop, ExprMatch, ExprReturn, ExprWhile, FnArg, ItemFn, ItemMod, Local, Pat,
+    spanned::Spanned, visit::Visit,
+};
+
+use crate::pipeline::entry_points::is_entry_point;
+
+/// WGSL builtins that must be called in uniform control flow, meaning by all invocations of a workgroup at once
+pub const UNIFORM_CONTROL_FLOW_BUILTINS: &[&str] = &[
+    "workgroupBarrier",
+    "storageBarrier",
+    "textureBarrier",
+    "workgroupUniformLoad",
+    "dpdx",
+    "dpdxCoarse",
+    "dpdxFine",
+    "dpdy",
+    "dpdyCoarse",
+    "dpdyFine",
+    "fwidth",
+    "fwidthCoarse",
+    "fwidthFine",
+    "textureSample",
+    "textureSampleBias",
+    "textureSampleCompare",
+];
+
+/// WGSL rejects barriers that not every invocation of a workgroup reaches, for example a barrier after `if iter_pos.x >= len { return; }`, but naga's error for it is hard to relate to the rust code. This follows WGSL's uniformity analysis closely enough to report these calls here, pointing at the call and at the branch, return or break that makes it non-uniform.
+pub fn validate_uniformity(original_rust_module: &ItemMod) {
+    let errors = find_non_uniform_barriers(original_rust_module);
+    if !errors.is_empty() {
+        for error in errors {
+            emit_error!(error.span(), error.to_string());
+        }
+        abort_if_dirty();
+    }
+}
+
+pub fn find_non_uniform_barriers(original_rust_module: &ItemMod) -> Vec<syn::Error> {
+    let mut functions = FunctionCollector(Vec::new());
+    functions.visit_item_mod(original_rust_module);
+    let functions = functions.0;
+    let requires_uniformity = functions_requiring_uniformity(&functions);
+    let mut errors = Vec::new();
+    for f in functions {
+        errors.extend(check_function(f, &requires_uniformity));
+    }
+    errors
+}
+
+/// every function of the module, including nested ones
+struct FunctionCollector<'ast>(Vec<&'ast ItemFn>);
+impl<'ast> Visit<'ast> for FunctionCollector<'ast> {
+    fn visit_item_fn(&mut self, f: &'ast ItemFn) {
+        self.0.push(f);
+        syn::visit::visit_item_fn(self, f);
+    }
+}
+
+/// the name of every called function, with the span of the call
+#[derive(Default)]
+struct CallCollector(Vec<(String, Span)>);
+impl<'ast> Visit<'ast> for CallCollector {
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        if let Some(name) = called_function(call) {
+            self.0.push((name, call.span()));
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+    fn visit_item_fn(&mut self, _f: &'ast ItemFn) {}
+}
+
+fn called_function(call: &ExprCall) -> Option<String> {
+    match &*call.func {
+        Expr::Path(path) => path.path.get_ident().map(|i| i.to_string()),
+        _ => None,
+    }
+}
+
+/// Barriers make invocations wait for each other, so the CPU runner has to run entry points that call them in lockstep
+pub const BARRIERS: &[&str] = &["workgroupBarrier", "storageBarrier"];
+
+/// The functions of the module that call a barrier, directly or through other functions
+pub fn functions_calling_barriers(module: &ItemMod) -> HashSet<String> {
+    let mut functions = FunctionCollector(Vec::new());
+    functions.visit_item_mod(module);
+    functions_calling(&functions.0, BARRIERS)
+        .into_keys()
+        .collect()
+}
+
+/// Functions that call a builtin requiring uniform control flow, directly or through other functions, mapped to that builtin. Calls to these need uniform control flow too.
+fn functions_requiring_uniformity(functions: &[&ItemFn]) -> HashMap<String, String> {
+    functions_calling(functions, UNIFORM_CONTROL_FLOW_BUILTINS)
+}
+
+/// Functions that call one of the builtins, directly or through other functions, mapped to that builtin
+fn functions_calling(functions: &[&ItemFn], builtins: &[&str]) -> HashMap<String, String> {
+    let calls: Vec<(String, Vec<String>)> = functions
+        .iter()
+        .map(|f| {
+            let mut collector = CallCollector::default();
+            collector.visit_block(&f.block);
+            (
+                f.sig.ident.to_string(),
+                collector.0.into_iter().map(|(name, _)| name).collect(),
+            )
+        })
+        .collect();
+    let mut requires: HashMap<String, String> = HashMap::new();
+    loop {
+        let mut changed = false;
+        for (name, callees) in calls.iter() {
+            if requires.contains_key(name) {
+                continue;
+            }
+            let builtin = callees.iter().find_map(|callee| {
+                if builtins.contains(&callee.as_str()) {
+                    Some(callee.clone())
+                } else {
+                    requires.get(callee).cloned()
+                }
+            });
+            if let Some(builtin) = builtin {
+                requires.insert(name.clone(), builtin);
+                changed = true;
+            }
+        }
+        if !changed {
+            return requires;
+        }
+    }
+}
+
+/// Variables only become non-uniform, never uniform again, so the function is checked until no new ones are found, which covers values carried from one loop iteration to the next.
+fn check_function(f: &ItemFn, requires_uniformity: &HashMap<String, String>) -> Vec<syn::Error> {
+    let mut non_uniform_vars: HashSet<String> = HashSet::new();
+    if is_entry_point(f) {
+        // the iteration position is different for every invocation
+        if let Some(FnArg::Typed(pat_type)) = f.sig.inputs.first() {
+            non_uniform_vars.extend(pattern_idents(&pat_type.pat));
+        }
+    }
+    loop {
+        let mut checker = UniformityChecker {
+            requires_uniformity,
+            non_uniform_vars: non_uniform_vars.clone(),
+            cause: None,
+            errors: Vec::new(),
+            reported: HashSet::new(),
+        };
+        checker.visit_block(&f.block);
+        if checker.non_uniform_vars.len() == non_uniform_vars.len() {
+            return checker.errors;
+        }
+        non_uniform_vars = checker.non_uniform_vars;
+    }
+}
+
+fn pattern_idents(pat: &Pat) -> Vec<String> {
+    struct Idents(Vec<String>);
+    impl Visit<'_> for Idents {
+        fn visit_pat_ident(&mut self, i: &syn::PatIdent) {
+            self.0.push(i.ident.to_string());
+            syn::visit::visit_pat_ident(self, i);
+        }
+    }
+    let mut idents = Idents(Vec::new());
+    idents.visit_pat(pat);
+    idents.0
+}
+
+/// the variable an assignment writes to, like `a` in `a.b[2] = 3`
+fn assigned_variable(left: &Expr) -> Option<String> {
+    match left {
+        Expr::Path(path) => path.path.get_ident().map(|i| i.to_string()),
+        Expr::Field(field) => assigned_variable(&field.base),
+        Expr::Index(index) => assigned_variable(&index.expr),
+        Expr::Paren(paren) => assigned_variable(&paren.expr),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CauseKind {
+    /// an `if`, `match`, loop condition or `&&`/`||` that is not the same for all invocations
+    Branch(&'static str),
+    Return,
+    Break,
+    Continue,
+}
+
+/// why control flow is non-uniform at some point
+#[derive(Clone, Copy)]
+struct Cause {
+    kind: CauseKind,
+    line: usize,
+}
+
+impl Cause {
+    fn new(kind: CauseKind, span: Span) -> Self {
+        Cause {
+            kind,
+            line: span.start().line,
+        }
+    }
+    fn is_exit(&self) -> bool {
+        !matches!(self.kind, CauseKind::Branch(_))
+    }
+    fn describe(&self) -> String {
+        match self.kind {
+            CauseKind::Branch(kind) => format!(
+                "it is inside the `{}` on line {}, which not all invocations take the same way",
+                kind, self.line
+            ),
+            CauseKind::Return => format!("some invocations return early on line {}", self.line),
+            CauseKind::Break => format!(
+                "some invocations `break` out of the loop on line {}",
+                self.line
+            ),
+            CauseKind::Continue => format!(
+                "some invocations `continue` to the next loop iteration on line {}",
+                self.line
+            ),
+        }
+    }
+    fn suggestion(&self, call: &str) -> String {
+        match self.kind {
+            CauseKind::Branch(kind) => format!(
+                "Move the call out of the `{}`, so that every invocation makes it, and only branch around the work before and after it",
+                kind
+            ),
+            CauseKind::Return | CauseKind::Break | CauseKind::Continue => format!(
+                "Instead of leaving early, let every invocation reach `{}` and put the work that should be skipped inside an `if`",
+                call
+            ),
+        }
+    }
+}
+
+struct UniformityChecker<'a> {
+    requires_uniformity: &'a HashMap<String, String>,
+    non_uniform_vars: HashSet<String>,
+    /// why the code currently being visited is not reached by all invocations at once, `None` while it is
+    cause: Option<Cause>,
+    errors: Vec<syn::Error>,
+    /// loop bodies can be visited twice, each call is only reported once, identified by its address in the syntax tree
+    reported: HashSet<*const ExprCall>,
+}
+
+impl UniformityChecker<'_> {
+    fn is_non_uniform(&self, expr: &Expr) -> bool {
+        struct Finder<'a> {
+            non_uniform_vars: &'a HashSet<String>,
+            found: bool,
+        }
+        impl<'ast> Visit<'ast> for Finder<'_> {
+            fn visit_path(&mut self, path: &'ast syn::Path) {
+                if let Some(ident) = path.get_ident() {
+                    if self.non_uniform_vars.contains(&ident.to_string()) {
+                        self.found = true;
+                    }
+                }
+                // the length of an output vec is an atomic counter, which other invocations change at any time
+                let segments: Vec<String> =
+                    path.segments.iter().map(|s| s.ident.to_string()).collect();
+                if segments.len() == 2
+                    && segments[0] == "WgslOutput"
+                    && segments[1].starts_with("len")
+                {
+                    self.found = true;
+                }
+                syn::visit::visit_path(self, path);
+            }
+        }
+        let mut finder = Finder {
+            non_uniform_vars: &self.non_uniform_vars,
+            found: false,
+        };
+        finder.visit_expr(expr);
+        finder.found
+    }
+
+    /// the code after a branch is non-uniform if some invocations left the function or loop inside of it
+    fn join(&mut self, outer: Option<Cause>, branches: &[Option<Cause>]) {
+        self.cause = outer.or(branches.iter().flatten().find(|c| c.is_exit()).copied());
+    }
+
+    fn visit_loop_body(&mut self, body: &Block, outer: Option<Cause>) {
+        let loop_cause = self.cause;
+        self.visit_block(body);
+        let after = self.cause;
+        // an invocation that left an iteration early does not reach the rest of the loop body in the next one
+        if loop_cause.is_none() {
+            if let Some(exit) = after {
+                self.cause = Some(exit);
+                self.visit_block(body);
+            }
+        }
+        self.cause = outer.or(after.filter(|c| c.kind == CauseKind::Return));
+    }
+
+    fn report(&mut self, call: &ExprCall, name: &str) {
+        let Some(cause) = self.cause else {
+            return;
+        };
+        if !self.reported.insert(call) {
+            return;
+        }
+        let what = match self.requires_uniformity.get(name) {
+            Some(builtin) => format!("`{}` calls `{}`, so it", name, builtin),
+            None => format!("`{}`", name),
+        };
+        self.errors.push(syn::Error::new(
+            call.span(),
+            format!(
+                "{} must be reached by all invocations of a workgroup at once, but {}. WGSL does not allow this. {}",
+                what,
+                cause.describe(),
+                cause.suggestion(name)
+            ),
+        ));
+    }
+}
+
+impl<'ast> Visit<'ast> for UniformityChecker<'_> {
+    fn visit_local(&mut self, local: &'ast Local) {
+        syn::visit::visit_local(self, local);
+        let non_uniform = self.cause.is_some()
+            || local
+                .init
+                .as_ref()
+                .is_some_and(|init| self.is_non_uniform(&init.expr));
+        if non_uniform {
+            self.non_uniform_vars.extend(pattern_idents(&local.pat));
+        }
+    }
+    fn visit_expr_assign(&mut self, assign: &'ast ExprAssign) {
+        syn::visit::visit_expr_assign(self, assign);
+        if self.cause.is_some() || self.is_non_uniform(&assign.right) {
+            self.non_uniform_vars
+                .extend(assigned_variable(&assign.left));
+        }
+    }
+    fn visit_expr_binary(&mut self, binary: &'ast ExprBinary) {
+        match binary.op {
+            // the right side is only evaluated by some invocations
+            BinOp::And(_) | BinOp::Or(_) => {
+                self.visit_expr(&binary.left);
+                let outer = self.cause;
+                if outer.is_none() && self.is_non_uniform(&binary.left) {
+                    let kind = if matches!(binary.op, BinOp::And(_)) {
+                        "&&"
+                    } else {
+                        "||"
+                    };
+                    self.cause = Some(Cause::new(CauseKind::Branch(kind), binary.op.span()));
+                }
+                self.visit_expr(&binary.right);
+                self.cause = outer;
+            }
+            BinOp::AddAssign(_)
+            | BinOp::SubAssign(_)
+            | BinOp::MulAssign(_)
+            | BinOp::DivAssign(_)
+            | BinOp::RemAssign(_)
+            | BinOp::BitXorAssign(_)
+            | BinOp::BitAndAssign(_)
+            | BinOp::BitOrAssign(_)
+            | BinOp::ShlAssign(_)
+            | BinOp::ShrAssign(_) => {
+                syn::visit::visit_expr_binary(self, binary);
+                if self.cause.is_some() || self.is_non_uniform(&binary.right) {
+                    self.non_uniform_vars
+                        .extend(assigned_variable(&binary.left));
+                }
+            }
+            _ => syn::visit::visit_expr_binary(self, binary),
+        }
+    }
+    fn visit_expr_if(&mut self, expr_if: &'ast ExprIf) {
+        self.visit_expr(&expr_if.cond);
+        let outer = self.cause;
+        let branch = match outer {
+            None if self.is_non_uniform(&expr_if.cond) => {
+                Some(Cause::new(CauseKind::Branch("if"), expr_if.if_token.span))
+            }
+            _ => outer,
+        };
+        self.cause = branch;
+        self.visit_block(&expr_if.then_branch);
+        let after_then = self.cause;
+        self.cause = branch;
+        if let Some((_, else_branch)) = &expr_if.else_branch {
+            self.visit_expr(else_branch);
+        }
+        let after_else = self.cause;
+        self.join(outer, &[after_then, after_else]);
+    }
+    fn visit_expr_match(&mut self, expr_match: &'ast ExprMatch) {
+        self.visit_expr(&expr_match.expr);
+        let outer = self.cause;
+        let branch = match outer {
+            None if self.is_non_uniform(&expr_match.expr) => Some(Cause::new(
+                CauseKind::Branch("match"),
+                expr_match.match_token.span,
+            )),
+            _ => outer,
+        };
+        let mut after_arms = Vec::new();
+        for arm in expr_match.arms.iter() {
+            self.cause = branch;
+            self.visit_arm(arm);
+            after_arms.push(self.cause);
+        }
+        self.join(outer, &after_arms);
+    }
+    fn visit_expr_while(&mut self, expr_while: &'ast ExprWhile) {
+        self.visit_expr(&expr_while.cond);
+        let outer = self.cause;
+        if outer.is_none() && self.is_non_uniform(&expr_while.cond) {
+            self.cause = Some(Cause::new(
+                CauseKind::Branch("while"),
+                expr_while.while_token.span,
+            ));
+        }
+        self.visit_loop_body(&expr_while.body, outer);
+    }
+    fn visit_expr_for_loop(&mut self, for_loop: &'ast ExprForLoop) {
+        self.visit_expr(&for_loop.expr);
+        let outer = self.cause;
+        if outer.is_some() || self.is_non_uniform(&for_loop.expr) {
+            self.non_uniform_vars.extend(pattern_idents(&for_loop.pat));
+            if outer.is_none() {
+                self.cause = Some(Cause::new(
+                    CauseKind::Branch("for"),
+                    for_loop.for_token.span,
+                ));
+            }
+        }
+        self.visit_loop_body(&for_loop.body, outer);
+    }
+    fn visit_expr_loop(&mut self, expr_loop: &'ast ExprLoop) {
+        let outer = self.cause;
+        self.visit_loop_body(&expr_loop.body, outer);
+    }
+    fn visit_expr_return(&mut self, expr_return: &'ast ExprReturn) {
+        syn::visit::visit_expr_return(self, expr_return);
+        if self.cause.is_some() {
+            self.cause = Some(Cause::new(CauseKind::Return, expr_return.return_token.span));
+        }
+    }
+    fn visit_expr_break(&mut self, expr_break: &'ast ExprBreak) {
+        syn::visit::visit_expr_break(self, expr_break);
+        if self.cause.is_some_and(|c| !c.is_exit()) {
+            self.cause = Some(Cause::new(CauseKind::Break, expr_break.break_token.span));
+        }
+    }
+    fn visit_expr_continue(&mut self, expr_continue: &'ast ExprContinue) {
+        if self.cause.is_some_and(|c| !c.is_exit()) {
+            self.cause = Some(Cause::new(
+                CauseKind::Continue,
+                expr_continue.continue_token.span,
+            ));
+        }
+    }
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        syn::visit::visit_expr_call(self, call);
+        if let Some(name) = called_function(call) {
+            if UNIFORM_CONTROL_FLOW_BUILTINS.contains(&name.as_str())
+                || self.requires_uniformity.contains_key(&name)
+            {
+                self.report(call, &name);
+            }
+        }
+    }
+    /// nested functions are checked on their own
+    fn visit_item_fn(&mut self, _f: &'ast ItemFn) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn errors(module: &ItemMod) -> Vec<String> {
+        find_non_uniform_barriers(module)
+            .iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_uniform_barriers_are_allowed() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                fn sync() {
+                    workgroupBarrier();
+                }
+                fn main(iter_pos: WgslIterationPosition) {
+                    let count = WgslConfigInput::get::<Config>().count;
+                    if count > 2 {
+                        workgroupBarrier();
+                    }
+                    for i in 0..count {
+                        sync();
+                    }
+                    workgroupBarrier();
+                    if iter_pos.x >= WgslVecInput::vec_len::<Position>() {
+                        return;
+                    }
+                    let p = WgslVecInput::vec_val::<Position>(iter_pos.x);
+                }
+            }
+        };
+        assert_eq!(errors(&module), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_non_uniform_barriers_are_reported() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                fn sync() {
+                    storageBarrier();
+                }
+                fn main(iter_pos: WgslIterationPosition) {
+                    let index = iter_pos.x * 2;
+                    if index > 4 {
+                        workgroupBarrier();
+                    }
+                    let mut i = 0;
+                    while i < iter_pos.y {
+                        i += 1;
+                    }
+                    let ok = i > 3 && sync_and_check();
+                    if iter_pos.x >= WgslVecInput::vec_len::<Position>() {
+                        return;
+                    }
+                    sync();
+                }
+                fn sync_and_check() -> bool {
+                    workgroupBarrier();
+                    return true;
+                }
+            }
+        };
+        let errors = errors(&module);
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors[0].starts_with("`workgroupBarrier` must be reached by all invocations"));
+        assert!(errors[0].contains("inside the `if`"));
+        assert!(errors[0].contains("Move the call out of the `if`"));
+        assert!(
+            errors[1]
+                .starts_with("`sync_and_check` calls `workgroupBarrier`, so it must be reached")
+        );
+        assert!(errors[1].contains("inside the `&&`"));
+        assert!(errors[2].starts_with("`sync` calls `storageBarrier`"));
+        assert!(errors[2].contains("some invocations return early"));
+        assert!(errors[2].contains("let every invocation reach `sync`"));
+    }
+
+    #[test]
+    fn test_early_loop_exits_make_the_rest_of_the_loop_non_uniform() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                fn main(iter_pos: WgslIterationPosition) {
+                    for i in 0..4 {
+                        workgroupBarrier();
+                        if i == iter_pos.x {
+                            continue;
+                        }
+                    }
+                    workgroupBarrier();
+                }
+            }
+        };
+        let errors = errors(&module);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains("`continue` to the next loop iteration"));
+    }
+}
@@ -1,9 +1,11 @@
 use proc_macro_error::abort;
 use quote::ToTokens;
-use syn::{Item, ItemMod, ItemUse, spanned::Spanned, visit::Visit};
+use syn::{Item, ItemMod, ItemUse, UseTree, spanned::Spanned, visit::Visit};
 
 const VALID_USE_STATEMENT_PATHS: [&str; 3] =
     ["wgsl_helpers", "bevy_gpu_compute", "bevy_gpu_compute_macro"];
+/// use statements starting with these import a `#[wgsl_shader_library]` module
+pub const LIBRARY_USE_STATEMENT_ROOTS: [&str; 2] = ["crate", "super"];
 
 pub fn validate_use_statements(original_rust_module: &ItemMod) {
     let mut handler = UseStatementHandler {};
@@ -22,11 +24,15 @@ impl Visit<'_> for UseStatementHandler {
 }
 
 fn validate_use_statement(use_stmt: &ItemUse) {
+    if is_library_import(use_stmt) {
+        validate_library_import(&use_stmt.tree);
+        return;
+    }
     let mut single_handler = SingleUseStatementHandler { found: false };
     single_handler.visit_item_use(use_stmt);
     if !single_handler.found {
         let message = format!(
-            "Invalid use statement: {:?}. You are only allowed to import from one of these crates: {}",
+            "Invalid use statement: {:?}. You are only allowed to import from one of these crates: {}, or to import a `#[wgsl_shader_library]` module with `use crate::path::to::library::*;`",
             use_stmt.to_token_stream().to_string(),
             VALID_USE_STATEMENT_PATHS.join(", ")
         );
@@ -34,6 +40,26 @@ fn validate_use_statement(use_stmt: &ItemUse) {
     }
 }
 
+pub fn is_library_import(use_stmt: &ItemUse) -> bool {
+    if let UseTree::Path(path) = &use_stmt.tree {
+        return LIBRARY_USE_STATEMENT_ROOTS.contains(&path.ident.to_string().as_str());
+    }
+    false
+}
+
+/// every definition of a library is added to the wgsl, so only glob imports make sense
+fn validate_library_import(tree: &UseTree) {
+    match tree {
+        UseTree::Path(path) => validate_library_import(&path.tree),
+        UseTree::Group(group) => group.items.iter().for_each(validate_library_import),
+        UseTree::Glob(_) => {}
+        UseTree::Name(_) | UseTree::Rename(_) => abort!(
+            tree.span(),
+            "Only glob imports of `#[wgsl_shader_library]` modules are supported, like `use crate::my_lib::*;`"
+        ),
+    }
+}
+
 struct SingleUseStatementHandler {
     found: bool,
 }
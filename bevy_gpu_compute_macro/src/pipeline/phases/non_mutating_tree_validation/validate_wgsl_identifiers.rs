@@ -0,0 +1,117 @@
+use proc_macro_error::{abort_if_dirty, emit_error};
+use syn::{
+    Field, Ident, ItemConst, ItemFn, ItemMod, ItemStatic, ItemStruct, ItemType, PatIdent,
+    visit::Visit,
+};
+
+/// Many valid rust identifiers, like `target`, `filter`, `ptr` or `sampler`, are keywords or reserved words in WGSL, and identifiers starting with `__` are not allowed there at all. The user's names are copied into the WGSL as they are, so these are reported here, pointing at the rust code, instead of as an error in the generated WGSL.
+pub fn validate_wgsl_identifiers(original_rust_module: &ItemMod) {
+    let errors = find_invalid_wgsl_identifiers(original_rust_module);
+    if !errors.is_empty() {
+        for error in errors {
+            emit_error!(error.span(), error.to_string());
+        }
+        abort_if_dirty();
+    }
+}
+
+pub fn find_invalid_wgsl_identifiers(original_rust_module: &ItemMod) -> Vec<syn::Error> {
+    let mut finder = InvalidIdentifierFinder { errors: Vec::new() };
+    finder.visit_item_mod(original_rust_module);
+    finder.errors
+}
+
+struct InvalidIdentifierFinder {
+    errors: Vec<syn::Error>,
+}
+
+impl InvalidIdentifierFinder {
+    fn check(&mut self, ident: &Ident) {
+        let name = ident.to_string();
+        let name = name.strip_prefix("r#").unwrap_or(&name);
+        if naga::keywords::wgsl::RESERVED.contains(&name) {
+            self.errors.push(syn::Error::new(
+                ident.span(),
+                format!(
+                    "`{}` is a keyword or reserved word in WGSL, so it cannot be used as a name in a shader module. Rename it, for example to `{}_`",
+                    name, name
+                ),
+            ));
+        } else if name.starts_with("__") {
+            self.errors.push(syn::Error::new(
+                ident.span(),
+                format!(
+                    "WGSL does not allow names starting with `__`, rename `{}`",
+                    name
+                ),
+            ));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for InvalidIdentifierFinder {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.check(&i.sig.ident);
+        syn::visit::visit_item_fn(self, i);
+    }
+    fn visit_item_const(&mut self, i: &'ast ItemConst) {
+        self.check(&i.ident);
+        syn::visit::visit_item_const(self, i);
+    }
+    fn visit_item_static(&mut self, i: &'ast ItemStatic) {
+        self.check(&i.ident);
+        syn::visit::visit_item_static(self, i);
+    }
+    fn visit_item_struct(&mut self, i: &'ast ItemStruct) {
+        self.check(&i.ident);
+        syn::visit::visit_item_struct(self, i);
+    }
+    fn visit_item_type(&mut self, i: &'ast ItemType) {
+        self.check(&i.ident);
+        syn::visit::visit_item_type(self, i);
+    }
+    fn visit_field(&mut self, i: &'ast Field) {
+        if let Some(ident) = &i.ident {
+            self.check(ident);
+        }
+        syn::visit::visit_field(self, i);
+    }
+    /// local variables and function parameters
+    fn visit_pat_ident(&mut self, i: &'ast PatIdent) {
+        self.check(&i.ident);
+        syn::visit::visit_pat_ident(self, i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_find_invalid_wgsl_identifiers() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                struct Ray {
+                    target: Vec3F32,
+                    origin: Vec3F32,
+                }
+                fn sampler(ptr: u32) -> u32 {
+                    let __hidden = ptr;
+                    let filtered = ptr;
+                    return filtered;
+                }
+            }
+        };
+        let errors: Vec<String> = find_invalid_wgsl_identifiers(&module)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors[0].starts_with("`target` is a keyword or reserved word in WGSL"));
+        assert!(errors[1].starts_with("`sampler` is a keyword"));
+        assert!(errors[2].starts_with("`ptr` is a keyword"));
+        assert!(errors[3].contains("`__hidden`"));
+    }
+}
@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use syn::Ident;
+use syn::{Attribute, Ident};
 
 use crate::pipeline::{
     phases::custom_type_collector::custom_type::CustomType,
@@ -44,14 +44,15 @@ fn get_methods(custom_types: &[CustomType]) -> TokenStream {
     custom_types
         .iter()
         .filter(|c| c.kind == CustomTypeKind::Uniform)
-        .map(|c| single_method(c.name.clone()))
+        .map(|c| single_method(c.name.clone(), c.doc_attributes()))
         .collect()
 }
-fn single_method(custom_type_name: CustomTypeIdents) -> TokenStream {
+fn single_method(custom_type_name: CustomTypeIdents, docs: Vec<Attribute>) -> TokenStream {
     let method_name: Ident = format_ident!("set_{}", custom_type_name.snake_case);
     let type_pascal_case: Ident = custom_type_name.name.clone();
-    let string_key: String = format!("{}", custom_type_name.name);
+    let string_key: String = custom_type_name.key();
     quote! {
+        #(#docs)*
         pub fn #method_name(&mut self, data: #type_pascal_case) -> &mut Self {
             self.bytes_per_wgsl_config_type_name
             .insert(#string_key .to_string(), bytemuck::bytes_of(&data).to_vec());
@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use syn::Ident;
+use syn::{Attribute, Ident};
 
 use crate::pipeline::phases::custom_type_collector::{
     custom_type::{CustomType, CustomTypeKind},
@@ -41,13 +41,14 @@ fn get_methods(custom_types: &[CustomType]) -> TokenStream {
     custom_types
         .iter()
         .filter(|c| c.kind == CustomTypeKind::OutputArray || c.kind == CustomTypeKind::OutputVec)
-        .map(|c| single_method(c.name.clone()))
+        .map(|c| single_method(c.name.clone(), c.doc_attributes()))
         .collect()
 }
-fn single_method(custom_type_name: CustomTypeIdents) -> TokenStream {
+fn single_method(custom_type_name: CustomTypeIdents, docs: Vec<Attribute>) -> TokenStream {
     let method_name: Ident = format_ident!("set_{}", custom_type_name.snake_case);
-    let string_key: String = format!("{}", custom_type_name.name);
+    let string_key: String = custom_type_name.key();
     quote! {
+        #(#docs)*
         pub fn #method_name(&mut self, length: usize) -> &mut Self {
             self.length_per_wgsl_output_type_name.insert(#string_key .to_string(), length);
             self
@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::Ident;
+use syn::{Attribute, Ident};
 
 use crate::pipeline::phases::custom_type_collector::{
     custom_type::{CustomType, CustomTypeKind},
@@ -39,16 +39,18 @@ fn get_fields_init_fields_and_converters(
     custom_types
         .iter()
         .filter(|c| c.kind == CustomTypeKind::OutputArray || c.kind == CustomTypeKind::OutputVec)
-        .map(|c| single_field_init_field_and_converter(c.name.clone()))
+        .map(|c| single_field_init_field_and_converter(c.name.clone(), c.doc_attributes()))
         .collect()
 }
 fn single_field_init_field_and_converter(
     custom_type_name: CustomTypeIdents,
+    docs: Vec<Attribute>,
 ) -> (TokenStream, TokenStream, TokenStream) {
-    let snake_name: Ident = custom_type_name.snake_case;
+    let snake_name: Ident = custom_type_name.snake_case.clone();
     let type_pascal_case: Ident = custom_type_name.name.clone();
-    let string_key: String = format!("{}", custom_type_name.name);
+    let string_key: String = custom_type_name.key();
     let field = quote! {
+        #(#docs)*
         pub #snake_name: Option<Vec<#type_pascal_case>>,
     };
     let init_field = quote! {
@@ -16,4 +16,21 @@ impl WgslHelperCategory {
             _ => None,
         }
     }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WgslHelperCategory::VecInput => "WgslVecInput",
+            WgslHelperCategory::Output => "WgslOutput",
+            WgslHelperCategory::ConfigInput => "WgslConfigInput",
+            WgslHelperCategory::_Invalid => "",
+        }
+    }
+    /// the names of the methods in this category, without the `_named` suffix
+    pub fn method_names(&self) -> &'static [&'static str] {
+        match self {
+            WgslHelperCategory::VecInput => &["vec_len", "vec_val"],
+            WgslHelperCategory::Output => &["push", "set", "len", "max_len"],
+            WgslHelperCategory::ConfigInput => &["get"],
+            WgslHelperCategory::_Invalid => &[],
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use crate::pipeline::{compilation_unit::CompilationUnit, phases::compiler_phase::CompilerPhase};
 
-use super::run::transform_wgsl_helper_methods;
+use super::run::{transform_wgsl_helper_methods, transform_wgsl_helper_methods_with_gpu_semantics};
 
 pub struct WgslHelperTransformer;
 
@@ -9,7 +9,14 @@ impl CompilerPhase for WgslHelperTransformer {
         let mut mod_for_gpu = input.original_rust_module().clone();
         let mut mod_for_cpu = input.original_rust_module().clone();
         transform_wgsl_helper_methods(input.custom_types(), &mut mod_for_gpu, false);
-        transform_wgsl_helper_methods(input.custom_types(), &mut mod_for_cpu, true);
+        if input.options().gpu_semantics {
+            transform_wgsl_helper_methods_with_gpu_semantics(
+                input.custom_types(),
+                &mut mod_for_cpu,
+            );
+        } else {
+            transform_wgsl_helper_methods(input.custom_types(), &mut mod_for_cpu, true);
+        }
         input.set_rust_module_for_cpu(mod_for_cpu);
         input.set_rust_module_for_gpu(mod_for_gpu);
     }
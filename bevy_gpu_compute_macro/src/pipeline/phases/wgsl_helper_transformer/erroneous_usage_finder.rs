@@ -1,5 +1,6 @@
 use syn::{
-    Expr,
+    Expr, ItemFn,
+    spanned::Spanned,
     visit::{self, Visit},
 };
 
@@ -7,17 +8,21 @@ use crate::pipeline::phases::custom_type_collector::custom_type::CustomType;
 
 use super::parse::parse_possible_wgsl_helper;
 
+/// Finds WGSL helpers outside of functions, like in consts. Helpers inside functions are checked while they are expanded.
 pub struct ErroneousUsageFinder {
     custom_types: Vec<CustomType>,
+    pub errors: Vec<syn::Error>,
 }
 impl ErroneousUsageFinder {
     pub fn new(custom_types: &[CustomType]) -> Self {
         Self {
             custom_types: custom_types.to_vec(),
+            errors: Vec::new(),
         }
     }
 }
 impl Visit<'_> for ErroneousUsageFinder {
+    fn visit_item_fn(&mut self, _node: &ItemFn) {}
     /// This error message relies on `WgslVecInput` being in `bevy_gpu_compute_core::wgsl_helpers`
     /**
     ```rust
@@ -28,11 +33,13 @@ impl Visit<'_> for ErroneousUsageFinder {
     fn visit_expr(&mut self, expr: &Expr) {
         visit::visit_expr(self, expr);
         if let Expr::Call(call) = expr {
-            let helper_method = parse_possible_wgsl_helper(call, &self.custom_types);
-            if helper_method.is_some() {
-                panic!(
-                    "WGSL Helpers (`bevy_gpu_compute_core::wgsl_helpers`) not allowed outside of functions."
-                );
+            match parse_possible_wgsl_helper(call, &self.custom_types) {
+                Ok(Some(_)) => self.errors.push(syn::Error::new(
+                    call.span(),
+                    "WGSL Helpers (`bevy_gpu_compute_core::wgsl_helpers`) not allowed outside of functions.",
+                )),
+                Ok(None) => {}
+                Err(error) => self.errors.push(error),
             }
         }
     }
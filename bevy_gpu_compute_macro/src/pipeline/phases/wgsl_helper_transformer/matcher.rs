@@ -1,96 +1,156 @@
+use proc_macro2::Span;
+use syn::{Expr, ExprCall, spanned::Spanned};
+
 use crate::pipeline::phases::{
     custom_type_collector::custom_type::CustomTypeKind,
     wgsl_helper_transformer::to_expanded_format::ToExpandedFormatMethodKind,
 };
 
 use super::{
-    category::WgslHelperCategory, helper_method::WgslHelperMethod,
+    category::WgslHelperCategory,
+    helper_method::WgslHelperMethod,
     method_name::WgslHelperMethodName,
+    suggestions::{attribute_for, attribute_of_kind, equivalent_helper},
 };
 
 pub struct WgslHelperMethodMatcher {}
 impl WgslHelperMethodMatcher {
-    pub fn choose_expand_format(method: &mut WgslHelperMethod) {
-        match (&method.category, &method.method) {
+    /// whether a binding of this kind can be used with the given helper method
+    pub fn accepts_kind(
+        category: &WgslHelperCategory,
+        method: &WgslHelperMethodName,
+        kind: &CustomTypeKind,
+    ) -> bool {
+        match (category, method) {
+            (WgslHelperCategory::ConfigInput, WgslHelperMethodName::Get) => {
+                *kind == CustomTypeKind::Uniform
+            }
+            (WgslHelperCategory::VecInput, WgslHelperMethodName::VecLen)
+            | (WgslHelperCategory::VecInput, WgslHelperMethodName::VecVal) => {
+                *kind == CustomTypeKind::InputArray
+            }
+            (WgslHelperCategory::Output, WgslHelperMethodName::Push)
+            | (WgslHelperCategory::Output, WgslHelperMethodName::Len) => {
+                *kind == CustomTypeKind::OutputVec
+            }
+            (WgslHelperCategory::Output, WgslHelperMethodName::MaxLen) => {
+                *kind == CustomTypeKind::OutputArray || *kind == CustomTypeKind::OutputVec
+            }
+            (WgslHelperCategory::Output, WgslHelperMethodName::Set) => {
+                *kind == CustomTypeKind::OutputArray
+            }
+            _ => false,
+        }
+    }
+    /// Sets the expander kind of the method, or returns an error pointing at `call` if the helper is used with the wrong kind of type or too few arguments
+    pub fn choose_expand_format(
+        method: &mut WgslHelperMethod,
+        call: &ExprCall,
+    ) -> Result<(), syn::Error> {
+        let (expander_kind, required_arguments) = match (&method.category, &method.method) {
             (WgslHelperCategory::ConfigInput, WgslHelperMethodName::Get) => {
-                assert!(
-                    method.t_def.kind == CustomTypeKind::Uniform,
-                    "Expected {} to be an input config type, since WgslConfigInput::get is called, instead found it was of type {:?}. Put #[wgsl_config] above your type declaration to fix this. A given type cannot be used for multiple purposes, for example a type T cannot be both a config and a input array.",
-                    method.t_def.name.name,
-                    method.t_def.kind
-                );
-                method.method_expander_kind = Some(ToExpandedFormatMethodKind::ConfigGet);
+                (ToExpandedFormatMethodKind::ConfigGet, 0)
             }
             (WgslHelperCategory::VecInput, WgslHelperMethodName::VecLen) => {
-                assert!(
-                    method.t_def.kind == CustomTypeKind::InputArray,
-                    "Expected {} to be an input array type, since WgslVecInput::vec_len is called, instead found it was of type {:?}. Put #[wgsl_input_array] above your type declaration to fix this. A given type cannot be used for multiple purposes, for example a type T cannot be both an input array and an output array.",
-                    method.t_def.name.name,
-                    method.t_def.kind
-                );
-                method.method_expander_kind = Some(ToExpandedFormatMethodKind::InputLen);
+                (ToExpandedFormatMethodKind::InputLen, 0)
             }
             (WgslHelperCategory::VecInput, WgslHelperMethodName::VecVal) => {
-                assert!(
-                    method.arg1.is_some(),
-                    "Expected an argument for input vec value getter"
-                );
-                assert!(
-                    method.t_def.kind == CustomTypeKind::InputArray,
-                    "Expected {} to be an input array type, since WgslVecInput::vec_val is called, instead found it was of type {:?}. Put #[wgsl_input_array] above your type declaration to fix this. A given type cannot be used for multiple purposes, for example a type T cannot be both an input array and an output array.",
-                    method.t_def.name.name,
-                    method.t_def.kind
-                );
-                method.method_expander_kind = Some(ToExpandedFormatMethodKind::InputVal);
+                (ToExpandedFormatMethodKind::InputVal, 1)
             }
             (WgslHelperCategory::Output, WgslHelperMethodName::Push) => {
-                assert!(
-                    method.arg1.is_some(),
-                    "Expected an argument for output push"
-                );
-                assert!(
-                    method.t_def.kind == CustomTypeKind::OutputVec,
-                    "Expected {} to be an output vec type, since WgslOutput::push is called, instead found it was of type {:?}. Put #[wgsl_output_vec] above your type declaration to fix this. A given type cannot be used for multiple purposes, for example a type T cannot be both an input array and an output array, an output cannot be both a vec and an array.",
-                    method.t_def.name.name,
-                    method.t_def.kind
-                );
-                method.method_expander_kind = Some(ToExpandedFormatMethodKind::OutputPush);
+                (ToExpandedFormatMethodKind::OutputPush, 1)
             }
             (WgslHelperCategory::Output, WgslHelperMethodName::MaxLen) => {
-                assert!(
-                    method.t_def.kind == CustomTypeKind::OutputArray
-                        || method.t_def.kind == CustomTypeKind::OutputVec,
-                    "Expected {} to be an output array or vec type, since WgslOutput::max_len is called, instead found it was of type {:?}. Put #[wgsl_output_array] or #[wgsl_output_vec] above your type declaration to fix this. A given type cannot be used for multiple purposes, for example a type T cannot be both an input array and an output array.",
-                    method.t_def.name.name,
-                    method.t_def.kind
-                );
-                method.method_expander_kind = Some(ToExpandedFormatMethodKind::OutputMaxLen);
+                (ToExpandedFormatMethodKind::OutputMaxLen, 0)
             }
             (WgslHelperCategory::Output, WgslHelperMethodName::Len) => {
-                assert!(
-                    method.t_def.kind == CustomTypeKind::OutputVec,
-                    "Expected {} to be an output vec type, since WgslOutput::len is called, instead found it was of type {:?}. Put #[wgsl_output_vec] above your type declaration to fix this. A given type cannot be used for multiple purposes, for example a type T cannot be both an input array and an output array.",
-                    method.t_def.name.name,
-                    method.t_def.kind
-                );
-                method.method_expander_kind = Some(ToExpandedFormatMethodKind::OutputLen);
+                (ToExpandedFormatMethodKind::OutputLen, 0)
             }
             (WgslHelperCategory::Output, WgslHelperMethodName::Set) => {
-                assert!(
-                    method.t_def.kind == CustomTypeKind::OutputArray,
-                    "Expected {} to be an output array type, since WgslOutput::set is called, instead found it was of type {:?}. Put #[wgsl_output_array] above your type declaration to fix this. A given type cannot be used for multiple purposes, for example a type T cannot be both an input array and an output array.",
-                    method.t_def.name.name,
-                    method.t_def.kind
-                );
-                assert!(
-                    method.arg1.is_some() && method.arg2.is_some(),
-                    "Expected two arguments for output set"
-                );
-                method.method_expander_kind = Some(ToExpandedFormatMethodKind::OutputSet);
+                (ToExpandedFormatMethodKind::OutputSet, 2)
             }
             _ => {
-                method.method_expander_kind = None;
+                // a method of another helper, like `WgslOutput::get`
+                return Err(syn::Error::new(
+                    method_ident_span(call),
+                    wrong_category_message(method),
+                ));
             }
+        };
+        if !Self::accepts_kind(&method.category, &method.method, &method.t_def.kind) {
+            return Err(syn::Error::new(call.span(), wrong_kind_message(method)));
         }
+        let given_arguments = method.arg1.is_some() as usize + method.arg2.is_some() as usize;
+        if given_arguments < required_arguments {
+            return Err(syn::Error::new(
+                call.span(),
+                format!(
+                    "`{}::{}` expects {} argument{}, but {} {} given",
+                    method.category.as_str(),
+                    method.method.as_str(),
+                    required_arguments,
+                    if required_arguments == 1 { "" } else { "s" },
+                    given_arguments,
+                    if given_arguments == 1 { "was" } else { "were" },
+                ),
+            ));
+        }
+        method.method_expander_kind = Some(expander_kind);
+        Ok(())
+    }
+}
+
+fn wrong_kind_message(method: &WgslHelperMethod) -> String {
+    let type_name = method.t_def.name.name.to_string();
+    let expected = match (&method.category, &method.method) {
+        (WgslHelperCategory::ConfigInput, _) => "an input config type",
+        (WgslHelperCategory::VecInput, _) => "an input array type",
+        (WgslHelperCategory::Output, WgslHelperMethodName::Set) => "an output array type",
+        (WgslHelperCategory::Output, WgslHelperMethodName::MaxLen) => "an output array or vec type",
+        _ => "an output vec type",
+    };
+    let found = match attribute_of_kind(&method.t_def.kind) {
+        Some(attribute) => format!("it is marked with {}", attribute),
+        None => "it has no binding attribute".to_string(),
+    };
+    let mut message = format!(
+        "Expected {} to be {}, since {}::{} is called, instead {}. Put {} above your type declaration to fix this. A type can be used for several bindings by adding several of these attributes.",
+        type_name,
+        expected,
+        method.category.as_str(),
+        method.method.as_str(),
+        found,
+        attribute_for(&method.category, &method.method),
+    );
+    if let Some(helper) = equivalent_helper(&method.method, &method.t_def.kind, &type_name) {
+        message.push_str(&format!(" Or did you mean `{}`?", helper));
+    }
+    message
+}
+
+fn wrong_category_message(method: &WgslHelperMethod) -> String {
+    let mut message = format!(
+        "`{}::{}` is not a WGSL helper method, the methods of `{}` are: {}.",
+        method.category.as_str(),
+        method.method.as_str(),
+        method.category.as_str(),
+        method.category.method_names().join(", ")
+    );
+    let type_name = method.t_def.name.name.to_string();
+    if let Some(helper) = equivalent_helper(&method.method, &method.t_def.kind, &type_name) {
+        message.push_str(&format!(" Did you mean `{}`?", helper));
+    }
+    message
+}
+
+/// the span of `get` in `WgslOutput::get::<T>()`, or of the whole call if it is not a path
+fn method_ident_span(call: &ExprCall) -> Span {
+    match &*call.func {
+        Expr::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map_or(call.span(), |segment| segment.ident.span()),
+        _ => call.span(),
     }
 }
@@ -23,4 +23,16 @@ impl WgslHelperMethodName {
             _ => None,
         }
     }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WgslHelperMethodName::VecLen => "vec_len",
+            WgslHelperMethodName::VecVal => "vec_val",
+            WgslHelperMethodName::Push => "push",
+            WgslHelperMethodName::Len => "len",
+            WgslHelperMethodName::MaxLen => "max_len",
+            WgslHelperMethodName::Set => "set",
+            WgslHelperMethodName::Get => "get",
+            WgslHelperMethodName::_Invalid => "",
+        }
+    }
 }
@@ -6,6 +6,7 @@ mod matcher;
 mod method_name;
 mod parse;
 mod run;
+mod suggestions;
 mod test;
 mod test_for_cpu;
 mod to_expanded_format;
@@ -1,11 +1,13 @@
-use syn::{Expr, ExprCall, GenericArgument, PathArguments, Type};
+use syn::{
+    Expr, ExprCall, ExprLit, GenericArgument, Ident, Lit, PathArguments, Type, spanned::Spanned,
+};
 
-use crate::pipeline::phases::custom_type_collector::custom_type::CustomType;
+use crate::pipeline::phases::custom_type_collector::custom_type::{CustomType, CustomTypeKind};
 use crate::pipeline::phases::wgsl_helper_transformer::helper_method::WgslHelperMethod;
 
 use super::{
     category::WgslHelperCategory, matcher::WgslHelperMethodMatcher,
-    method_name::WgslHelperMethodName,
+    method_name::WgslHelperMethodName, suggestions::closest_name,
 };
 fn get_special_function_category(call: &ExprCall) -> Option<WgslHelperCategory> {
     if let Expr::Path(path) = &*call.func {
@@ -15,57 +17,210 @@ fn get_special_function_category(call: &ExprCall) -> Option<WgslHelperCategory>
     }
     None
 }
-fn get_special_function_method(call: &ExprCall) -> Option<WgslHelperMethodName> {
+/// the bool is true for the `_named` variants of the helper methods, like `vec_val_named`
+fn get_special_function_method(call: &ExprCall) -> Option<(WgslHelperMethodName, bool)> {
     if let Expr::Path(path) = &*call.func {
         if let Some(last_seg) = path.path.segments.last() {
-            return WgslHelperMethodName::from_ident(last_seg.ident.clone());
+            let ident = last_seg.ident.to_string();
+            if let Some(unnamed) = ident.strip_suffix("_named") {
+                return WgslHelperMethodName::from_ident(Ident::new(
+                    unnamed,
+                    last_seg.ident.span(),
+                ))
+                .map(|m| (m, true));
+            }
+            return WgslHelperMethodName::from_ident(last_seg.ident.clone()).map(|m| (m, false));
         }
     }
     None
 }
-fn get_special_function_generic_type<'a>(
-    call: &'a ExprCall,
-    custom_types: &'a [CustomType],
-) -> Option<&'a CustomType> {
+/// the error for a helper call of a known category whose method does not exist, like `WgslOutput::pushh`
+fn unknown_method_error(call: &ExprCall, category: &WgslHelperCategory) -> syn::Error {
+    let Expr::Path(path) = &*call.func else {
+        return syn::Error::new(call.span(), "Unknown WGSL helper method");
+    };
+    let Some(last_seg) = path.path.segments.last() else {
+        return syn::Error::new(call.span(), "Unknown WGSL helper method");
+    };
+    let ident = last_seg.ident.to_string();
+    let (unnamed, suffix) = match ident.strip_suffix("_named") {
+        Some(unnamed) => (unnamed, "_named"),
+        None => (ident.as_str(), ""),
+    };
+    let mut message = format!(
+        "`{}::{}` is not a WGSL helper method.",
+        category.as_str(),
+        ident
+    );
+    match closest_name(unnamed, category.method_names().iter().copied()) {
+        Some(suggestion) => message.push_str(&format!(
+            " Did you mean `{}::{}{}`?",
+            category.as_str(),
+            suggestion,
+            suffix
+        )),
+        None => message.push_str(&format!(
+            " The methods of `{}` are: {}",
+            category.as_str(),
+            category.method_names().join(", ")
+        )),
+    }
+    syn::Error::new(last_seg.ident.span(), message)
+}
+/// the type given as the generic argument of the helper, like `Position` in `WgslVecInput::vec_val::<Position>(i)`
+fn get_special_function_generic_type(call: &ExprCall) -> Option<&Ident> {
     if let Expr::Path(path) = &*call.func {
         if let Some(last_seg) = path.path.segments.last() {
             if let PathArguments::AngleBracketed(args) = &last_seg.arguments {
                 if let Some(GenericArgument::Type(Type::Path(type_path))) = args.args.first() {
-                    if let Some(last_seg) = type_path.path.segments.last() {
-                        return custom_types.iter().find(|t| t.name.eq(&last_seg.ident));
-                    }
+                    return type_path.path.segments.last().map(|s| &s.ident);
                 }
             }
         }
     }
     None
 }
-
-pub fn parse_possible_wgsl_helper<'a>(
+/// all custom types (one per binding) that have the type given as the generic argument
+fn get_special_function_generic_type_candidates<'a>(
     call: &'a ExprCall,
     custom_types: &'a [CustomType],
-) -> Option<WgslHelperMethod> {
-    let category = get_special_function_category(call);
-    let method = get_special_function_method(call);
-    let type_name = get_special_function_generic_type(call, custom_types);
-    if let Some(cat) = category {
-        if let Some(met) = method {
-            if let Some(ty) = type_name {
-                let args = call.args.clone();
-                let mut method = WgslHelperMethod {
-                    category: cat,
-                    method: met,
-                    t_def: ty.clone(),
-                    arg1: args.first().cloned(),
-                    arg2: args.get(1).cloned(),
-                    method_expander_kind: None,
-                };
-                WgslHelperMethodMatcher::choose_expand_format(&mut method);
-                if method.method_expander_kind.is_some() {
-                    return Some(method);
-                }
+) -> Result<Vec<&'a CustomType>, syn::Error> {
+    let Some(type_ident) = get_special_function_generic_type(call) else {
+        return Err(syn::Error::new(
+            call.func.span(),
+            "WGSL helpers need the type of the binding as a generic argument, for example `WgslVecInput::vec_val::<Position>(index)`",
+        ));
+    };
+    let binding_types: Vec<&CustomType> = custom_types
+        .iter()
+        .filter(|t| t.kind != CustomTypeKind::ArrayLengthVariable)
+        .collect();
+    let candidates: Vec<&CustomType> = binding_types
+        .iter()
+        .filter(|t| t.name.eq(type_ident))
+        .copied()
+        .collect();
+    if candidates.is_empty() {
+        let mut message = format!(
+            "`{}` is not an input, output or config type of this shader module.",
+            type_ident
+        );
+        let names: Vec<String> = binding_types
+            .iter()
+            .map(|t| t.name.name.to_string())
+            .collect();
+        match closest_name(&type_ident.to_string(), names.iter().map(|n| n.as_str())) {
+            Some(suggestion) => message.push_str(&format!(" Did you mean `{}`?", suggestion)),
+            None => message.push_str(
+                " Mark the type with an attribute like #[wgsl_input_array], #[wgsl_output_vec], #[wgsl_output_array] or #[wgsl_config] to use it with WGSL helpers.",
+            ),
+        }
+        return Err(syn::Error::new(type_ident.span(), message));
+    }
+    Ok(candidates)
+}
+
+fn get_binding_name_argument(call: &ExprCall) -> Result<String, syn::Error> {
+    match call.args.first() {
+        Some(Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        })) => Ok(s.value()),
+        Some(other) => Err(syn::Error::new(
+            other.span(),
+            "The first argument of a `_named` WGSL helper must be the binding name, as a string literal",
+        )),
+        None => Err(syn::Error::new(
+            call.span(),
+            "The first argument of a `_named` WGSL helper must be the binding name, as a string literal",
+        )),
+    }
+}
+
+/// Picks the binding that the helper refers to. If no binding has a fitting kind the first candidate is returned, so that the matcher can report the problem.
+fn choose_binding<'a>(
+    call: &ExprCall,
+    category: &WgslHelperCategory,
+    method: &WgslHelperMethodName,
+    binding_name: Option<&String>,
+    candidates: Vec<&'a CustomType>,
+) -> Result<&'a CustomType, syn::Error> {
+    let candidates: Vec<&CustomType> = match binding_name {
+        Some(name) => {
+            let named: Vec<&CustomType> = candidates
+                .into_iter()
+                .filter(|c| c.name.binding_name.as_ref().is_some_and(|b| b == name))
+                .collect();
+            if named.is_empty() {
+                return Err(syn::Error::new(
+                    call.span(),
+                    format!(
+                        "No binding named \"{}\" exists for this type. Declare one with an attribute like `#[wgsl_input_array(name = \"{}\")]`",
+                        name, name
+                    ),
+                ));
             }
+            named
         }
+        None => candidates,
+    };
+    let fitting: Vec<&CustomType> = candidates
+        .iter()
+        .filter(|c| WgslHelperMethodMatcher::accepts_kind(category, method, &c.kind))
+        .copied()
+        .collect();
+    if fitting.len() == 1 {
+        return Ok(fitting[0]);
     }
-    None
+    let unnamed: Vec<&CustomType> = fitting
+        .iter()
+        .filter(|c| c.name.binding_name.is_none())
+        .copied()
+        .collect();
+    if unnamed.len() == 1 {
+        return Ok(unnamed[0]);
+    }
+    if fitting.len() > 1 {
+        return Err(syn::Error::new(
+            call.span(),
+            format!(
+                "`{}` is used for more than one binding of this kind, use the `_named` version of this helper to choose one, for example `WgslVecInput::vec_val_named::<{}>(\"{}\", index)`",
+                fitting[0].name.name,
+                fitting[0].name.name,
+                fitting[0].name.key()
+            ),
+        ));
+    }
+    Ok(candidates[0])
+}
+
+/// Returns `Ok(None)` for calls that are not WGSL helpers, and an error pointing at the call if it is a helper that is used incorrectly.
+pub fn parse_possible_wgsl_helper<'a>(
+    call: &'a ExprCall,
+    custom_types: &'a [CustomType],
+) -> Result<Option<WgslHelperMethod>, syn::Error> {
+    let Some(cat) = get_special_function_category(call) else {
+        return Ok(None);
+    };
+    let Some((met, named)) = get_special_function_method(call) else {
+        return Err(unknown_method_error(call, &cat));
+    };
+    let candidates = get_special_function_generic_type_candidates(call, custom_types)?;
+    let binding_name = if named {
+        Some(get_binding_name_argument(call)?)
+    } else {
+        None
+    };
+    let ty = choose_binding(call, &cat, &met, binding_name.as_ref(), candidates)?;
+    // the binding name is not an argument of the expanded helper
+    let args: Vec<&Expr> = call.args.iter().skip(named as usize).collect();
+    let mut method = WgslHelperMethod {
+        category: cat,
+        method: met,
+        t_def: ty.clone(),
+        arg1: args.first().cloned().cloned(),
+        arg2: args.get(1).cloned().cloned(),
+        method_expander_kind: None,
+    };
+    WgslHelperMethodMatcher::choose_expand_format(&mut method, call)?;
+    Ok(method.method_expander_kind.is_some().then_some(method))
 }
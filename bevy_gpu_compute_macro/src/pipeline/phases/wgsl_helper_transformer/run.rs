@@ -1,11 +1,14 @@
+use proc_macro_error::{abort_if_dirty, emit_error};
 use proc_macro2::TokenStream;
 
 use syn::{
-    Expr, ItemFn, ItemMod, parse_quote,
+    Expr, ExprCall, ItemFn, ItemMod, parse_quote,
+    spanned::Spanned,
     visit::Visit,
     visit_mut::{self, VisitMut},
 };
 
+use crate::pipeline::entry_points::is_entry_point;
 use crate::pipeline::phases::custom_type_collector::custom_type::CustomType;
 
 use super::{
@@ -14,18 +17,47 @@ use super::{
     to_expanded_format_for_cpu::ToExpandedFormatForCpu,
 };
 
-/// Rust's normal type checking will ensure that these helper functions are using correctly defined types
+/// Rust's normal type checking will ensure that these helper functions are using correctly defined types.
+/// Misused helpers are reported as compile errors, all at once, pointing at the offending calls. They are only reported for the GPU version of the module, since the CPU version is converted from the same code.
 pub fn transform_wgsl_helper_methods(
     custom_types: &[CustomType],
     rust_module: &mut ItemMod,
     for_cpu: bool,
 ) {
+    let errors = try_transform_wgsl_helper_methods(custom_types, rust_module, for_cpu);
+    if !for_cpu && !errors.is_empty() {
+        for error in errors {
+            emit_error!(error.span(), error.to_string());
+        }
+        abort_if_dirty();
+    }
+}
+
+/// The CPU version of a module with `#[wgsl_shader_module(gpu_semantics)]`, where inputs and outputs are accessed like with robust buffer access on the GPU: out of range reads return zeroed values, and out of range writes are dropped
+pub fn transform_wgsl_helper_methods_with_gpu_semantics(
+    custom_types: &[CustomType],
+    rust_module: &mut ItemMod,
+) {
+    let mut converter = WgslHelperExpressionConverter::new(custom_types, true);
+    converter.gpu_semantics = true;
+    converter.visit_item_mod_mut(rust_module);
+}
+
+/// Like `transform_wgsl_helper_methods`, but returns the errors instead of emitting them. Helper calls with errors are left as they are.
+pub fn try_transform_wgsl_helper_methods(
+    custom_types: &[CustomType],
+    rust_module: &mut ItemMod,
+    for_cpu: bool,
+) -> Vec<syn::Error> {
     let mut converter = WgslHelperExpressionConverter::new(custom_types, for_cpu);
     converter.visit_item_mod_mut(rust_module);
+    let mut errors = converter.errors;
     if !for_cpu {
         let mut error_finder = ErroneousUsageFinder::new(custom_types);
         error_finder.visit_item_mod(rust_module);
+        errors.extend(error_finder.errors);
     }
+    errors
 }
 
 struct WgslHelperExpressionConverter {
@@ -33,11 +65,13 @@ struct WgslHelperExpressionConverter {
     in_main_func: bool,
     nesting_level: u32,
     for_cpu: bool,
+    gpu_semantics: bool,
+    errors: Vec<syn::Error>,
 }
 
 impl VisitMut for WgslHelperExpressionConverter {
     fn visit_item_fn_mut(&mut self, node: &mut ItemFn) {
-        if node.sig.ident == "main" && self.nesting_level == 0 {
+        if is_entry_point(node) && self.nesting_level == 0 {
             self.in_main_func = true;
             self.nesting_level += 1;
             visit_mut::visit_item_fn_mut(self, node);
@@ -54,15 +88,20 @@ impl VisitMut for WgslHelperExpressionConverter {
         if self.nesting_level > 0 {
             let in_main = self.in_main_func && self.nesting_level == 1;
             if let Expr::Call(call) = expr {
-                let helper_method = parse_possible_wgsl_helper(call, &self.custom_types);
-                if let Some(method) = helper_method {
-                    if self.for_cpu {
-                        let replacement = process_wgsl_helper_for_cpu(method);
-                        *expr = parse_quote!(#replacement);
-                    } else {
-                        let replacement = process_wgsl_helper(method, in_main);
-                        *expr = parse_quote!(#replacement);
+                match parse_possible_wgsl_helper(call, &self.custom_types) {
+                    Ok(Some(method)) => {
+                        let replacement = if self.for_cpu {
+                            Ok(ToExpandedFormatForCpu::run(&method, self.gpu_semantics))
+                        } else {
+                            process_wgsl_helper(method, call, in_main)
+                        };
+                        match replacement {
+                            Ok(replacement) => *expr = parse_quote!(#replacement),
+                            Err(error) => self.errors.push(error),
+                        }
                     }
+                    Ok(None) => {}
+                    Err(error) => self.errors.push(error),
                 }
             }
         }
@@ -77,11 +116,17 @@ impl WgslHelperExpressionConverter {
             in_main_func: false,
             nesting_level: 0,
             for_cpu,
+            gpu_semantics: false,
+            errors: Vec::new(),
         }
     }
 }
 
-fn process_wgsl_helper(helper_method: WgslHelperMethod, in_main_func: bool) -> TokenStream {
+fn process_wgsl_helper(
+    helper_method: WgslHelperMethod,
+    call: &ExprCall,
+    in_main_func: bool,
+) -> Result<TokenStream, syn::Error> {
     if !helper_method
         .method_expander_kind
         .as_ref()
@@ -89,12 +134,10 @@ fn process_wgsl_helper(helper_method: WgslHelperMethod, in_main_func: bool) -> T
         .valid_outside_main()
         && !in_main_func
     {
-        panic!(
-            "WGSL helpers that read from inputs or write to outputs (`bevy_gpu_compute_core::wgsl_helpers`) can only be used inside the main function. It is technically possible to pass in entire input arrays, configs, or output arrays to helper functions, but considering the performance implications, it is not recommended. Instead interact with your inputs and outputs in the main function and pass in only the necessary data to the helper functions."
-        );
+        return Err(syn::Error::new(
+            call.span(),
+            "WGSL helpers that read from inputs or write to outputs (`bevy_gpu_compute_core::wgsl_helpers`) can only be used inside the main function. It is technically possible to pass in entire input arrays, configs, or output arrays to helper functions, but considering the performance implications, it is not recommended. Instead interact with your inputs and outputs in the main function and pass in only the necessary data to the helper functions. Functions marked `#[wgsl_entry]` count as main functions.",
+        ));
     }
-    ToExpandedFormat::run(&helper_method)
-}
-fn process_wgsl_helper_for_cpu(helper_method: WgslHelperMethod) -> TokenStream {
-    ToExpandedFormatForCpu::run(&helper_method)
+    Ok(ToExpandedFormat::run(&helper_method))
 }
@@ -0,0 +1,115 @@
+use crate::pipeline::phases::custom_type_collector::custom_type::CustomTypeKind;
+
+use super::{category::WgslHelperCategory, method_name::WgslHelperMethodName};
+
+/// The candidate closest to `name`, if it is close enough to probably be a typo of it.
+pub fn closest_name<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance, ignoring case
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// the attribute that makes a type usable with the given helper
+pub fn attribute_for(category: &WgslHelperCategory, method: &WgslHelperMethodName) -> &'static str {
+    match (category, method) {
+        (WgslHelperCategory::ConfigInput, _) => "#[wgsl_config]",
+        (WgslHelperCategory::VecInput, _) => "#[wgsl_input_array]",
+        (WgslHelperCategory::Output, WgslHelperMethodName::Set) => "#[wgsl_output_array]",
+        (WgslHelperCategory::Output, WgslHelperMethodName::MaxLen) => {
+            "#[wgsl_output_array] or #[wgsl_output_vec]"
+        }
+        (WgslHelperCategory::Output, _) => "#[wgsl_output_vec]",
+        (WgslHelperCategory::_Invalid, _) => "",
+    }
+}
+
+pub fn attribute_of_kind(kind: &CustomTypeKind) -> Option<&'static str> {
+    match kind {
+        CustomTypeKind::Uniform => Some("#[wgsl_config]"),
+        CustomTypeKind::InputArray => Some("#[wgsl_input_array]"),
+        CustomTypeKind::OutputArray => Some("#[wgsl_output_array]"),
+        CustomTypeKind::OutputVec => Some("#[wgsl_output_vec]"),
+        _ => None,
+    }
+}
+
+/// The helper that does the same thing for the kind of binding the type actually has, like `WgslOutput::push` instead of `WgslOutput::set` for a `#[wgsl_output_vec]`.
+pub fn equivalent_helper(
+    method: &WgslHelperMethodName,
+    kind: &CustomTypeKind,
+    type_name: &str,
+) -> Option<String> {
+    let helper = match (method, kind) {
+        (WgslHelperMethodName::Set, CustomTypeKind::OutputVec) => {
+            format!("WgslOutput::push::<{}>(value)", type_name)
+        }
+        (WgslHelperMethodName::Push, CustomTypeKind::OutputArray) => {
+            format!("WgslOutput::set::<{}>(index, value)", type_name)
+        }
+        (WgslHelperMethodName::Len, CustomTypeKind::OutputArray) => {
+            format!("WgslOutput::max_len::<{}>()", type_name)
+        }
+        (WgslHelperMethodName::VecVal | WgslHelperMethodName::VecLen, CustomTypeKind::Uniform) => {
+            format!("WgslConfigInput::get::<{}>()", type_name)
+        }
+        (WgslHelperMethodName::Get, CustomTypeKind::InputArray) => {
+            format!("WgslVecInput::vec_val::<{}>(index)", type_name)
+        }
+        // the same method of the helper that fits the binding, for methods used on the wrong helper like `WgslOutput::get`
+        (WgslHelperMethodName::Get, CustomTypeKind::Uniform) => {
+            format!("WgslConfigInput::get::<{}>()", type_name)
+        }
+        (WgslHelperMethodName::VecVal, CustomTypeKind::InputArray) => {
+            format!("WgslVecInput::vec_val::<{}>(index)", type_name)
+        }
+        (WgslHelperMethodName::VecLen, CustomTypeKind::InputArray) => {
+            format!("WgslVecInput::vec_len::<{}>()", type_name)
+        }
+        (WgslHelperMethodName::VecLen | WgslHelperMethodName::Len, CustomTypeKind::OutputVec) => {
+            format!("WgslOutput::len::<{}>()", type_name)
+        }
+        (WgslHelperMethodName::VecLen, CustomTypeKind::OutputArray) => {
+            format!("WgslOutput::max_len::<{}>()", type_name)
+        }
+        _ => return None,
+    };
+    Some(helper)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closest_name() {
+        assert_eq!(closest_name("pushh", ["push", "set", "len"]), Some("push"));
+        assert_eq!(
+            closest_name("Positon", ["Position", "Radius"]),
+            Some("Position")
+        );
+        assert_eq!(closest_name("velocity", ["Position", "Radius"]), None);
+    }
+}
@@ -2,13 +2,22 @@
 mod tests {
     use crate::pipeline::phases::{
         custom_type_collector::custom_type::{CustomType, CustomTypeKind},
-        wgsl_helper_transformer::run::transform_wgsl_helper_methods,
+        wgsl_helper_transformer::run::{
+            transform_wgsl_helper_methods, try_transform_wgsl_helper_methods,
+        },
     };
 
     use proc_macro2::TokenStream;
     use quote::{ToTokens, format_ident};
     use syn::{ItemMod, parse_quote};
 
+    fn assert_only_in_main_error(errors: &[syn::Error]) {
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().starts_with(
+            "WGSL helpers that read from inputs or write to outputs (`bevy_gpu_compute_core::wgsl_helpers`) can only be used inside the main function."
+        ));
+    }
+
     #[test]
     fn test_vec_len() {
         let mut input: ItemMod = parse_quote! {
@@ -37,9 +46,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "WGSL helpers that read from inputs or write to outputs (`bevy_gpu_compute_core::wgsl_helpers`) can only be used inside the main function. It is technically possible to pass in entire input arrays, configs, or output arrays to helper functions, but considering the performance implications, it is not recommended. Instead interact with your inputs and outputs in the main function and pass in only the necessary data to the helper functions."
-    )]
     fn test_vec_val_only_in_main() {
         let mut input: ItemMod = parse_quote! {
             mod test {
@@ -56,7 +62,8 @@ mod tests {
             TokenStream::new(),
         )];
 
-        transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        let errors = try_transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        assert_only_in_main_error(&errors);
     }
 
     #[test]
@@ -88,9 +95,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "WGSL helpers that read from inputs or write to outputs (`bevy_gpu_compute_core::wgsl_helpers`) can only be used inside the main function. It is technically possible to pass in entire input arrays, configs, or output arrays to helper functions, but considering the performance implications, it is not recommended. Instead interact with your inputs and outputs in the main function and pass in only the necessary data to the helper functions."
-    )]
     fn test_push_only_in_main() {
         let mut input: ItemMod = parse_quote! {
             mod test {
@@ -106,7 +110,8 @@ mod tests {
             TokenStream::new(),
         )];
 
-        transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        let errors = try_transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        assert_only_in_main_error(&errors);
     }
     #[test]
     fn test_push() {
@@ -118,7 +123,7 @@ mod tests {
             }
         };
 
-        let expected_output = "mod test { fn main () { { let collisionresult_output_array_index = atomicAdd (& collisionresult_counter , 1u) ; if collisionresult_output_array_index < COLLISIONRESULT_OUTPUT_ARRAY_LENGTH { collisionresult_output_array [collisionresult_output_array_index] = value ; } } ; } }";
+        let expected_output = "mod test { fn main () { { let collision_result_output_array_index = atomicAdd (& collision_result_counter , 1u) ; if collision_result_output_array_index < COLLISION_RESULT_OUTPUT_ARRAY_LENGTH { collision_result_output_array [collision_result_output_array_index] = value ; } } ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("CollisionResult"),
@@ -147,7 +152,7 @@ mod tests {
             }
         };
         let expected_output =
-            "mod test { fn example () { let x = COLLISIONRESULT_OUTPUT_ARRAY_LENGTH ; } }";
+            "mod test { fn example () { let x = COLLISION_RESULT_OUTPUT_ARRAY_LENGTH ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("CollisionResult"),
@@ -175,7 +180,7 @@ mod tests {
                 }
             }
         };
-        let expected_output = "mod test { fn example () { let x = collisionresult_counter ; } }";
+        let expected_output = "mod test { fn example () { let x = collision_result_counter ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("CollisionResult"),
@@ -195,9 +200,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "WGSL helpers that read from inputs or write to outputs (`bevy_gpu_compute_core::wgsl_helpers`) can only be used inside the main function. It is technically possible to pass in entire input arrays, configs, or output arrays to helper functions, but considering the performance implications, it is not recommended. Instead interact with your inputs and outputs in the main function and pass in only the necessary data to the helper functions."
-    )]
     fn test_output_set_not_in_main() {
         let mut input: ItemMod = parse_quote! {
             mod test {
@@ -213,7 +215,8 @@ mod tests {
             TokenStream::new(),
         )];
 
-        transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        let errors = try_transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        assert_only_in_main_error(&errors);
     }
     #[test]
 
@@ -226,7 +229,7 @@ mod tests {
             }
         };
         let expected_output =
-            "mod test { fn main () { collisionresult_output_array [idx] = val ; } }";
+            "mod test { fn main () { collision_result_output_array [idx] = val ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("CollisionResult"),
@@ -245,9 +248,6 @@ mod tests {
         );
     }
     #[test]
-    #[should_panic(
-        expected = "WGSL helpers that read from inputs or write to outputs (`bevy_gpu_compute_core::wgsl_helpers`) can only be used inside the main function. It is technically possible to pass in entire input arrays, configs, or output arrays to helper functions, but considering the performance implications, it is not recommended. Instead interact with your inputs and outputs in the main function and pass in only the necessary data to the helper functions."
-    )]
     fn test_config_get_outside_main() {
         let mut input: ItemMod = parse_quote! {
             mod test {
@@ -263,7 +263,8 @@ mod tests {
             TokenStream::new(),
         )];
 
-        transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        let errors = try_transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        assert_only_in_main_error(&errors);
     }
     #[test]
     fn test_config_get() {
@@ -274,7 +275,7 @@ mod tests {
                 }
             }
         };
-        let expected_output = "mod test { fn main () { let t = position ; } }";
+        let expected_output = "mod test { fn main () { let t = position_config ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("Position"),
@@ -292,4 +293,122 @@ mod tests {
             expected_output, result
         );
     }
+
+    #[test]
+    fn test_named_bindings_of_the_same_type() {
+        let mut input: ItemMod = parse_quote! {
+            mod test {
+                fn main() {
+                    let p = WgslVecInput::vec_val_named::<Particle>("prev", 2);
+                    let c = WgslVecInput::vec_val::<Particle>(2);
+                    WgslOutput::set::<Particle>(2, p);
+                }
+            }
+        };
+        let expected_output = "mod test { fn main () { let p = prev_input_array [2] ; let c = particle_input_array [2] ; particle_output_array [2] = p ; } }";
+
+        let custom_types = vec![
+            CustomType::new_named(
+                &format_ident!("Particle"),
+                Some(&format_ident!("prev")),
+                CustomTypeKind::InputArray,
+                TokenStream::new(),
+            ),
+            CustomType::new(
+                &format_ident!("Particle"),
+                CustomTypeKind::InputArray,
+                TokenStream::new(),
+            ),
+            CustomType::new(
+                &format_ident!("Particle"),
+                CustomTypeKind::OutputArray,
+                TokenStream::new(),
+            ),
+        ];
+
+        transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        let result = input.to_token_stream().to_string();
+        assert_eq!(
+            result, expected_output,
+            "Expected: {}\nGot: {}",
+            expected_output, result
+        );
+    }
+
+    #[test]
+    fn test_errors_are_collected_with_suggestions() {
+        let mut input: ItemMod = parse_quote! {
+            mod test {
+                fn main() {
+                    WgslOutput::set::<CollisionResult>(idx, val);
+                    WgslOutput::pushh::<CollisionResult>(val);
+                    let p = WgslVecInput::vec_val::<Positon>(0);
+                }
+            }
+        };
+
+        let custom_types = vec![
+            CustomType::new(
+                &format_ident!("CollisionResult"),
+                CustomTypeKind::OutputVec,
+                TokenStream::new(),
+            ),
+            CustomType::new(
+                &format_ident!("Position"),
+                CustomTypeKind::InputArray,
+                TokenStream::new(),
+            ),
+        ];
+
+        let errors = try_transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages.len(), 3, "{:?}", messages);
+        assert!(messages[0].contains("instead it is marked with #[wgsl_output_vec]"));
+        assert!(
+            messages[0].contains("Or did you mean `WgslOutput::push::<CollisionResult>(value)`?")
+        );
+        assert!(messages[1].contains("Did you mean `WgslOutput::push`?"));
+        assert!(messages[2].contains("Did you mean `Position`?"));
+    }
+
+    #[test]
+    fn test_method_of_another_helper_is_an_error() {
+        let mut input: ItemMod = parse_quote! {
+            mod test {
+                fn main() {
+                    let v = WgslOutput::get::<CollisionResult>();
+                    let c = WgslOutput::get::<Config>();
+                    let l = WgslOutput::vec_len::<Position>();
+                }
+            }
+        };
+
+        let custom_types = vec![
+            CustomType::new(
+                &format_ident!("CollisionResult"),
+                CustomTypeKind::OutputVec,
+                TokenStream::new(),
+            ),
+            CustomType::new(
+                &format_ident!("Config"),
+                CustomTypeKind::Uniform,
+                TokenStream::new(),
+            ),
+            CustomType::new(
+                &format_ident!("Position"),
+                CustomTypeKind::InputArray,
+                TokenStream::new(),
+            ),
+        ];
+
+        let errors = try_transform_wgsl_helper_methods(&custom_types, &mut input, false);
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages.len(), 3, "{:?}", messages);
+        assert!(messages[0].starts_with(
+            "`WgslOutput::get` is not a WGSL helper method, the methods of `WgslOutput` are: push, set, len, max_len."
+        ));
+        assert!(!messages[0].contains("Did you mean"));
+        assert!(messages[1].contains("Did you mean `WgslConfigInput::get::<Config>()`?"));
+        assert!(messages[2].contains("Did you mean `WgslVecInput::vec_len::<Position>()`?"));
+    }
 }
@@ -74,8 +74,7 @@ mod tests {
             }
         };
 
-        let expected_output =
-            "mod test { fn example () { collisionresult_output_array . push (value) ; } }";
+        let expected_output = "mod test { fn example () { CpuOutput :: push (& mut collision_result_output_array , value) ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("CollisionResult"),
@@ -103,8 +102,7 @@ mod tests {
                 }
             }
         };
-        let expected_output =
-            "mod test { fn example () { let x = collisionresult_output_array . len () as u32 ; } }";
+        let expected_output = "mod test { fn example () { let x = CpuOutput :: max_len (& collision_result_output_array) ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("CollisionResult"),
@@ -132,8 +130,7 @@ mod tests {
                 }
             }
         };
-        let expected_output =
-            "mod test { fn example () { let x = collisionresult_output_array . len () as u32 ; } }";
+        let expected_output = "mod test { fn example () { let x = CpuOutput :: len (& collision_result_output_array) ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("CollisionResult"),
@@ -161,8 +158,7 @@ mod tests {
                 }
             }
         };
-        let expected_output =
-            "mod test { fn example () { collisionresult_output_array [idx as usize] = val ; } }";
+        let expected_output = "mod test { fn example () { CpuOutput :: set (& mut collision_result_output_array , idx as usize , val) ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("CollisionResult"),
@@ -181,6 +177,39 @@ mod tests {
         );
     }
     #[test]
+    fn test_compound_indices() {
+        let mut input: ItemMod = parse_quote! {
+            mod test {
+                fn example() {
+                    let x = WgslVecInput::vec_val::<Radius>(i + 1);
+                    WgslOutput::set::<Radius>(i * 2, x);
+                }
+            }
+        };
+        let expected_output = "mod test { fn example () { let x = radius_input_array [(i + 1) as usize] ; CpuOutput :: set (& mut radius_output_array , (i * 2) as usize , x) ; } }";
+
+        let custom_types = vec![
+            CustomType::new(
+                &format_ident!("Radius"),
+                CustomTypeKind::InputArray,
+                TokenStream::new(),
+            ),
+            CustomType::new(
+                &format_ident!("Radius"),
+                CustomTypeKind::OutputArray,
+                TokenStream::new(),
+            ),
+        ];
+
+        transform_wgsl_helper_methods(&custom_types, &mut input, true);
+        let result = input.to_token_stream().to_string();
+        assert_eq!(
+            result, expected_output,
+            "Expected: {}\nGot: {}",
+            expected_output, result
+        );
+    }
+    #[test]
     fn test_config_get() {
         let mut input: ItemMod = parse_quote! {
             mod test {
@@ -189,7 +218,7 @@ mod tests {
                 }
             }
         };
-        let expected_output = "mod test { fn example () { let t = position ; } }";
+        let expected_output = "mod test { fn example () { let t = position_config ; } }";
 
         let custom_types = vec![CustomType::new(
             &format_ident!("Position"),
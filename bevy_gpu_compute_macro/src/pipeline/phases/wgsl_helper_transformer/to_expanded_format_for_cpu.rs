@@ -3,13 +3,15 @@ use proc_macro2::Span;
 use proc_macro2::TokenStream;
 use quote::ToTokens;
 use quote::quote;
+use syn::Expr;
 
 use super::helper_method::WgslHelperMethod;
 use super::to_expanded_format::ToExpandedFormatMethodKind;
 
 pub struct ToExpandedFormatForCpu {}
 impl ToExpandedFormatForCpu {
-    pub fn run(method: &WgslHelperMethod) -> TokenStream {
+    /// with `gpu_semantics`, out of range input reads and output writes behave like robust buffer access on the GPU, instead of panicking
+    pub fn run(method: &WgslHelperMethod, gpu_semantics: bool) -> TokenStream {
         match method.method_expander_kind {
             Some(ToExpandedFormatMethodKind::ConfigGet) => {
                 let name = method.t_def.name.uniform();
@@ -30,8 +32,15 @@ impl ToExpandedFormatForCpu {
                 } else {
                     abort!(Span::call_site(), "arg1 is None for input value method")
                 };
-                quote! {
-                    #name [ #index as usize ]
+                let index = cpu_index(index);
+                if gpu_semantics {
+                    quote! {
+                        robust_read(&#name, #index)
+                    }
+                } else {
+                    quote! {
+                        #name [ #index ]
+                    }
                 }
             }
             Some(ToExpandedFormatMethodKind::OutputPush) => {
@@ -42,19 +51,18 @@ impl ToExpandedFormatForCpu {
                 } else {
                     abort!(Span::call_site(), "arg1 is None for output push method")
                 };
-                quote! {#arr .push( #value )}
+                quote! {CpuOutput::push(&mut #arr, #value)}
             }
             Some(ToExpandedFormatMethodKind::OutputMaxLen) => {
-                // this may be a bit confusing, because the MaxLengths have no effect on the CPU side, but for now this I believe is the simplest way to handle things
                 let var_name = method.t_def.name.output_array();
                 quote! {
-                    #var_name .len() as u32
+                    CpuOutput::max_len(&#var_name)
                 }
             }
             Some(ToExpandedFormatMethodKind::OutputLen) => {
                 let var_name = method.t_def.name.output_array();
                 quote! {
-                    #var_name .len() as u32
+                    CpuOutput::len(&#var_name)
                 }
             }
             Some(ToExpandedFormatMethodKind::OutputSet) => {
@@ -69,11 +77,32 @@ impl ToExpandedFormatForCpu {
                 } else {
                     abort!(Span::call_site(), "arg2 is None for output set method")
                 };
-                quote! {
-                    #arr [ #index as usize ] = #value
+                let index = cpu_index(index);
+                if gpu_semantics {
+                    quote! {
+                        robust_set(&mut #arr, #index, #value)
+                    }
+                } else {
+                    quote! {
+                        CpuOutput::set(&mut #arr, #index, #value)
+                    }
                 }
             }
             None => panic!("method_expander_kind is None"),
         }
     }
 }
+
+/// Indices can be any `WgslIndex`, but rust only indexes with `usize`. Anything but a simple expression is parenthesized, so that in `i + 1 as usize` the cast does not only apply to the `1`.
+fn cpu_index(index: &Expr) -> TokenStream {
+    match index {
+        Expr::Path(_)
+        | Expr::Lit(_)
+        | Expr::Field(_)
+        | Expr::Call(_)
+        | Expr::MethodCall(_)
+        | Expr::Paren(_)
+        | Expr::Index(_) => quote! { #index as usize },
+        _ => quote! { (#index) as usize },
+    }
+}
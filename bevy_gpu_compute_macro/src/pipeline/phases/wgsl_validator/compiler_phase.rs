@@ -0,0 +1,25 @@
+use crate::pipeline::{
+    compilation_unit::CompilationUnit,
+    phases::{
+        compiler_phase::CompilerPhase,
+        final_structure_generator::types_for_rust_usage::define_types_for_use_in_rust_and_set_binding_numbers,
+    },
+};
+
+use super::validate::validate_generated_wgsl;
+
+/// Catches invalid WGSL at compile time, instead of when the shader module is created at runtime.
+/// Modules that import a `#[wgsl_shader_library]` are skipped, since the library definitions are only added at runtime.
+pub struct WgslValidator;
+
+impl CompilerPhase for WgslValidator {
+    fn execute(&self, input: &mut CompilationUnit) {
+        if !input.library_imports().is_empty() {
+            return;
+        }
+        // binding numbers are only assigned when generating the final structure
+        let mut wgsl_module = input.wgsl_module_user_portion().clone();
+        define_types_for_use_in_rust_and_set_binding_numbers(&mut wgsl_module);
+        validate_generated_wgsl(&wgsl_module, input.original_rust_module());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod compiler_phase;
+mod rust_spans;
+mod validate;
@@ -0,0 +1,63 @@
+use std::ops::Range;
+
+use bevy_gpu_compute_core::{
+    IterSpaceDimmension, wgsl::shader_module::complete_shader_module::WgslShaderModule,
+};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{ItemMod, spanned::Spanned};
+
+use crate::pipeline::module_items::find_item;
+
+/// Finds the rust item that generated the section of wgsl containing `range`, and within it the first identifier matching the wgsl at `range`, if there is one. Falls back to the module name if no item can be found.
+pub fn rust_span_for_wgsl_range(
+    wgsl_module: &WgslShaderModule,
+    iter_space_dimmensions: IterSpaceDimmension,
+    original_rust_module: &ItemMod,
+    wgsl: &str,
+    range: Range<usize>,
+) -> Span {
+    let error_text = wgsl[range.clone()].trim();
+    let wgsl_line = wgsl[..range.start].matches('\n').count() + 1;
+    let Some(item_name) = wgsl_module.section_name_at_line(iter_space_dimmensions, wgsl_line)
+    else {
+        // generated code like bindings, which mentions the user defined type it was generated for
+        return error_text
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .find_map(|ident| find_item(original_rust_module, ident))
+            .map_or(original_rust_module.ident.span(), |item| item.span());
+    };
+    let Some(item) = find_item(original_rust_module, item_name) else {
+        return original_rust_module.ident.span();
+    };
+    find_ident_span(item.to_token_stream(), error_text).unwrap_or(item.span())
+}
+
+fn find_ident_span(tokens: TokenStream, ident: &str) -> Option<Span> {
+    tokens.into_iter().find_map(|token| match token {
+        TokenTree::Ident(i) if i == ident => Some(i.span()),
+        TokenTree::Group(g) => find_ident_span(g.stream(), ident),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_find_ident_span() {
+        let module: ItemMod = parse_quote! {
+            mod m {
+                fn helper() -> u32 { return missing; }
+            }
+        };
+        assert!(find_item(&module, "helper").is_some());
+        assert!(find_item(&module, "other").is_none());
+        assert!(
+            find_ident_span(find_item(&module, "helper").to_token_stream(), "missing").is_some()
+        );
+    }
+}
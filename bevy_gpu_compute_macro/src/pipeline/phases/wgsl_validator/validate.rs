@@ -0,0 +1,85 @@
+use bevy_gpu_compute_core::{
+    IterSpaceDimmension,
+    wgsl::shader_module::{
+        complete_shader_module::WgslShaderModule, user_defined_portion::WgslShaderModuleUserPortion,
+    },
+};
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use proc_macro_error::abort;
+use proc_macro2::Span;
+
+use super::rust_spans::rust_span_for_wgsl_range;
+
+/// Parses and validates the complete shader for every iteration space dimmension, since the workgroup size changes with it.
+pub fn validate_generated_wgsl(
+    wgsl_module: &WgslShaderModuleUserPortion,
+    original_rust_module: &syn::ItemMod,
+) {
+    for dimmension in [
+        IterSpaceDimmension::OneD,
+        IterSpaceDimmension::TwoD,
+        IterSpaceDimmension::ThreeD,
+    ] {
+        let complete_module = WgslShaderModule::new(wgsl_module.clone());
+        let wgsl = complete_module.wgsl_code(dimmension);
+        // naga does not support f16 yet, so shaders that use it can only be checked at runtime
+        if wgsl
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .any(|ident| ident == "f16")
+        {
+            return;
+        }
+        if let Err((range, report)) = validate_wgsl(&wgsl) {
+            let span = range
+                .map(|r| {
+                    rust_span_for_wgsl_range(
+                        &complete_module,
+                        dimmension,
+                        original_rust_module,
+                        &wgsl,
+                        r,
+                    )
+                })
+                .unwrap_or(Span::call_site());
+            abort!(
+                span,
+                "The WGSL generated from this code is invalid. This is probably a bug in bevy_gpu_compute, or a rust feature that cannot be converted to WGSL.\n{}",
+                report
+            );
+        }
+    }
+}
+
+/// On failure returns the location of the error in the wgsl, if naga reported one, and the full report
+fn validate_wgsl(wgsl: &str) -> Result<(), (Option<std::ops::Range<usize>>, String)> {
+    let module = naga::front::wgsl::parse_str(wgsl).map_err(|e| {
+        (
+            e.labels().next().and_then(|(span, _)| span.to_range()),
+            e.emit_to_string_with_path(wgsl, "generated.wgsl"),
+        )
+    })?;
+    // the device features are not known at compile time, so anything that some device could run is accepted
+    Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(&module)
+        .map_err(|e| {
+            (
+                e.spans().next().and_then(|(span, _)| span.to_range()),
+                e.emit_to_string_with_path(wgsl, "generated.wgsl"),
+            )
+        })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_wgsl() {
+        assert!(validate_wgsl("fn f() -> u32 { return 1u; }").is_ok());
+        let source = "fn f() -> u32 { return missing; }";
+        let (range, report) = validate_wgsl(source).unwrap_err();
+        assert_eq!(&source[range.unwrap()], "missing");
+        assert!(report.contains("missing"));
+    }
+}
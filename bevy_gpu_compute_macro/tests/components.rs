@@ -1,20 +1,22 @@
-#![feature(f16)]
 #![allow(clippy::all)]
 use std::collections::HashMap;
 
 use bevy_gpu_compute_core::{
+    CpuSchedule, FloatTolerance, IterSpaceDimmension, IterationSpace, SplitMix64,
     TypeErasedArrayOutputData, TypesSpec,
     wgsl::{
         shader_custom_type_name::ShaderCustomTypeName,
-        shader_module::user_defined_portion::WgslShaderModuleUserPortion,
+        shader_module::{
+            complete_shader_module::WgslShaderModule,
+            user_defined_portion::WgslShaderModuleUserPortion,
+        },
         shader_sections::{
-            WgslConstAssignment, WgslFunction, WgslInputArray, WgslOutputArray,
-            WgslShaderModuleSectionCode, WgslType,
+            WgslFunction, WgslInputArray, WgslOutputArray, WgslShaderModuleSectionCode, WgslType,
         },
     },
     wgsl_helpers::WgslIterationPosition,
 };
-use bevy_gpu_compute_macro::wgsl_shader_module;
+use bevy_gpu_compute_macro::{wgsl_shader_library, wgsl_shader_module};
 use pretty_assertions::assert_eq;
 
 #[test]
@@ -35,7 +37,8 @@ fn test_simple_struct() {
     assert!(t2.uniforms.is_empty());
     assert!(t2.helper_functions.is_empty());
     assert!(t2.main_function.is_some());
-    assert!(t2.helper_types.len() == 1);
+    // unused types are left out of the wgsl
+    assert!(t2.helper_types.is_empty());
     assert_eq!(
         t2.main_function.unwrap().code.wgsl_code,
         "fn main(@builtin(global_invocation_id) iter_pos: vec3<u32>) {}"
@@ -108,7 +111,9 @@ fn test_type_alias() {
     pub mod test_module {
         use bevy_gpu_compute_core::wgsl_helpers::*;
         type MyType = i32;
-        fn main(iter_pos: WgslIterationPosition) {}
+        fn main(iter_pos: WgslIterationPosition) {
+            let v: MyType = 1;
+        }
     }
 
     let t2 = test_module::parsed();
@@ -129,7 +134,9 @@ fn test_consts() {
     pub mod test_module {
         use bevy_gpu_compute_core::wgsl_helpers::{WgslIterationPosition, *};
         const MY_CONST: i32 = 3;
-        fn main(iter_pos: WgslIterationPosition) {}
+        fn main(iter_pos: WgslIterationPosition) {
+            let v = MY_CONST;
+        }
     }
 
     let t2 = test_module::parsed();
@@ -186,16 +193,24 @@ fn test_helper_functions() {
     #[wgsl_shader_module]
     pub mod test_module {
         use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::wgsl_input_array;
+        #[wgsl_input_array]
+        type Position = [f32; 2];
         fn calculate_distance_squared(p1: [f32; 2], p2: [f32; 2]) -> f32 {
             let dx = p1[0] - p2[0];
             let dy = p1[1] - p2[1];
             dx * dx + dy * dy
         }
-        fn main(iter_pos: WgslIterationPosition) {}
+        fn main(iter_pos: WgslIterationPosition) {
+            let d = calculate_distance_squared(
+                WgslVecInput::vec_val::<Position>(0),
+                WgslVecInput::vec_val::<Position>(1),
+            );
+        }
     }
     let t2 = test_module::parsed();
     assert!(t2.output_arrays.is_empty());
-    assert!(t2.input_arrays.is_empty());
+    assert!(t2.input_arrays.len() == 1);
     assert!(t2.uniforms.is_empty());
     assert!(t2.helper_functions.len() == 1);
     assert!(t2.main_function.is_some());
@@ -236,7 +251,12 @@ fn test_simple_type_transforms() {
             x: f32,
             y: Vec3F32,
         }
-        fn main(iter_pos: WgslIterationPosition) {}
+        fn main(iter_pos: WgslIterationPosition) {
+            let t = TStruct {
+                x: 1.0,
+                y: Vec3F32::new(1.0, 2.0, 3.0),
+            };
+        }
     }
 
     let t2 = test_module::parsed();
@@ -256,22 +276,113 @@ fn test_simple_type_transforms() {
 fn test_doc_comments() {
     #[wgsl_shader_module]
     pub mod test_module {
+        //! Inner doc comments are allowed too
         use bevy_gpu_compute_core::wgsl_helpers::*;
-        use bevy_gpu_compute_macro::wgsl_config;
+        use bevy_gpu_compute_macro::{wgsl_config, wgsl_input_array};
+        /// Settings shared by every invocation, see `values[0]`
         #[wgsl_config]
         struct MyConfig {
+            /// multiplies every value
             f16_val: PodF16,
         }
-        fn main(iter_pos: WgslIterationPosition) {}
+        /// One value per invocation
+        #[wgsl_input_array]
+        type Value = f32;
+        /// Always two
+        pub const TWO: f32 = 2.0;
+        /** Doubles [`Value`]s */
+        #[inline]
+        #[allow(clippy::needless_return)]
+        fn double(v: f32) -> f32 {
+            #[allow(unused_variables)]
+            let unused = 1;
+            return v * TWO;
+        }
+        /// The entry point
+        fn main(iter_pos: WgslIterationPosition) {
+            let v = double(1.0);
+        }
     }
     let t2 = test_module::parsed();
     assert!(t2.output_arrays.is_empty());
-    assert!(t2.input_arrays.is_empty());
+    assert!(t2.input_arrays.len() == 1);
     assert!(t2.uniforms.len() == 1);
-    assert!(t2.helper_functions.is_empty());
+    assert!(t2.helper_functions.len() == 1);
     assert!(t2.main_function.is_some());
-    assert!(t2.static_consts.is_empty());
+    assert!(t2.static_consts.len() == 1);
     assert!(t2.helper_types.is_empty());
+    // doc comments are not emitted into the wgsl by default
+    let wgsl = WgslShaderModule::new(t2).wgsl_code(IterSpaceDimmension::OneD);
+    assert!(!wgsl.contains("//"));
+    assert!(!wgsl.contains("inline"));
+    assert!(wgsl.contains("fn double(v: f32) -> f32"));
+    // the builders still work, and the CPU module keeps the documented items
+    let _ = test_module::InputDataBuilder::new().set_value(vec![1.0]);
+    assert_eq!(test_module::TWO, 2.0);
+}
+
+#[test]
+fn test_emit_doc_comments() {
+    #[wgsl_shader_module(emit_doc_comments)]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::wgsl_output_array;
+        /// One result per invocation
+        #[wgsl_output_array]
+        struct Result {
+            v: f32,
+        }
+        /// Always two
+        const TWO: f32 = 2.0;
+        /// Doubles a value
+        ///
+        /// Works with any `f32`
+        fn double(v: f32) -> f32 {
+            return v * TWO;
+        }
+        fn main(iter_pos: WgslIterationPosition) {
+            let v = double(1.0);
+        }
+    }
+    let t2 = test_module::parsed();
+    assert!(
+        t2.helper_functions[0]
+            .code
+            .wgsl_code
+            .starts_with("// Doubles a value\n//\n// Works with any `f32`\nfn double")
+    );
+    assert!(
+        t2.static_consts[0]
+            .code
+            .wgsl_code
+            .starts_with("// Always two\nconst TWO")
+    );
+    assert!(
+        t2.output_arrays[0]
+            .item_type
+            .code
+            .wgsl_code
+            .starts_with("// One result per invocation\nstruct Result")
+    );
+    assert!(
+        !t2.main_function
+            .as_ref()
+            .unwrap()
+            .code
+            .wgsl_code
+            .contains("//")
+    );
+    let module = WgslShaderModule::new(t2);
+    assert!(
+        module
+            .wgsl_code(IterSpaceDimmension::OneD)
+            .contains("// Doubles a value\n//\n// Works with any `f32`\nfn double(v: f32) -> f32 {\n    return v * TWO;\n}\n")
+    );
+    assert!(
+        !module
+            .pretty_wgsl(IterSpaceDimmension::OneD, false)
+            .contains("//")
+    );
 }
 #[test]
 fn test_type_casting_and_implicit_returns() {
@@ -286,7 +397,9 @@ fn test_type_casting_and_implicit_returns() {
             if true { y + z } else { x }
         }
         #[allow(unused_variables)]
-        fn main(iter_pos: WgslIterationPosition) {}
+        fn main(iter_pos: WgslIterationPosition) {
+            let h = helper();
+        }
     }
     let t2 = test_module::parsed();
     assert!(t2.output_arrays.is_empty());
@@ -326,6 +439,155 @@ fn test_mutable_variables() {
     );
 }
 
+#[test]
+fn test_renamed_iter_pos() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        fn main(gid: WgslIterationPosition) {
+            let x = gid.x;
+        }
+    }
+    let t2 = test_module::parsed();
+    assert_eq!(
+        t2.main_function.unwrap().code.wgsl_code,
+        "fn main(@builtin(global_invocation_id) gid: vec3<u32>) { let x = gid.x; }"
+    );
+}
+
+#[test]
+fn test_destructured_iter_pos() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::wgsl_output_array;
+        #[wgsl_output_array]
+        type Sum = u32;
+        pub fn main(WgslIterationPosition { x, y: row, .. }: WgslIterationPosition) {
+            let iter_pos = x + row;
+            WgslOutput::set::<Sum>(x, iter_pos);
+        }
+    }
+    let t2 = test_module::parsed();
+    let main = t2.main_function.unwrap().code.wgsl_code;
+    // the generated parameter name does not collide with the user's variables
+    assert!(main.starts_with("fn main(@builtin(global_invocation_id) iter_pos_: vec3<u32>)"));
+    assert!(main.contains("let x = iter_pos_.x;"));
+    assert!(main.contains("let row = iter_pos_.y;"));
+    assert!(!main.contains(".z"));
+
+    let mut sums = vec![0; 3];
+    test_module::main(WgslIterationPosition { x: 2, y: 5, z: 0 }, &mut sums);
+    assert_eq!(sums, vec![0, 0, 7]);
+}
+
+#[test]
+fn test_barriers() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::wgsl_output_array;
+        #[wgsl_output_array]
+        type Sum = u32;
+        pub fn main(iter_pos: WgslIterationPosition) {
+            let in_range = iter_pos.x < WgslOutput::max_len::<Sum>();
+            if in_range {
+                WgslOutput::set::<Sum>(iter_pos.x, iter_pos.x);
+            }
+            storageBarrier();
+            if in_range {
+                WgslOutput::set::<Sum>(iter_pos.x, iter_pos.x + 1);
+            }
+        }
+    }
+    let t2 = test_module::parsed();
+    let main = t2.main_function.unwrap().code.wgsl_code;
+    assert!(main.contains("storageBarrier();"), "{}", main);
+
+    // called directly, a single invocation does not wait at barriers
+    let mut sums = vec![0; 2];
+    test_module::main(WgslIterationPosition { x: 1, y: 0, z: 0 }, &mut sums);
+    assert_eq!(sums, vec![0, 2]);
+}
+
+#[test]
+fn test_barriers_on_the_cpu() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_output_vec]
+        type Arrived = u32;
+        #[wgsl_output_array]
+        type Seen = u32;
+        fn wait_for_the_others() {
+            storageBarrier();
+        }
+        fn main(iter_pos: WgslIterationPosition) {
+            WgslOutput::push::<Arrived>(iter_pos.x);
+            wait_for_the_others();
+            // every invocation of the workgroup pushed before any gets here
+            let seen = WgslOutput::len::<Arrived>();
+            WgslOutput::set::<Seen>(iter_pos.x, seen);
+        }
+    }
+    let mut max_lengths = test_module::MaxOutputLengthsBuilder::new();
+    max_lengths.set_arrived(100).set_seen(100);
+    let max_lengths = max_lengths.finish();
+    let outputs = test_module::run_entry_points_on_cpu(
+        &["main".to_string()],
+        IterationSpace::new(100, 1, 1),
+        &test_module::ConfigInputDataBuilder::new().finish(),
+        &test_module::InputDataBuilder::new().finish(),
+        &max_lengths,
+        &CpuSchedule::default(),
+    );
+    let seen = test_module::OutputDataBuilder::from(outputs).seen.unwrap();
+    // workgroups of 64, the second one runs after the first finished
+    assert!(seen[..64].iter().all(|&s| s == 64), "{:?}", seen);
+    assert!(seen[64..].iter().all(|&s| s == 100), "{:?}", seen);
+}
+
+#[test]
+fn test_usize_indices() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::{wgsl_input_array, wgsl_output_array};
+        #[wgsl_input_array]
+        type Position = [f32; 2];
+        #[wgsl_output_array]
+        type Sum = f32;
+        const AXES: usize = 2;
+        pub fn main(iter_pos: WgslIterationPosition) {
+            let i = iter_pos.x as usize;
+            let p = WgslVecInput::vec_val::<Position>(i);
+            let mut sum = 0.0;
+            let mut axis: usize = 0;
+            while axis < AXES {
+                sum += p[axis];
+                axis += 1;
+            }
+            WgslOutput::set::<Sum>(i - 1, sum);
+        }
+    }
+    let t2 = test_module::parsed();
+    let main = t2.main_function.as_ref().unwrap().code.wgsl_code.clone();
+    assert!(main.contains("let i = u32(iter_pos.x);"), "{}", main);
+    assert!(main.contains("var axis : u32 = 0;"), "{}", main);
+    // naga cannot infer the type of the literal in `axis += 1`
+    assert!(main.contains("axis = axis + 1;"), "{}", main);
+    assert_eq!(t2.static_consts[0].code.wgsl_code, "const AXES : u32 = 2;");
+
+    let mut sums = vec![0.0; 2];
+    test_module::main(
+        WgslIterationPosition { x: 1, y: 0, z: 0 },
+        &[[1.0, 2.0], [3.0, 4.0]],
+        &mut sums,
+    );
+    assert_eq!(sums, vec![7.0, 0.0]);
+}
+
 #[test]
 fn test_input_arrays() {
     #[wgsl_shader_module]
@@ -383,6 +645,29 @@ fn test_max_output_lengths_helper() {
     );
 }
 
+#[test]
+fn test_acronym_names() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_input_array]
+        type HTTPData = u32;
+        #[wgsl_output_vec]
+        type HTTPData2 = u32;
+        fn main(iter_pos: WgslIterationPosition) {
+            let data = WgslVecInput::vec_val::<HTTPData>(iter_pos.x);
+            WgslOutput::push::<HTTPData2>(data);
+        }
+    }
+    let _ = test_module::InputDataBuilder::new().set_http_data(vec![1]);
+    let _ = test_module::MaxOutputLengthsBuilder::new().set_http_data2(1);
+    let t2 = test_module::parsed();
+    let main = &t2.main_function.unwrap().code.wgsl_code;
+    assert!(main.contains("http_data_input_array"));
+    assert!(main.contains("HTTP_DATA2_OUTPUT_ARRAY_LENGTH"));
+}
+
 #[test]
 
 fn test_configs_builder() {
@@ -391,7 +676,10 @@ fn test_configs_builder() {
         use bevy_gpu_compute_core::wgsl_helpers::*;
         use bevy_gpu_compute_macro::*;
         #[wgsl_config]
-        type MyPosition = [f32; 2];
+        struct MyPosition {
+            pub x: f32,
+            pub y: f32,
+        }
         #[wgsl_config]
         struct MyConfig {
             pub v1: f32,
@@ -401,7 +689,7 @@ fn test_configs_builder() {
     }
     #[allow(unused_variables)]
     let config = test_module::ConfigInputDataBuilder::new()
-        .set_my_position([3.0, 4.0])
+        .set_my_position(test_module::MyPosition { x: 3.0, y: 4.0 })
         .set_my_config(test_module::MyConfig { v1: 10.0, v2: 20.0 })
         .finish();
 }
@@ -446,7 +734,7 @@ fn test_output_vec() {
             .atomic_counter_name
             .as_ref()
             .unwrap(),
-        &"collisionresult_counter".to_string()
+        &"collision_result_counter".to_string()
     )
 }
 
@@ -510,16 +798,33 @@ fn test_entire_collision_shader() {
     }
     let t2 = collision_shader::parsed();
 
-    let user_portion = WgslShaderModuleUserPortion { static_consts: vec![WgslConstAssignment { code: WgslShaderModuleSectionCode {  wgsl_code: "const EXAMPLE_MODULE_CONST : u32 = 42;".to_string() } }], helper_types: vec![], uniforms: vec![WgslType { name: ShaderCustomTypeName::new("Uniforms"), code: WgslShaderModuleSectionCode { wgsl_code: "struct Uniforms { time : f32, resolution : vec2 < f32 > , }".to_string() } }], input_arrays: vec![WgslInputArray { item_type: WgslType { name: ShaderCustomTypeName::new("Position"), code: WgslShaderModuleSectionCode {  wgsl_code: "alias Position  = array < f32, 2 > ;".to_string() } } }, WgslInputArray { item_type: WgslType { name: ShaderCustomTypeName::new("Radius") , code: WgslShaderModuleSectionCode {  wgsl_code: "alias Radius  = f32;".to_string() } } }], output_arrays: vec![WgslOutputArray { item_type: WgslType { name: ShaderCustomTypeName::new("CollisionResult"), code: WgslShaderModuleSectionCode { wgsl_code: "struct CollisionResult { entity1 : u32, entity2 : u32, }".to_string() } }, atomic_counter_name: Some("collisionresult_counter".to_string()) }], helper_functions: vec![WgslFunction { name: "calculate_distance_squared".to_string(), code: WgslShaderModuleSectionCode {  wgsl_code: "fn calculate_distance_squared(p1 : array < f32, 2 > , p2 : array < f32, 2 >)\n-> f32\n{\n    let dx = p1 [0] - p2 [0]; let dy = p1 [1] - p2 [1]; return dx * dx + dy *\n    dy;\n}".to_string() } }], main_function: Some(WgslFunction { name: "main".to_owned(), code: WgslShaderModuleSectionCode {  wgsl_code: "fn main(@builtin(global_invocation_id) iter_pos: vec3<u32>)\n{\n    let current_entity = iter_pos.x; let other_entity = iter_pos.y; if\n    current_entity >= POSITION_INPUT_ARRAY_LENGTH || other_entity >=\n    POSITION_INPUT_ARRAY_LENGTH || current_entity == other_entity ||\n    current_entity >= other_entity { return; } let current_radius =\n    radius_input_array [current_entity]; let other_radius = radius_input_array\n    [other_entity]; if current_radius <= 0.0 || other_radius <= 0.0\n    { return; } let current_pos = position_input_array [current_entity]; let\n    other_pos = position_input_array [other_entity]; let dist_squared =\n    calculate_distance_squared(current_pos, other_pos); let radius_sum =\n    current_radius + other_radius; if dist_squared < radius_sum * radius_sum\n    {\n        {\n            let collisionresult_output_array_index =\n            atomicAdd(& collisionresult_counter, 1u); if\n            collisionresult_output_array_index <\n            COLLISIONRESULT_OUTPUT_ARRAY_LENGTH\n            {\n                collisionresult_output_array\n                [collisionresult_output_array_index] =
+    let user_portion = WgslShaderModuleUserPortion { static_consts: vec![], helper_types: vec![], uniforms: vec![WgslType { name: ShaderCustomTypeName::new("Uniforms"), code: WgslShaderModuleSectionCode { wgsl_code: "struct Uniforms { time : f32, resolution : vec2 < f32 > , }".to_string() } }], input_arrays: vec![WgslInputArray { item_type: WgslType { name: ShaderCustomTypeName::new("Position"), code: WgslShaderModuleSectionCode {  wgsl_code: "alias Position  = array < f32, 2 > ;".to_string() } } }, WgslInputArray { item_type: WgslType { name: ShaderCustomTypeName::new("Radius") , code: WgslShaderModuleSectionCode {  wgsl_code: "alias Radius  = f32;".to_string() } } }], output_arrays: vec![WgslOutputArray { item_type: WgslType { name: ShaderCustomTypeName::new("CollisionResult"), code: WgslShaderModuleSectionCode { wgsl_code: "struct CollisionResult { entity1 : u32, entity2 : u32, }".to_string() } }, atomic_counter_name: Some("collision_result_counter".to_string()) }], helper_functions: vec![WgslFunction { name: "calculate_distance_squared".to_string(), code: WgslShaderModuleSectionCode {  wgsl_code: "fn calculate_distance_squared(p1 : array < f32, 2 > , p2 : array < f32, 2 >)\n-> f32\n{\n    let dx = p1 [0] - p2 [0]; let dy = p1 [1] - p2 [1]; return dx * dx + dy *\n    dy;\n}".to_string() } }], main_function: Some(WgslFunction { name: "main".to_owned(), code: WgslShaderModuleSectionCode {  wgsl_code: "fn main(@builtin(global_invocation_id) iter_pos: vec3<u32>)\n{\n    let current_entity = iter_pos.x; let other_entity = iter_pos.y; if\n    current_entity >= POSITION_INPUT_ARRAY_LENGTH || other_entity >=\n    POSITION_INPUT_ARRAY_LENGTH || current_entity == other_entity ||\n    current_entity >= other_entity { return; } let current_radius =\n    radius_input_array [current_entity]; let other_radius = radius_input_array\n    [other_entity]; if current_radius <= 0.0 || other_radius <= 0.0\n    { return; } let current_pos = position_input_array [current_entity]; let\n    other_pos = position_input_array [other_entity]; let dist_squared =\n    calculate_distance_squared(current_pos, other_pos); let radius_sum =\n    current_radius + other_radius; if dist_squared < radius_sum * radius_sum\n    {\n        {\n            let collision_result_output_array_index =\n            atomicAdd(& collision_result_counter, 1u); if\n            collision_result_output_array_index <\n            COLLISION_RESULT_OUTPUT_ARRAY_LENGTH\n            {\n                collision_result_output_array\n                [collision_result_output_array_index] =
                 CollisionResult(current_entity, other_entity);\n            }\n        };\n    }\n}".to_owned() } }),
+        entry_functions: vec![],
         binding_numbers_by_variable_name: Some(HashMap::from([
-            ("uniforms".to_string(), 1),
+            ("uniforms_config".to_string(), 1),
             ("position_input_array".to_string(), 2),
             ("radius_input_array".to_string(), 3),
-            ("collisionresult_output_array".to_string(), 4),
-            ("collisionresult_counter".to_string(), 5),
-        ])), };
+            ("collision_result_output_array".to_string(), 4),
+            ("collision_result_counter".to_string(), 5),
+        ])),
+        source_map: t2.source_map.clone(),
+    };
     assert_eq!(t2, user_portion);
+    let main_map = t2.source_map.section("main").unwrap();
+    assert!(main_map.file.ends_with("components.rs"));
+    assert_eq!(
+        main_map.rust_lines.len(),
+        t2.main_function.unwrap().code.wgsl_code.lines().count()
+    );
+    assert!(
+        t2.source_map
+            .section("calculate_distance_squared")
+            .is_some()
+    );
+    assert!(t2.source_map.section("CollisionResult").is_some());
+    // the const is not used by the shader, so it is left out of the wgsl
+    assert!(t2.source_map.section("EXAMPLE_MODULE_CONST").is_none());
 }
 
 #[test]
@@ -720,8 +1025,8 @@ fn test_functions_exposed_for_rust() {
     example_shader_module::main(
         WgslIterationPosition { x: 0, y: 1, z: 1 },
         config,
-        input_positions,
-        input_radii,
+        &input_positions,
+        &input_radii,
         &mut output_collisions,
     );
     // results are in the mutated "output_collisions"...
@@ -733,3 +1038,547 @@ fn test_functions_exposed_for_rust() {
         }]
     );
 }
+
+#[test]
+fn test_run_on_cpu() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_config]
+        struct Config {
+            pub scale: f32,
+        }
+        #[wgsl_input_array]
+        type Radius = f32;
+        #[wgsl_output_array]
+        type Scaled = f32;
+        #[wgsl_output_vec]
+        #[derive(PartialEq)]
+        struct Overlap {
+            pub a: u32,
+            pub b: u32,
+        }
+        fn main(iter_pos: WgslIterationPosition) {
+            let a = iter_pos.x;
+            let b = iter_pos.y;
+            if a >= WgslVecInput::vec_len::<Radius>() || b >= WgslVecInput::vec_len::<Radius>() {
+                return;
+            }
+            if b == 0 {
+                WgslOutput::set::<Scaled>(
+                    a,
+                    WgslVecInput::vec_val::<Radius>(a) * WgslConfigInput::get::<Config>().scale,
+                );
+            }
+            if a < b {
+                WgslOutput::push::<Overlap>(Overlap { a: a, b: b });
+            }
+        }
+    }
+    let mut config = test_module::ConfigInputDataBuilder::new();
+    config.set_config(test_module::Config { scale: 2.0 });
+    let mut inputs = test_module::InputDataBuilder::new();
+    inputs.set_radius(vec![1.0, 2.0, 3.0]);
+    let mut max_lengths = test_module::MaxOutputLengthsBuilder::new();
+    max_lengths.set_scaled(4).set_overlap(2);
+
+    let results = test_module::run_on_cpu(
+        IterationSpace::new(3, 3, 1),
+        &config.finish(),
+        &inputs.finish(),
+        &max_lengths.finish(),
+    );
+    // output arrays have their max length, with the values that were not set zeroed
+    assert_eq!(results.scaled, Some(vec![2.0, 4.0, 6.0, 0.0]));
+    // 3 values were pushed, but only 2 fit
+    assert_eq!(
+        results.overlap,
+        Some(vec![
+            test_module::Overlap { a: 0, b: 1 },
+            test_module::Overlap { a: 0, b: 2 },
+        ])
+    );
+}
+
+#[test]
+fn test_random_inputs() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_config]
+        struct Config {
+            pub scale: f32,
+        }
+        #[wgsl_input_array]
+        struct Particle {
+            pub id: u32,
+            pub offset: i32,
+            pub position: Vec2F32,
+        }
+        #[wgsl_output_array]
+        type Scaled = f32;
+        fn main(iter_pos: WgslIterationPosition) {
+            let particle = WgslVecInput::vec_val::<Particle>(iter_pos.x);
+            WgslOutput::set::<Scaled>(
+                iter_pos.x,
+                particle.position.x * WgslConfigInput::get::<Config>().scale,
+            );
+        }
+    }
+    let mut rng = SplitMix64::new(1);
+    let (config, inputs) = test_module::random_inputs(&mut rng, 64);
+    let scale = config.get::<test_module::Config>("Config").unwrap().scale;
+    assert!(scale == 0.0 || scale.is_normal());
+    let particles: Vec<test_module::Particle> = inputs.get_values("Particle").unwrap();
+    assert_eq!(particles.len(), 64);
+    for particle in particles {
+        assert!(particle.id < 256);
+        assert!((-128..128).contains(&particle.offset));
+        assert!((-100.0..100.0).contains(&particle.position.y));
+    }
+}
+
+#[test]
+fn test_gpu_semantics() {
+    #[wgsl_shader_module(gpu_semantics)]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_input_array]
+        type Value = u32;
+        #[wgsl_output_array]
+        struct Result {
+            pub wrapped: u32,
+            pub divided: u32,
+            pub out_of_range: u32,
+            pub converted: i32,
+        }
+        fn main(iter_pos: WgslIterationPosition) {
+            let i = iter_pos.x;
+            let value = WgslVecInput::vec_val::<Value>(i);
+            let zero = value - value;
+            let big = value as f32 * 4294967296.0;
+            if i == 1 {
+                // clamped to the last index, like on the GPU, and then overwritten below
+                WgslOutput::set::<Result>(
+                    i + 10,
+                    Result {
+                        wrapped: 0,
+                        divided: 0,
+                        out_of_range: 0,
+                        converted: 0,
+                    },
+                );
+            }
+            WgslOutput::set::<Result>(
+                i,
+                Result {
+                    wrapped: value + 1,
+                    divided: value / zero,
+                    out_of_range: WgslVecInput::vec_val::<Value>(i + 10),
+                    converted: big as i32,
+                },
+            );
+        }
+    }
+    let mut inputs = test_module::InputDataBuilder::new();
+    inputs.set_value(vec![u32::MAX, 7]);
+    let mut max_lengths = test_module::MaxOutputLengthsBuilder::new();
+    max_lengths.set_result(2);
+    let results = test_module::run_on_cpu(
+        IterationSpace::new(2, 1, 1),
+        &test_module::ConfigInputDataBuilder::new().finish(),
+        &inputs.finish(),
+        &max_lengths.finish(),
+    );
+    let results = results.result.unwrap();
+    assert_eq!(results[0].wrapped, 0);
+    assert_eq!(results[1].wrapped, 8);
+    assert_eq!(results[1].divided, 7);
+    // the last input, since out of range indices are clamped
+    assert_eq!(results[1].out_of_range, 7);
+    assert_eq!(results[1].converted, 2147483520);
+}
+
+#[test]
+fn test_detect_data_races() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_input_array]
+        type Value = u32;
+        #[wgsl_output_array]
+        type Sum = u32;
+        #[wgsl_output_vec]
+        type Even = u32;
+        fn main(iter_pos: WgslIterationPosition) {
+            let value = WgslVecInput::vec_val::<Value>(iter_pos.x);
+            // every invocation sets index 0 of `Sum`, so which value ends up there depends on the order
+            WgslOutput::set::<Sum>(iter_pos.x / 4, value);
+            WgslOutput::set::<Sum>(iter_pos.x + 1, value);
+            if value % 2 == 0 {
+                WgslOutput::push::<Even>(value);
+            }
+        }
+    }
+    let mut inputs = test_module::InputDataBuilder::new();
+    inputs.set_value(vec![1, 2, 3, 4]);
+    let mut max_lengths = test_module::MaxOutputLengthsBuilder::new();
+    max_lengths.set_sum(5);
+    max_lengths.set_even(2);
+    let report = test_module::detect_data_races(
+        IterationSpace::new(4, 1, 1),
+        &test_module::ConfigInputDataBuilder::new().finish(),
+        &inputs.finish(),
+        &max_lengths.finish(),
+        8,
+    );
+    assert!(report.has_races());
+    assert_eq!(report.conflicting_writes.len(), 1);
+    assert_eq!(report.conflicting_writes[0].entry_point, "main");
+    assert_eq!(report.conflicting_writes[0].output, "Sum");
+    assert_eq!(report.conflicting_writes[0].index, 0);
+    assert_eq!(report.conflicting_writes[0].invocations.len(), 4);
+    // both even values fit, and the order they are pushed in is not compared
+    assert_eq!(report.differing_outputs, vec!["Sum".to_string()]);
+    let mut max_lengths = test_module::MaxOutputLengthsBuilder::new();
+    max_lengths.set_sum(5);
+    max_lengths.set_even(1);
+    let mut inputs = test_module::InputDataBuilder::new();
+    inputs.set_value(vec![1, 2, 3, 4]);
+    let report = test_module::detect_data_races(
+        IterationSpace::new(4, 1, 1),
+        &test_module::ConfigInputDataBuilder::new().finish(),
+        &inputs.finish(),
+        &max_lengths.finish(),
+        8,
+    );
+    // only one of the two even values fits, which one depends on the order
+    assert!(report.differing_outputs.contains(&"Even".to_string()));
+}
+
+#[test]
+fn test_detect_order_dependent_pushes() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_output_vec]
+        type Ticket = u32;
+        fn main(iter_pos: WgslIterationPosition) {
+            // the same values are pushed in every order, but which invocation gets which one is up to the order
+            let ticket = WgslOutput::len::<Ticket>();
+            WgslOutput::push::<Ticket>(ticket);
+        }
+    }
+    let mut max_lengths = test_module::MaxOutputLengthsBuilder::new();
+    max_lengths.set_ticket(4);
+    let report = test_module::detect_data_races(
+        IterationSpace::new(4, 1, 1),
+        &test_module::ConfigInputDataBuilder::new().finish(),
+        &test_module::InputDataBuilder::new().finish(),
+        &max_lengths.finish(),
+        8,
+    );
+    assert!(report.conflicting_writes.is_empty());
+    assert_eq!(report.differing_outputs, vec!["Ticket".to_string()]);
+}
+
+#[test]
+fn test_compare_outputs() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_output_array]
+        struct Hit {
+            pub normal: Vec2F32,
+            pub entity: u32,
+        }
+        #[wgsl_output_vec]
+        type Touched = u32;
+        fn main(iter_pos: WgslIterationPosition) {}
+    }
+    use bevy_gpu_compute_core::wgsl_helpers::Vec2F32;
+    use test_module::{Hit, OutputDataBuilder};
+    let hit = |x: f32, entity: u32| Hit {
+        normal: Vec2F32::new(x, 1.0),
+        entity,
+    };
+    let expected = OutputDataBuilder {
+        hit: Some(vec![hit(0.5, 1), hit(0.25, 2)]),
+        touched: Some(vec![1, 2, 3]),
+    };
+    let actual = OutputDataBuilder {
+        hit: Some(vec![hit(0.5000001, 1), hit(0.75, 3)]),
+        touched: Some(vec![3, 1, 2]),
+    };
+    let differences = test_module::compare_outputs(&expected, &actual, &FloatTolerance::default());
+    let paths: Vec<&str> = differences.iter().map(|d| d.path.as_str()).collect();
+    assert_eq!(paths, vec!["Hit[1].normal.x", "Hit[1].entity"]);
+}
+
+#[test]
+fn test_helper_structs_with_pointer_sized_integers() {
+    // helper types stay on the GPU, so unlike transfer types they may use usize and isize, and still get `CompareFields`
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        struct Range {
+            pub start: usize,
+            pub offset: isize,
+        }
+        #[wgsl_output_array]
+        type Start = u32;
+        fn main(iter_pos: WgslIterationPosition) {
+            let range = Range {
+                start: iter_pos.x as usize,
+                offset: 0,
+            };
+            WgslOutput::set::<Start>(range.start, range.start as u32);
+        }
+    }
+    use bevy_gpu_compute_core::CompareFields;
+    let range = |start: usize| test_module::Range { start, offset: -1 };
+    let mut differences = Vec::new();
+    range(1).compare_fields(
+        &range(2),
+        "Range",
+        &FloatTolerance::default(),
+        &mut differences,
+    );
+    assert_eq!(differences[0].path, "Range.start");
+}
+
+#[test]
+fn test_nested_structs_and_arrays_in_transfer_types() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_input_array]
+        struct Body {
+            pub shape: Shape,
+            pub verts: [Vec2F32; 4],
+        }
+        struct Shape {
+            pub radius: f32,
+            pub kind: u32,
+        }
+        #[wgsl_output_array]
+        type Grid = [[f32; 2]; 2];
+        fn main(iter_pos: WgslIterationPosition) {}
+    }
+    let t2 = test_module::parsed();
+    assert_eq!(
+        t2.input_arrays.first().unwrap().item_type.code.wgsl_code,
+        "struct Body { shape : Shape, verts : array < vec2 < f32 > , 4 > , }"
+    );
+    assert_eq!(
+        t2.output_arrays.first().unwrap().item_type.code.wgsl_code,
+        "alias Grid  = array < array < f32, 2 > , 2 > ;"
+    );
+    let wgsl = WgslShaderModule::new(t2).wgsl_code(IterSpaceDimmension::OneD);
+    assert!(wgsl.find("struct Shape").unwrap() < wgsl.find("struct Body").unwrap());
+
+    let input_data = test_module::InputDataBuilder::new()
+        .set_body(vec![test_module::Body {
+            shape: test_module::Shape {
+                radius: 1.0,
+                kind: 2,
+            },
+            verts: [bevy_gpu_compute_core::wgsl_helpers::Vec2F32::new(0.0, 1.0); 4],
+        }])
+        .finish();
+    assert_eq!(input_data.get_length("Body").unwrap(), 1);
+}
+
+#[test]
+fn test_named_bindings() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::*;
+        #[wgsl_input_array(name = "prev")]
+        #[wgsl_output_array(name = "next")]
+        struct Particle {
+            pub x: f32,
+            pub v: f32,
+        }
+        #[wgsl_input_array(name = "masses")]
+        #[wgsl_input_array(name = "charges")]
+        type Scalar = f32;
+        fn main(iter_pos: WgslIterationPosition) {
+            let p = WgslVecInput::vec_val_named::<Particle>("prev", iter_pos.x);
+            let m = WgslVecInput::vec_val_named::<Scalar>("masses", iter_pos.x);
+            let c = WgslVecInput::vec_val_named::<Scalar>("charges", iter_pos.x);
+            WgslOutput::set::<Particle>(
+                iter_pos.x,
+                Particle {
+                    x: p.x + p.v,
+                    v: p.v * m * c,
+                },
+            );
+        }
+    }
+    let t2 = test_module::parsed();
+    assert_eq!(t2.input_arrays.len(), 3);
+    assert_eq!(t2.output_arrays.len(), 1);
+    assert_eq!(
+        t2.input_arrays[0].item_type.name,
+        ShaderCustomTypeName::new_named("Particle", "prev")
+    );
+    let main = &t2.main_function.as_ref().unwrap().code.wgsl_code;
+    assert!(main.contains("let p = prev_input_array [iter_pos.x];"));
+    assert!(main.contains("charges_input_array [iter_pos.x];"));
+    assert!(main.contains("next_output_array"));
+    assert!(main.contains("[iter_pos.x] = Particle(p.x + p.v, p.v * m * c);"));
+    let input_data = test_module::InputDataBuilder::new()
+        .set_prev(vec![test_module::Particle { x: 1.0, v: 2.0 }])
+        .set_masses(vec![3.0])
+        .set_charges(vec![4.0, 5.0])
+        .finish();
+    assert_eq!(input_data.get_length("prev").unwrap(), 1);
+    assert_eq!(input_data.get_length("charges").unwrap(), 2);
+    let max_outputs = test_module::MaxOutputLengthsBuilder::new()
+        .set_next(1)
+        .finish();
+    assert_eq!(
+        max_outputs.get_by_name(&ShaderCustomTypeName::new_named("Particle", "next")),
+        1
+    );
+    let wgsl = WgslShaderModule::new(t2).wgsl_code(IterSpaceDimmension::OneD);
+    assert_eq!(wgsl.matches("struct Particle").count(), 1);
+    assert!(wgsl.contains("var<storage, read> prev_input_array: array<Particle>;"));
+    assert!(wgsl.contains("var<storage, read_write> next_output_array: array<Particle>;"));
+}
+
+#[wgsl_shader_library]
+mod test_hashing_library {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    const HASH_MULTIPLIER: u32 = 3;
+    struct Cell {
+        x: i32,
+        y: i32,
+    }
+    fn hash(v: u32) -> u32 {
+        return v * HASH_MULTIPLIER + 1;
+    }
+}
+
+#[wgsl_shader_library]
+mod test_grid_library {
+    use crate::test_hashing_library::*;
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    fn cell_hash(c: Cell) -> u32 {
+        return hash(c.x as u32) + hash(c.y as u32);
+    }
+}
+
+#[test]
+fn test_shader_library() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use crate::{test_grid_library::*, test_hashing_library::*};
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::{wgsl_input_array, wgsl_output_array};
+        #[wgsl_input_array]
+        struct Body {
+            pub cell: Cell,
+        }
+        #[wgsl_output_array]
+        type Hashed = u32;
+        pub fn main(iter_pos: WgslIterationPosition) {
+            let body = WgslVecInput::vec_val::<Body>(iter_pos.x);
+            WgslOutput::set::<Hashed>(iter_pos.x, cell_hash(body.cell));
+        }
+    }
+    let library = test_hashing_library::parsed_library();
+    assert!(library.main_function.is_none());
+    assert!(library.binding_numbers_by_variable_name.is_none());
+    assert_eq!(library.helper_functions.len(), 1);
+
+    let t2 = test_module::parsed();
+    // the definitions shared by both libraries are only included once
+    let function_names: Vec<&str> = t2
+        .helper_functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(function_names, vec!["cell_hash", "hash"]);
+    assert_eq!(t2.static_consts.len(), 1);
+    assert_eq!(t2.helper_types.len(), 1);
+    assert_eq!(t2.helper_types[0].name.name(), "Cell");
+    assert_eq!(t2.input_arrays.len(), 1);
+    let wgsl = WgslShaderModule::new(t2).wgsl_code(IterSpaceDimmension::OneD);
+    assert!(wgsl.find("struct Cell").unwrap() < wgsl.find("struct Body").unwrap());
+
+    // the library functions are also usable on the CPU
+    let mut hashed = vec![0; 2];
+    test_module::main(
+        WgslIterationPosition { x: 1, y: 0, z: 0 },
+        &[
+            test_module::Body {
+                cell: test_hashing_library::Cell { x: 0, y: 0 },
+            },
+            test_module::Body {
+                cell: test_hashing_library::Cell { x: 1, y: 2 },
+            },
+        ],
+        &mut hashed,
+    );
+    assert_eq!(hashed, vec![0, 4 + 7]);
+}
+
+#[test]
+fn test_multiple_entry_points() {
+    #[wgsl_shader_module]
+    pub mod test_module {
+        use bevy_gpu_compute_core::wgsl_helpers::*;
+        use bevy_gpu_compute_macro::{wgsl_entry, wgsl_input_array, wgsl_output_array};
+        #[wgsl_input_array]
+        type Value = u32;
+        #[wgsl_output_array]
+        type Total = u32;
+        #[wgsl_entry]
+        pub fn clear(iter_pos: WgslIterationPosition) {
+            WgslOutput::set::<Total>(iter_pos.x, 0u32);
+        }
+        /// adds each value to its slot
+        #[wgsl_entry]
+        pub fn accumulate(iter_pos: WgslIterationPosition) {
+            let v = WgslVecInput::vec_val::<Value>(iter_pos.x);
+            WgslOutput::set::<Total>(iter_pos.x, v + 1);
+        }
+    }
+    let t2 = test_module::parsed();
+    assert!(t2.main_function.is_none());
+    assert!(t2.helper_functions.is_empty());
+    assert_eq!(t2.entry_point_names(), vec!["clear", "accumulate"]);
+    let wgsl = WgslShaderModule::new(t2).wgsl_code(IterSpaceDimmension::OneD);
+    assert_eq!(
+        wgsl.matches("@compute @workgroup_size(64, 1, 1)").count(),
+        2
+    );
+    assert!(wgsl.contains("fn clear(@builtin(global_invocation_id) iter_pos: vec3<u32>)"));
+    assert!(wgsl.contains("fn accumulate(@builtin(global_invocation_id) iter_pos: vec3<u32>)"));
+
+    // each entry point is also a cpu function
+    let mut totals = vec![9; 2];
+    test_module::clear(
+        WgslIterationPosition { x: 0, y: 0, z: 0 },
+        &[4, 5],
+        &mut totals,
+    );
+    assert_eq!(totals, vec![0, 9]);
+    test_module::accumulate(
+        WgslIterationPosition { x: 1, y: 0, z: 0 },
+        &[4, 5],
+        &mut totals,
+    );
+    assert_eq!(totals, vec![0, 6]);
+}
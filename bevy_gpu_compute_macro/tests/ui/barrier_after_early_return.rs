@@ -0,0 +1,21 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::{wgsl_input_array, wgsl_output_array};
+    #[wgsl_input_array]
+    type Position = [f32; 2];
+    #[wgsl_output_array]
+    type Sum = f32;
+    fn main(iter_pos: WgslIterationPosition) {
+        if iter_pos.x >= WgslVecInput::vec_len::<Position>() {
+            return;
+        }
+        let p = WgslVecInput::vec_val::<Position>(iter_pos.x);
+        workgroupBarrier();
+        WgslOutput::set::<Sum>(iter_pos.x, p[0] + p[1]);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use bevy_gpu_compute_macro::{wgsl_shader_library, wgsl_shader_module};
+
+#[wgsl_shader_library]
+mod hashing {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    const SEED: u32 = 7;
+    fn hash(x: u32) -> u32 {
+        return x * SEED;
+    }
+}
+
+#[wgsl_shader_library]
+mod noise {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    const SEED: u32 = 8;
+    fn noise(x: u32) -> u32 {
+        return x + SEED;
+    }
+}
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::*;
+    use crate::{hashing::*, noise::*};
+    #[wgsl_output_array]
+    type Value = u32;
+    fn main(iter_pos: WgslIterationPosition) {
+        WgslOutput::set::<Value>(iter_pos.x, hash(noise(iter_pos.x)));
+    }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::wgsl_output_vec;
+    #[wgsl_output_vec]
+    type Hit = u32;
+    fn main(iter_pos: WgslIterationPosition) {
+        WgslOutput::pussh::<Hit>(iter_pos.x);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::{wgsl_input_array, wgsl_output_array};
+    #[wgsl_input_array]
+    type Radius = f32;
+    #[wgsl_output_array]
+    type Hit = u32;
+    fn main(iter_pos: WgslIterationPosition) {
+        let r = WgslVecInput::vec_vall::<Radius>(iter_pos.x);
+        WgslOutput::push::<Hit>(1);
+        let n = WgslOutput::len::<Hit>();
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::wgsl_input_array;
+    #[wgsl_input_array]
+    type Radius = f32;
+    const COUNT: u32 = WgslVecInput::vec_len::<Radius>();
+    fn main(iter_pos: WgslIterationPosition) {}
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::wgsl_input_array;
+    #[wgsl_input_array]
+    type Radius = f32;
+    fn radius_at(i: u32) -> f32 {
+        return WgslVecInput::vec_val::<Radius>(i);
+    }
+    fn main(iter_pos: WgslIterationPosition) {
+        let r = radius_at(iter_pos.x);
+    }
+}
+
+fn main() {}
@@ -8,7 +8,9 @@ mod my_mod {
         y: false,
         z: true,
     };
-    fn main(iter_pos: WgslIterationPosition) {}
+    fn main(iter_pos: WgslIterationPosition) {
+        let v = MY_CONST;
+    }
 }
 
 fn main() {}
@@ -0,0 +1,9 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    fn main(WgslIterationPosition { x, w, .. }: WgslIterationPosition) {}
+}
+
+fn main() {}
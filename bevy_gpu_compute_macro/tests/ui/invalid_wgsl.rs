@@ -0,0 +1,13 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::wgsl_config;
+    // arrays in uniforms need a 16 byte stride in WGSL
+    #[wgsl_config]
+    type Weights = [f32; 4];
+    fn main(iter_pos: WgslIterationPosition) {}
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    fn main(mut gid: WgslIterationPosition) {
+        gid.x += 1;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use bevy_gpu_compute_macro::wgsl_shader_library;
+
+#[wgsl_shader_library]
+mod my_lib {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::wgsl_input_array;
+    #[wgsl_input_array]
+    struct Position {
+        v: Vec2F32,
+    }
+}
+
+fn main() {}
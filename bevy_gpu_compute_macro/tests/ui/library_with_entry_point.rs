@@ -0,0 +1,11 @@
+use bevy_gpu_compute_macro::wgsl_shader_library;
+
+#[wgsl_shader_library]
+mod my_lib {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::wgsl_entry;
+    #[wgsl_entry]
+    fn clear(iter_pos: WgslIterationPosition) {}
+}
+
+fn main() {}
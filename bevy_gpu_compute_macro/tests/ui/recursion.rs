@@ -0,0 +1,23 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    fn is_even(n: u32) -> bool {
+        if n == 0 {
+            return true;
+        }
+        return is_odd(n - 1);
+    }
+    fn is_odd(n: u32) -> bool {
+        if n == 0 {
+            return false;
+        }
+        return is_even(n - 1);
+    }
+    fn main(iter_pos: WgslIterationPosition) {
+        let even = is_even(iter_pos.x);
+    }
+}
+
+fn main() {}
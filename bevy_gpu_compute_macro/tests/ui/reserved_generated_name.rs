@@ -0,0 +1,15 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::wgsl_output_vec;
+    #[wgsl_output_vec]
+    type HTTPData = u32;
+    fn main(iter_pos: WgslIterationPosition) {
+        let http_data_counter = 0;
+        WgslOutput::push::<HTTPData>(http_data_counter);
+    }
+}
+
+fn main() {}
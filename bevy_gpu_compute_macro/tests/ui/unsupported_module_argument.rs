@@ -0,0 +1,9 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module(emit_comments)]
+mod my_mod {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    fn main(iter_pos: WgslIterationPosition) {}
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    use bevy_gpu_compute_macro::wgsl_input_array;
+    #[wgsl_input_array]
+    struct Particle {
+        position: Vec2F32,
+        cell: usize,
+    }
+    fn main(iter_pos: WgslIterationPosition) {
+        let p = WgslVecInput::vec_val::<Particle>(iter_pos.x);
+    }
+}
+
+fn main() {}
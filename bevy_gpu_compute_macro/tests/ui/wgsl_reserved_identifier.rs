@@ -0,0 +1,15 @@
+use bevy_gpu_compute_macro::wgsl_shader_module;
+
+#[wgsl_shader_module]
+mod my_shader {
+    use bevy_gpu_compute_core::wgsl_helpers::*;
+    struct Ray {
+        origin: Vec3F32,
+        target: Vec3F32,
+    }
+    fn main(iter_pos: WgslIterationPosition) {
+        let filter = iter_pos.x;
+    }
+}
+
+fn main() {}